@@ -0,0 +1,398 @@
+//! Command-line tool for dumping and querying UFO fonts.
+//!
+//! All output is written to standard output as JSON, except for the default text format of
+//! `diff` and the SVG document of `svg`. Errors are written to standard error and
+//! exit with status 1.
+//!
+//! Requires the `cli` feature.
+
+use std::io::{self, BufWriter, Write};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use indexmap::IndexMap;
+use serde::Serialize;
+use serde_json::{Value, json};
+
+use ufo_gleaner::cmap::{Cmap, build_cmap};
+use ufo_gleaner::diff::diff_fonts;
+use ufo_gleaner::error::Result;
+use ufo_gleaner::font::Font;
+use ufo_gleaner::gleaner::Gleaner;
+use ufo_gleaner::glif::{GlifData, ParseOptions};
+use ufo_gleaner::provider::FileProvider;
+use ufo_gleaner::svg::{SvgLayout, SvgOptions};
+
+/// Dump and query the glyphs of a UFO font as JSON.
+#[derive(Parser)]
+#[command(name = "ufo-gleaner", version)]
+struct Cli {
+    /// Path to the UFO directory.
+    ufo: PathBuf,
+    /// Fill omitted attributes with their GLIF specification defaults.
+    #[arg(long, global = true)]
+    apply_defaults: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print all glyphs, keyed by name. Glyphs that fail to parse are `null`.
+    Dump {
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = Format::Json)]
+        format: Format,
+    },
+    /// Print a single glyph.
+    Glyph {
+        /// The glyph name.
+        name: String,
+    },
+    /// Print the Unicode mapping as hexadecimal code points to glyph names. Glyphs that fail to
+    /// parse are skipped.
+    Cmap,
+    /// Print glyph, contour, point and component counts, and the glyphs that fail to parse.
+    Stats,
+    /// Compare the font with another UFO, treating the other one as the new version.
+    Diff {
+        /// Path to the other UFO directory.
+        other: PathBuf,
+        /// Output format.
+        #[arg(long, value_enum, default_value_t = DiffFormat::Text)]
+        format: DiffFormat,
+    },
+    /// Print an SVG proof of the named glyphs, or of all readable glyphs, with components
+    /// decomposed.
+    Svg {
+        /// The glyph names.
+        names: Vec<String>,
+        /// How to arrange the glyphs.
+        #[arg(long, value_enum, default_value_t = Layout::Grid)]
+        layout: Layout,
+        /// Number of cells per row of the grid layout.
+        #[arg(long, default_value_t = 10)]
+        columns: usize,
+        /// Mark anchors.
+        #[arg(long)]
+        anchors: bool,
+        /// Draw guidelines.
+        #[arg(long)]
+        guidelines: bool,
+    },
+    /// Print the SVG path data of the named glyphs, keyed by name.
+    SvgPath {
+        /// The glyph names.
+        #[arg(required = true)]
+        names: Vec<String>,
+        /// Draw only the glyphs' own contours, leaving components out.
+        #[arg(long)]
+        no_decompose: bool,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Format {
+    /// A single JSON object.
+    Json,
+    /// One `{"name": ..., "glyph": ...}` object per line.
+    Jsonl,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum DiffFormat {
+    /// One line per added, removed or changed glyph, followed by its changes.
+    Text,
+    /// A JSON object with `added`, `removed` and `changed` keys.
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Layout {
+    /// One-em cells, a fixed number per row.
+    Grid,
+    /// A line of text, spaced by advance widths.
+    Line,
+}
+
+/// Counts reported by the `stats` command.
+#[derive(Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct Stats {
+    glyphs: usize,
+    contours: usize,
+    points: usize,
+    components: usize,
+    failed: usize,
+    failed_glyphs: Vec<String>,
+}
+
+impl Stats {
+    fn new(glyphs: &IndexMap<String, Option<GlifData>>) -> Self {
+        let mut stats = Self {
+            glyphs: glyphs.len(),
+            ..Default::default()
+        };
+        for (name, data) in glyphs {
+            let Some(data) = data else {
+                stats.failed += 1;
+                stats.failed_glyphs.push(name.clone());
+                continue;
+            };
+            if let Some(outline) = &data.outline {
+                stats.contours += outline.contours.len();
+                stats.points += outline
+                    .contours
+                    .iter()
+                    .map(|c| c.points.len())
+                    .sum::<usize>();
+                stats.components += outline.components.len();
+            }
+        }
+        stats
+    }
+}
+
+/// Converts a [`Cmap`] into a JSON object keyed by code points in GLIF `hex` notation.
+fn cmap_to_json(cmap: &Cmap) -> Value {
+    cmap.iter()
+        .map(|(unicode, names)| (format!("{unicode:04X}"), json!(names)))
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+fn run(cli: Cli, out: &mut impl Write) -> Result<()> {
+    let provider = FileProvider::new(cli.ufo);
+    let options = ParseOptions::default().with_apply_defaults(cli.apply_defaults);
+    match cli.command {
+        Command::Dump { format } => {
+            let glyphs = Gleaner::with_options(provider, options)?.glean()?;
+            match format {
+                Format::Json => serde_json::to_writer(&mut *out, &glyphs)?,
+                Format::Jsonl => {
+                    for (name, glyph) in &glyphs {
+                        serde_json::to_writer(&mut *out, &json!({"name": name, "glyph": glyph}))?;
+                        writeln!(out)?;
+                    }
+                    return Ok(());
+                }
+            }
+        }
+        Command::Glyph { name } => {
+            let font = Font::with_options(provider, options)?;
+            serde_json::to_writer(&mut *out, &font.try_glyph(&name)?.data()?)?;
+        }
+        Command::Cmap => {
+            let glyphs = Gleaner::with_options(provider, options)?.glean()?;
+            let cmap = build_cmap(glyphs.iter().filter_map(|(name, data)| {
+                data.as_ref()
+                    .map(|data| (name.as_str(), data.unicodes.as_slice()))
+            }));
+            serde_json::to_writer(&mut *out, &cmap_to_json(&cmap))?;
+        }
+        Command::Stats => {
+            let glyphs = Gleaner::with_options(provider, options)?.glean()?;
+            serde_json::to_writer(&mut *out, &Stats::new(&glyphs))?;
+        }
+        Command::Diff { other, format } => {
+            let old = Font::with_options(provider, options)?;
+            let new = Font::with_options(FileProvider::new(other), options)?;
+            let diff = diff_fonts(&old, &new);
+            match format {
+                DiffFormat::Text => {
+                    write!(out, "{diff}")?;
+                    return Ok(());
+                }
+                DiffFormat::Json => serde_json::to_writer(&mut *out, &diff)?,
+            }
+        }
+        Command::Svg {
+            names,
+            layout,
+            columns,
+            anchors,
+            guidelines,
+        } => {
+            let font = Font::with_options(provider, options)?;
+            let layout = match layout {
+                Layout::Grid => SvgLayout::Grid { columns },
+                Layout::Line => SvgLayout::Line,
+            };
+            let options = SvgOptions::default()
+                .with_layout(layout)
+                .with_anchors(anchors)
+                .with_guidelines(guidelines);
+            let svg = if names.is_empty() {
+                font.to_svg_document_all(options)?
+            } else {
+                font.to_svg_document(names, options)?
+            };
+            write!(out, "{svg}")?;
+            return Ok(());
+        }
+        Command::SvgPath {
+            names,
+            no_decompose,
+        } => {
+            let font = Font::with_options(provider, options)?;
+            let paths = names
+                .into_iter()
+                .map(|name| {
+                    let path = font.try_glyph(&name)?.to_svg_path(!no_decompose)?;
+                    Ok((name, path))
+                })
+                .collect::<Result<IndexMap<_, _>>>()?;
+            serde_json::to_writer(&mut *out, &paths)?;
+        }
+    }
+    writeln!(out)?;
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let mut out = BufWriter::new(io::stdout().lock());
+    match run(cli, &mut out).and_then(|()| Ok(out.flush()?)) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("ufo-gleaner: {err}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    fn ufo() -> tempfile::TempDir {
+        let dir = tempdir().unwrap();
+        let glyphs = dir.path().join("glyphs");
+        fs::create_dir(&glyphs).unwrap();
+        fs::write(
+            glyphs.join("contents.plist"),
+            br#"<plist version="1.0"><dict>
+                <key>A</key><string>A_.glif</string>
+                <key>Aacute</key><string>A_acute.glif</string>
+                <key>broken</key><string>broken.glif</string>
+            </dict></plist>"#,
+        )
+        .unwrap();
+        fs::write(
+            glyphs.join("A_.glif"),
+            br#"<glyph name="A" format="2"><unicode hex="0041"/><outline>
+                <contour><point x="0" y="0" type="line"/><point x="10" y="0" type="line"/></contour>
+            </outline></glyph>"#,
+        )
+        .unwrap();
+        fs::write(
+            glyphs.join("A_acute.glif"),
+            br#"<glyph name="Aacute" format="2"><unicode hex="00C1"/><outline>
+                <component base="A"/><component base="acute"/>
+            </outline></glyph>"#,
+        )
+        .unwrap();
+        fs::write(glyphs.join("broken.glif"), b"<glyph").unwrap();
+        dir
+    }
+
+    fn output(args: &[&str]) -> Result<String> {
+        let mut out = Vec::new();
+        run(Cli::parse_from(args), &mut out)?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn test_commands() {
+        let dir = ufo();
+        let path = dir.path().to_str().unwrap();
+
+        let stats: Value =
+            serde_json::from_str(&output(&["ufo-gleaner", path, "stats"]).unwrap()).unwrap();
+        assert_eq!(
+            stats,
+            json!({
+                "glyphs": 3,
+                "contours": 1,
+                "points": 2,
+                "components": 2,
+                "failed": 1,
+                "failedGlyphs": ["broken"]
+            })
+        );
+
+        let cmap = output(&["ufo-gleaner", path, "cmap"]).unwrap();
+        assert_eq!(cmap, "{\"0041\":[\"A\"],\"00C1\":[\"Aacute\"]}\n");
+
+        let glyph: Value =
+            serde_json::from_str(&output(&["ufo-gleaner", path, "glyph", "A"]).unwrap()).unwrap();
+        assert_eq!(glyph["name"], "A");
+        assert!(output(&["ufo-gleaner", path, "glyph", "missing"]).is_err());
+
+        let lines = output(&["ufo-gleaner", path, "dump", "--format", "jsonl"]).unwrap();
+        let lines = lines.lines().collect::<Vec<_>>();
+        assert_eq!(lines.len(), 3);
+        let broken: Value = serde_json::from_str(lines[2]).unwrap();
+        assert_eq!(broken, json!({"name": "broken", "glyph": null}));
+    }
+
+    #[test]
+    fn test_svg() {
+        let dir = ufo();
+        let path = dir.path().to_str().unwrap();
+
+        let svg = output(&[
+            "ufo-gleaner",
+            path,
+            "svg",
+            "A",
+            "Aacute",
+            "--layout",
+            "line",
+        ])
+        .unwrap();
+        assert!(svg.starts_with("<svg "));
+        assert_eq!(svg.matches(r#"d="M0 0L10 0Z""#).count(), 2);
+        let svg = output(&["ufo-gleaner", path, "svg"]).unwrap();
+        assert_eq!(svg.matches("<title>").count(), 2);
+        assert!(!svg.contains("<title>broken</title>"));
+        assert!(output(&["ufo-gleaner", path, "svg", "broken"]).is_err());
+
+        let paths = output(&["ufo-gleaner", path, "svg-path", "Aacute", "--no-decompose"]).unwrap();
+        assert_eq!(paths, "{\"Aacute\":\"\"}\n");
+        let paths = output(&["ufo-gleaner", path, "svg-path", "Aacute"]).unwrap();
+        assert_eq!(paths, "{\"Aacute\":\"M0 0L10 0Z\"}\n");
+    }
+
+    #[test]
+    fn test_diff() {
+        let (old, new) = (ufo(), ufo());
+        for dir in [&old, &new] {
+            fs::write(
+                dir.path().join("glyphs/broken.glif"),
+                br#"<glyph name="broken" format="2"/>"#,
+            )
+            .unwrap();
+        }
+        fs::write(
+            new.path().join("glyphs/A_.glif"),
+            br#"<glyph name="A" format="2"><unicode hex="0041"/><advance width="10"/></glyph>"#,
+        )
+        .unwrap();
+        let (old, new) = (old.path().to_str().unwrap(), new.path().to_str().unwrap());
+        assert_eq!(
+            output(&["ufo-gleaner", old, "diff", new]).unwrap(),
+            "~ A\n    advance width: 0 -> 10\n    contour 0 removed (2 points)\n"
+        );
+        assert!(
+            output(&["ufo-gleaner", old, "diff", old])
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
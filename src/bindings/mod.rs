@@ -4,18 +4,33 @@ pub mod py_data;
 pub mod py_error;
 pub mod py_font;
 pub mod py_gleaner;
+pub mod py_lint;
+pub mod py_objects;
 pub mod py_provider;
+pub mod py_transform;
 
 pub use py_data::*;
 pub use py_error::*;
 pub use py_font::*;
 pub use py_gleaner::*;
+pub use py_lint::*;
+pub use py_objects::*;
 pub use py_provider::*;
+pub use py_transform::*;
 
 pub fn register(_py: Python<'_>, m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_class::<py_gleaner::PyGleaner>()?;
     m.add_class::<py_provider::PyFileProvider>()?;
     m.add_class::<py_font::PyFont>()?;
     m.add_class::<py_font::PyGlyph>()?;
+    m.add_class::<py_objects::PyAnchor>()?;
+    m.add_class::<py_objects::PyGuideline>()?;
+    m.add_class::<py_objects::PyContour>()?;
+    m.add_class::<py_objects::PyPoint>()?;
+    m.add_class::<py_objects::PyComponent>()?;
+    m.add_class::<py_transform::PyTransform>()?;
+    m.add_class::<py_lint::PyLinter>()?;
+    m.add("MAX_RASTER_SIZE", crate::raster::MAX_SIZE)?;
+    m.add("MAX_RASTER_PIXELS", crate::raster::MAX_PIXELS)?;
     Ok(())
 }
@@ -1,6 +1,6 @@
-use pyo3::exceptions::PyRuntimeError;
+use pyo3::exceptions::{PyRuntimeError, PyTypeError};
 use pyo3::prelude::*;
-use pyo3::types::{PyBool, PyDict, PyFloat, PyInt, PyList, PyString};
+use pyo3::types::{PyBool, PyBytes, PyDict, PyFloat, PyInt, PyList, PyString, PyTuple};
 use serde::Serialize;
 use serde_json::Value;
 
@@ -64,9 +64,42 @@ pub fn json_to_pydict<'py>(py: Python<'py>, val: &Value) -> PyResult<PyObject> {
     }
 }
 
+/// Converts a Python object made of dicts, lists, tuples, strings, bytes, numbers and booleans
+/// into a [`plist::Value`].
+pub fn pyobject_to_plist(obj: &Bound<'_, PyAny>) -> PyResult<plist::Value> {
+    if let Ok(b) = obj.downcast::<PyBool>() {
+        Ok(plist::Value::Boolean(b.is_true()))
+    } else if let Ok(i) = obj.downcast::<PyInt>() {
+        Ok(plist::Value::Integer(i.extract::<i64>()?.into()))
+    } else if let Ok(f) = obj.downcast::<PyFloat>() {
+        Ok(plist::Value::Real(f.value()))
+    } else if let Ok(s) = obj.downcast::<PyString>() {
+        Ok(plist::Value::String(s.to_str()?.to_owned()))
+    } else if let Ok(b) = obj.downcast::<PyBytes>() {
+        Ok(plist::Value::Data(b.as_bytes().to_vec()))
+    } else if let Ok(dict) = obj.downcast::<PyDict>() {
+        let mut out = plist::Dictionary::new();
+        for (k, v) in dict.iter() {
+            out.insert(k.extract::<String>()?, pyobject_to_plist(&v)?);
+        }
+        Ok(plist::Value::Dictionary(out))
+    } else if obj.is_instance_of::<PyList>() || obj.is_instance_of::<PyTuple>() {
+        let items = obj
+            .try_iter()?
+            .map(|item| pyobject_to_plist(&item?))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok(plist::Value::Array(items))
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "cannot convert {} to a property list value",
+            obj.get_type().name()?
+        )))
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::glif::Advance;
+    use crate::glif::{Advance, GlifFormatVersion};
 
     use super::*;
     use pyo3::types::{PyDict, PyList};
@@ -240,8 +273,7 @@ mod tests {
         Python::with_gil(|py| {
             let glif_data = GlifData {
                 name: "notehead".into(),
-                format: "1".into(),
-                format_minor: Some("1".into()),
+                format: GlifFormatVersion::new(1, 0),
                 advance: Some(Advance {
                     width: Some(100.0),
                     height: Some(120.0),
@@ -253,6 +285,7 @@ mod tests {
                 guidelines: vec![],
                 outline: None,
                 lib: None,
+                lib_source: None,
             };
 
             let py_glif = PyGlifData { inner: glif_data };
@@ -1,6 +1,7 @@
 use pyo3::PyErr;
 use pyo3::exceptions::{
-    PyAttributeError, PyFileNotFoundError, PyOSError, PyRuntimeError, PySyntaxError, PyValueError,
+    PyAttributeError, PyFileNotFoundError, PyKeyError, PyOSError, PyRuntimeError, PySyntaxError,
+    PyValueError,
 };
 
 use crate::error::{Error, ErrorKind};
@@ -32,6 +33,10 @@ impl From<Error> for PyErr {
             ErrorKind::Parse => PyValueError::new_err(err.to_string()),
             ErrorKind::FileNotFound => PyFileNotFoundError::new_err(err.to_string()),
             ErrorKind::MissingAttribute(attr) => PyAttributeError::new_err(attr.clone()),
+            ErrorKind::GlyphNotFound(name) => PyKeyError::new_err(name.clone()),
+            ErrorKind::UnsupportedFormat(_) => PyValueError::new_err(err.to_string()),
+            ErrorKind::InvalidName(_) => PyValueError::new_err(err.to_string()),
+            ErrorKind::InvalidValue(_) => PyValueError::new_err(err.to_string()),
             ErrorKind::Other(_) => PyRuntimeError::new_err(err.to_string()),
         }
     }
@@ -1,11 +1,26 @@
+use std::collections::{BTreeMap, HashMap};
 use std::rc::Rc;
 
+use pyo3::exceptions::{PyIndexError, PyValueError};
 use pyo3::prelude::*;
+use pyo3::types::{PyBytes, PyDict};
 
 use crate::bindings::{PyFileProvider, PyProvider};
+use crate::cache::CachePolicy;
 use crate::font::{Font, Glyph, Iter};
+use crate::glif::{LibMode, ParseOptions};
 
-use crate::bindings::PyGlifData;
+use crate::bindings::{
+    PyAnchor, PyComponent, PyContour, PyGlifData, PyGuideline, PyTransform, pyobject_to_plist,
+};
+use crate::color::Color;
+use crate::constants::ufo::glif_default;
+use crate::diff::{diff_fonts, diff_glyphs};
+use crate::glif::{Advance, Anchor, Component, Contour, Guideline};
+use crate::hash::HashOptions;
+use crate::raster::{RasterFrame, RasterOptions};
+use crate::svg::{SvgLayout, SvgOptions};
+use crate::transform::Transform;
 
 /// Provide a lazy iterator over `Glyph` objects
 #[pyclass(unsendable)]
@@ -42,43 +57,64 @@ pub struct PyFont {
 #[pymethods]
 impl PyFont {
     /// Create a new `Font` by parsing `contents.plist` via the given provider.
+    ///
+    /// If `apply_defaults` is `True`, omitted GLIF attributes are filled with their
+    /// specification defaults when glyphs are parsed. `lib` controls when glyph libs are parsed:
+    /// `"lazy"` (on first access to `Glyph.lib`), `"eager"` or `"skip"`.
+    ///
+    /// By default every parsed glyph stays in memory. `cache_size` keeps at most that many parsed
+    /// glyphs, evicting the least recently used ones, and `0` disables caching. `cache_points`
+    /// limits the cache by the total number of points instead. Edited glyphs are never evicted.
     #[new]
-    pub fn new(py: Python<'_>, provider: Py<PyAny>) -> PyResult<Self> {
+    #[pyo3(signature = (
+        provider, apply_defaults = false, lib = "lazy", cache_size = None, cache_points = None
+    ))]
+    pub fn new(
+        py: Python<'_>,
+        provider: Py<PyAny>,
+        apply_defaults: bool,
+        lib: &str,
+        cache_size: Option<usize>,
+        cache_points: Option<usize>,
+    ) -> PyResult<Self> {
+        let options = ParseOptions::default()
+            .with_apply_defaults(apply_defaults)
+            .with_lib_mode(lib.parse::<LibMode>()?);
+        let policy = match (cache_size, cache_points) {
+            (None, None) => CachePolicy::Unbounded,
+            (Some(0), None) => CachePolicy::None,
+            (Some(size), None) => CachePolicy::Glyphs(size),
+            (None, Some(points)) => CachePolicy::Points(points),
+            (Some(_), Some(_)) => {
+                return Err(PyValueError::new_err(
+                    "cache_size and cache_points cannot both be set",
+                ));
+            }
+        };
         // Try to downcast to PyFileProvider.
         // Return if Ok. If not, assume it's a custom PyProvider implementation.
-        match provider.extract::<PyRef<PyFileProvider>>(py) {
-            Ok(file_provider) => {
-                let font = Font::new(file_provider.inner.clone())?;
-                Ok(Self { inner: font })
-            }
+        let font = match provider.extract::<PyRef<PyFileProvider>>(py) {
+            Ok(file_provider) => Font::with_options(file_provider.inner.clone(), options)?,
             Err(_) => {
                 let provider = PyProvider::new(py, provider)?;
-                let font = Font::new(provider)?;
-                Ok(Self { inner: font })
+                Font::with_options(provider, options)?
             }
-        }
+        };
+        font.set_cache_policy(policy);
+        Ok(Self { inner: font })
     }
 
     /// Return the `Glyph` object mapped to the given name.
-    pub fn __getitem__(&self, py: Python<'_>, name: &str) -> PyResult<Option<Py<PyGlyph>>> {
-        // Ensure the glyph map is initialized.
-        Ok(self
-            .inner
-            .glyph(name)
-            .map(|inner_glyph| {
-                Py::new(
-                    py,
-                    PyGlyph {
-                        inner: Rc::clone(&inner_glyph),
-                    },
-                )
-            })
-            .transpose()?)
+    ///
+    /// Raises `KeyError` if the name is not listed in `contents.plist`.
+    pub fn __getitem__(&self, py: Python<'_>, name: &str) -> PyResult<Py<PyGlyph>> {
+        let inner = self.inner.try_glyph(name)?;
+        Py::new(py, PyGlyph { inner })
     }
 
     /// Return `True` if the font contains the given name.
-    pub fn __contains__(&self, name: &str) -> PyResult<bool> {
-        Ok(self.inner.glyph(name).is_some())
+    pub fn __contains__(&self, name: &str) -> bool {
+        self.inner.contains(name)
     }
 
     /// Return an iterator over the glyphs in the font.
@@ -87,19 +123,242 @@ impl PyFont {
         Py::new(py, PyIter { inner: iter })
     }
 
-    /// Optional: length
+    /// Return the number of glyphs listed in `contents.plist`.
     fn __len__(&self) -> usize {
-        self.inner.glyphs().len()
+        self.inner.len()
+    }
+
+    /// Return the `Glyph` object mapped to the given name, or `default` if it does not exist.
+    #[pyo3(signature = (name, default = None))]
+    pub fn get(
+        &self,
+        py: Python<'_>,
+        name: &str,
+        default: Option<PyObject>,
+    ) -> PyResult<Option<PyObject>> {
+        match self.inner.glyph(name) {
+            Some(inner) => Ok(Some(Py::new(py, PyGlyph { inner })?.into_any())),
+            None => Ok(default),
+        }
+    }
+
+    /// Get the font's `lib.plist` as a dict.
+    #[getter]
+    pub fn lib(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &*self.inner.lib()?)
+    }
+
+    /// Get the `public.postscriptNames` mapping of glyph names to production names.
+    #[getter]
+    pub fn postscript_names(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.lib()?.postscript_names()?)
+    }
+
+    /// Get the glyph names listed in `public.skipExportGlyphs`.
+    #[getter]
+    pub fn skip_export_glyphs(&self) -> PyResult<Vec<String>> {
+        Ok(self.inner.lib()?.skip_export_glyphs()?)
+    }
+
+    /// Get the `public.openTypeCategories` mapping of glyph names to category names.
+    #[getter]
+    pub fn opentype_categories(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.lib()?.opentype_categories()?)
+    }
+
+    /// Get the `public.unicodeVariationSequences` mapping of variation selectors to dicts of
+    /// base code points and glyph names.
+    #[getter]
+    pub fn unicode_variation_sequences(&self) -> PyResult<BTreeMap<u32, BTreeMap<u32, String>>> {
+        Ok(self.inner.lib()?.unicode_variation_sequences()?)
+    }
+
+    /// Get the `public.objectLibs` mapping of object identifiers to lib dicts.
+    #[getter]
+    pub fn object_libs(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.lib()?.object_libs()?)
+    }
+
+    /// Get the default layer's color from `layerinfo.plist` as an `(r, g, b, a)` tuple.
+    ///
+    /// An invalid color returns `None` and is reported by the `invalid-color` lint rule.
+    #[getter]
+    pub fn layer_color(&self) -> PyResult<Option<(f64, f64, f64, f64)>> {
+        Ok(self.inner.layer_info()?.color.map(|c| c.to_tuple()))
+    }
+
+    /// Get the names of all glyphs in the font, ordered by `public.glyphOrder` with unlisted
+    /// glyphs appended in sorted order.
+    #[getter]
+    pub fn glyph_order(&self) -> Vec<String> {
+        self.inner.glyph_order()
+    }
+
+    /// Check the file names in `contents.plist` against the UFO 3 glyph file name algorithm.
+    ///
+    /// Returns a list of dicts with a `kind` key: `"nonconforming"` issues carry `glyph`,
+    /// `fileName` and `expected`; `"clash"` issues carry `fileName` and `glyphs`.
+    pub fn check_file_names(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.check_file_names())
+    }
+
+    /// Cross-check `contents.plist` against the files in the `glyphs` directory.
+    ///
+    /// Returns a list of dicts with a `kind` key: `"orphanFile"`, `"missingFile"`,
+    /// `"nameMismatch"`, `"unreadable"` or `"caseClash"`. Custom providers must implement
+    /// `list_dir(path: str) -> list[str]`.
+    pub fn check_consistency(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.check_consistency()?)
+    }
+
+    /// Check the components of every glyph.
+    ///
+    /// Returns a list of dicts with `kind`, `glyph` and `index` keys: `"missingBase"` issues
+    /// carry the missing `base`, `"noBase"` issues mark components without a `base` attribute,
+    /// and `"degenerateTransform"` issues mark components whose transform has a zero
+    /// determinant. Glyphs that cannot be parsed are reported as `"unreadable"` issues with
+    /// `glyph` and `error` keys.
+    pub fn check_components(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.check_components()?)
+    }
+
+    /// Group the glyphs with identical outlines, as compared by `Glyph.outline_hash`.
+    ///
+    /// Returns a list of lists of two or more glyph names. Glyphs without contours and glyphs
+    /// that cannot be read are skipped.
+    #[pyo3(signature = (advance = false, anchors = false))]
+    pub fn duplicate_outlines(&self, advance: bool, anchors: bool) -> Vec<Vec<String>> {
+        let options = HashOptions::default()
+            .with_advance(advance)
+            .with_anchors(anchors);
+        self.inner.duplicate_outlines(options)
+    }
+
+    /// Compare this font, as the old version, with `other`.
+    ///
+    /// Returns a dict with `added` and `removed` lists of glyph names, and a `changed` dict
+    /// mapping glyph names to their changes as returned by `Glyph.diff`. A glyph that cannot be
+    /// read in either font is listed as changed, with an `unreadable` change.
+    pub fn diff(&self, py: Python<'_>, other: PyRef<PyFont>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &diff_fonts(&self.inner, &other.inner))
+    }
+
+    /// Compare this font with `other` and return the changes as human-readable text.
+    pub fn diff_text(&self, other: PyRef<PyFont>) -> String {
+        diff_fonts(&self.inner, &other.inner).to_string()
+    }
+
+    /// Return an SVG document showing the named glyphs, or all glyphs in glyph order.
+    ///
+    /// Named glyphs that cannot be read raise an error; when showing all glyphs, they are left
+    /// out.
+    /// `layout` is `"grid"`, with `columns` one-em cells per row, or `"line"`, which sets the
+    /// glyphs as a line of text. Anchors and guidelines are drawn on request.
+    #[pyo3(signature = (names = None, layout = "grid", columns = 10, anchors = false, guidelines = false))]
+    pub fn to_svg_document(
+        &self,
+        names: Option<Vec<String>>,
+        layout: &str,
+        columns: usize,
+        anchors: bool,
+        guidelines: bool,
+    ) -> PyResult<String> {
+        let layout = match layout {
+            "grid" => SvgLayout::Grid { columns },
+            "line" => SvgLayout::Line,
+            _ => {
+                return Err(PyValueError::new_err(format!(
+                    "layout must be 'grid' or 'line', not '{layout}'"
+                )));
+            }
+        };
+        let options = SvgOptions::default()
+            .with_layout(layout)
+            .with_anchors(anchors)
+            .with_guidelines(guidelines);
+        Ok(match names {
+            Some(names) => self.inner.to_svg_document(names, options)?,
+            None => self.inner.to_svg_document_all(options)?,
+        })
+    }
+
+    /// Get a dict mapping each Unicode code point to the list of glyph names that claim it.
+    ///
+    /// Code points claimed by more than one glyph are conflicts; see `cmap_conflicts`.
+    #[getter]
+    pub fn cmap(&self) -> BTreeMap<u32, Vec<String>> {
+        self.inner.cmap()
+    }
+
+    /// Return the code points claimed by more than one glyph, mapped to the glyph names.
+    pub fn cmap_conflicts(&self) -> BTreeMap<u32, Vec<String>> {
+        self.inner.cmap_conflicts()
+    }
+
+    /// Return the names of the glyphs mapped to the given code point.
+    pub fn glyphs_for(&self, codepoint: u32) -> Vec<String> {
+        self.inner.glyphs_for(codepoint)
+    }
+
+    /// Return the names of the glyphs mapped to any code point from `start` to `end`, inclusive.
+    pub fn glyphs_in_range(&self, start: u32, end: u32) -> Vec<String> {
+        self.inner.glyphs_in_range(start..=end)
+    }
+
+    /// Return the names of the glyphs without Unicode code points.
+    pub fn unencoded_glyphs(&self) -> PyResult<Vec<String>> {
+        Ok(self.inner.unencoded_glyphs()?)
+    }
+
+    /// Check which of the given code points are mapped to a glyph.
+    ///
+    /// Returns a dict with sorted `covered` and `missing` lists of code points.
+    pub fn coverage(&self, py: Python<'_>, codepoints: Vec<u32>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.coverage(codepoints))
+    }
+
+    /// Get the names of the glyphs edited since they were loaded or last saved.
+    #[getter]
+    pub fn dirty_glyphs(&self) -> Vec<String> {
+        self.inner.dirty_glyphs()
+    }
+
+    /// Write modified glyphs and `contents.plist` back through the font's provider.
+    ///
+    /// Untouched files are left as they are. Custom providers must implement `write(path, data)`,
+    /// `remove(path)` and `create_dir(path)` to support saving.
+    pub fn save(&self) -> PyResult<()> {
+        Ok(self.inner.save()?)
+    }
+
+    /// Re-read `contents.plist` and `lib.plist` and discard all cached glyph data, including
+    /// unsaved edits.
+    pub fn reload(&self) -> PyResult<()> {
+        Ok(self.inner.reload()?)
+    }
+
+    /// Return `True` if `contents.plist` or `lib.plist` changed since they were read.
+    pub fn is_stale(&self) -> PyResult<bool> {
+        Ok(self.inner.is_stale()?)
+    }
+
+    /// Return the names of the loaded glyphs whose `.glif` file changed since it was read.
+    pub fn stale_glyphs(&self) -> PyResult<Vec<String>> {
+        Ok(self.inner.stale_glyphs()?)
     }
 
-    /// Return a list of the glyph names in the font.
+    /// Bring the font up to date with its files and return the names of the glyphs whose cached
+    /// data was discarded.
+    ///
+    /// Reloads the whole font if `contents.plist` or `lib.plist` changed, otherwise only the
+    /// stale glyphs.
+    pub fn refresh(&self) -> PyResult<Vec<String>> {
+        Ok(self.inner.refresh()?)
+    }
+
+    /// Return a list of the glyph names in the font, in glyph order.
     pub fn keys(&self) -> PyResult<Vec<String>> {
-        Ok(self
-            .inner
-            .glyphs()
-            .keys()
-            .map(|k| k.into())
-            .collect::<Vec<_>>())
+        Ok(self.inner.contents().keys().cloned().collect::<Vec<_>>())
     }
 
     /// Return a list of the `Glyph` objects in the font.
@@ -149,7 +408,10 @@ impl PyGlyph {
     #[new]
     pub fn new(py: Python<'_>, font: PyObject, name: String) -> PyResult<Self> {
         let font = font.extract::<PyRef<PyFont>>(py)?;
-        let inner = Glyph::new(&font.inner, name);
+        let inner = font
+            .inner
+            .glyph(&name)
+            .unwrap_or_else(|| Glyph::new(&font.inner, name));
 
         Ok(Self { inner })
     }
@@ -160,16 +422,23 @@ impl PyGlyph {
         self.inner.name().to_owned()
     }
 
-    /// Get the major format version string of the glyph's GLIF file.
+    /// Get the major format version of the glyph's GLIF file.
+    #[getter]
+    pub fn format(&self) -> PyResult<u8> {
+        Ok(self.inner.format()?.major)
+    }
+
+    /// Get the minor format version of the glyph's GLIF file.
     #[getter]
-    pub fn format(&self) -> PyResult<String> {
-        Ok(self.inner.format()?)
+    pub fn format_minor(&self) -> PyResult<u8> {
+        Ok(self.inner.format()?.minor)
     }
 
-    /// Get the minor format version string of the glyph's GLIF file.
+    /// Get the format version of the glyph's GLIF file as a `(major, minor)` tuple.
     #[getter]
-    pub fn format_minor(&self) -> PyResult<Option<String>> {
-        Ok(self.inner.format_minor()?)
+    pub fn format_version(&self) -> PyResult<(u8, u8)> {
+        let version = self.inner.format()?;
+        Ok((version.major, version.minor))
     }
 
     /// Get the glyph's advance width/height data (if any).
@@ -179,18 +448,80 @@ impl PyGlyph {
         Ok(Some(PyGlifData::to_pyobject(py, &value)?))
     }
 
-    /// Get the glyph's list of Unicode code points.
+    /// Set the glyph's advance from a dict with optional `width` and `height` keys, or `None`.
+    #[setter]
+    pub fn set_advance(&self, advance: Option<HashMap<String, Option<f64>>>) -> PyResult<()> {
+        let advance = advance.map(|a| Advance {
+            width: a.get("width").copied().flatten(),
+            height: a.get("height").copied().flatten(),
+        });
+        Ok(self.inner.set_advance(advance)?)
+    }
+
+    /// Get or set the glyph's advance width.
+    #[getter]
+    pub fn width(&self) -> PyResult<Option<f64>> {
+        Ok(self.inner.advance()?.and_then(|a| a.width))
+    }
+
+    #[setter]
+    pub fn set_width(&self, width: Option<f64>) -> PyResult<()> {
+        Ok(self
+            .inner
+            .edit(|d| d.advance.get_or_insert_default().width = width)?)
+    }
+
+    /// Get or set the glyph's advance height.
+    #[getter]
+    pub fn height(&self) -> PyResult<Option<f64>> {
+        Ok(self.inner.advance()?.and_then(|a| a.height))
+    }
+
+    #[setter]
+    pub fn set_height(&self, height: Option<f64>) -> PyResult<()> {
+        Ok(self
+            .inner
+            .edit(|d| d.advance.get_or_insert_default().height = height)?)
+    }
+
+    /// Get or set the glyph's list of Unicode code points.
     #[getter]
     pub fn unicodes(&self) -> PyResult<Vec<u32>> {
         Ok(self.inner.unicodes()?)
     }
 
-    /// Get an optional note attached to the glyph.
+    #[setter]
+    pub fn set_unicodes(&self, unicodes: Vec<u32>) -> PyResult<()> {
+        Ok(self.inner.set_unicodes(unicodes)?)
+    }
+
+    /// Get or set an optional note attached to the glyph.
     #[getter]
     pub fn note(&self) -> PyResult<Option<String>> {
         Ok(self.inner.note()?)
     }
 
+    #[setter]
+    pub fn set_note(&self, note: Option<String>) -> PyResult<()> {
+        Ok(self.inner.set_note(note)?)
+    }
+
+    /// Get whether the glyph was edited since it was loaded or last saved.
+    #[getter]
+    pub fn is_dirty(&self) -> bool {
+        self.inner.is_dirty()
+    }
+
+    /// Return `True` if the glyph's `.glif` file changed since it was read.
+    pub fn is_stale(&self) -> PyResult<bool> {
+        Ok(self.inner.is_stale()?)
+    }
+
+    /// Discard the cached glyph data, including unsaved edits, and read it again.
+    pub fn reload(&self) -> PyResult<()> {
+        Ok(self.inner.reload()?)
+    }
+
     /// Get the glyph's associated image, if present.
     #[getter]
     pub fn image(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
@@ -198,39 +529,380 @@ impl PyGlyph {
         Ok(Some(PyGlifData::to_pyobject(py, &value)?))
     }
 
-    /// Get the glyph's guidelines.
+    /// Get the glyph's guidelines as editable `Guideline` objects.
     #[getter]
-    pub fn guidelines(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
-        let value = self.inner.guidelines()?;
-        let data = value
-            .into_iter()
-            .map(|v| PyGlifData::to_pyobject(py, &v))
-            .collect::<PyResult<Vec<_>>>()?;
-        Ok(data)
+    pub fn guidelines(&self) -> PyResult<Vec<PyGuideline>> {
+        let len = self.inner.guidelines()?.len();
+        Ok((0..len)
+            .map(|index| PyGuideline {
+                glyph: Rc::clone(&self.inner),
+                index,
+            })
+            .collect())
+    }
+
+    /// Append a guideline to the glyph and return it.
+    #[pyo3(signature = (x = None, y = None, angle = None, name = None, color = None, identifier = None))]
+    pub fn add_guideline(
+        &self,
+        x: Option<f64>,
+        y: Option<f64>,
+        angle: Option<f64>,
+        name: Option<String>,
+        color: Option<(f64, f64, f64, f64)>,
+        identifier: Option<String>,
+    ) -> PyResult<PyGuideline> {
+        let color = color
+            .map(|(r, g, b, a)| Color::new(r, g, b, a))
+            .transpose()?;
+        self.inner.add_guideline(Guideline {
+            x,
+            y,
+            angle,
+            name,
+            color,
+            identifier,
+            ..Default::default()
+        })?;
+        Ok(PyGuideline {
+            glyph: Rc::clone(&self.inner),
+            index: self.inner.guidelines()?.len() - 1,
+        })
     }
 
-    /// Get the glyph's anchors.
+    /// Remove the guideline at `index`.
+    pub fn remove_guideline(&self, index: usize) -> PyResult<()> {
+        match self.inner.remove_guideline(index)? {
+            Some(_) => Ok(()),
+            None => Err(PyIndexError::new_err("guideline index out of range")),
+        }
+    }
+
+    /// Get the glyph's anchors as editable `Anchor` objects.
     #[getter]
-    pub fn anchors(&self, py: Python<'_>) -> PyResult<Vec<PyObject>> {
-        let value = self.inner.anchors()?;
-        let data = value
-            .into_iter()
-            .map(|v| PyGlifData::to_pyobject(py, &v))
-            .collect::<PyResult<Vec<_>>>()?;
-        Ok(data)
+    pub fn anchors(&self) -> PyResult<Vec<PyAnchor>> {
+        let len = self.inner.anchors()?.len();
+        Ok((0..len)
+            .map(|index| PyAnchor {
+                glyph: Rc::clone(&self.inner),
+                index,
+            })
+            .collect())
+    }
+
+    /// Append an anchor to the glyph and return it.
+    #[pyo3(signature = (x, y, name = None, color = None, identifier = None))]
+    pub fn add_anchor(
+        &self,
+        x: f64,
+        y: f64,
+        name: Option<String>,
+        color: Option<(f64, f64, f64, f64)>,
+        identifier: Option<String>,
+    ) -> PyResult<PyAnchor> {
+        let color = color
+            .map(|(r, g, b, a)| Color::new(r, g, b, a))
+            .transpose()?;
+        self.inner.add_anchor(Anchor {
+            x: Some(x),
+            y: Some(y),
+            name,
+            color,
+            identifier,
+            ..Default::default()
+        })?;
+        Ok(PyAnchor {
+            glyph: Rc::clone(&self.inner),
+            index: self.inner.anchors()?.len() - 1,
+        })
+    }
+
+    /// Remove the anchor at `index`.
+    pub fn remove_anchor(&self, index: usize) -> PyResult<()> {
+        match self.inner.remove_anchor(index)? {
+            Some(_) => Ok(()),
+            None => Err(PyIndexError::new_err("anchor index out of range")),
+        }
     }
 
     /// Get the glyph's outline, if present.
+    ///
+    /// The outline is returned as a dict snapshot; use `contours` and `components` to edit it.
     #[getter]
     pub fn outline(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
         let value = self.inner.outline()?;
         Ok(Some(PyGlifData::to_pyobject(py, &value)?))
     }
 
+    /// Get the glyph's contours as editable `Contour` objects.
+    #[getter]
+    pub fn contours(&self) -> PyResult<Vec<PyContour>> {
+        let len = self.inner.outline()?.map_or(0, |o| o.contours.len());
+        Ok((0..len)
+            .map(|index| PyContour {
+                glyph: Rc::clone(&self.inner),
+                index,
+            })
+            .collect())
+    }
+
+    /// Append an empty contour to the glyph and return it.
+    #[pyo3(signature = (identifier = None))]
+    pub fn add_contour(&self, identifier: Option<String>) -> PyResult<PyContour> {
+        self.inner.add_contour(Contour {
+            identifier,
+            points: Vec::new(),
+        })?;
+        let len = self.inner.outline()?.map_or(0, |o| o.contours.len());
+        Ok(PyContour {
+            glyph: Rc::clone(&self.inner),
+            index: len - 1,
+        })
+    }
+
+    /// Remove the contour at `index`.
+    pub fn remove_contour(&self, index: usize) -> PyResult<()> {
+        match self.inner.remove_contour(index)? {
+            Some(_) => Ok(()),
+            None => Err(PyIndexError::new_err("contour index out of range")),
+        }
+    }
+
+    /// Get the glyph's components as editable `Component` objects.
+    #[getter]
+    pub fn components(&self) -> PyResult<Vec<PyComponent>> {
+        let len = self.inner.outline()?.map_or(0, |o| o.components.len());
+        Ok((0..len)
+            .map(|index| PyComponent {
+                glyph: Rc::clone(&self.inner),
+                index,
+            })
+            .collect())
+    }
+
+    /// Append a component referring to `base` to the glyph and return it.
+    ///
+    /// If `transform` is given, its non-default values are written as the component's
+    /// transformation attributes.
+    #[pyo3(signature = (base, transform = None, identifier = None))]
+    pub fn add_component(
+        &self,
+        base: String,
+        transform: Option<PyTransform>,
+        identifier: Option<String>,
+    ) -> PyResult<PyComponent> {
+        let t = transform.map_or(Transform::IDENTITY, |t| t.inner);
+        let non_default = |value: f64, default: f64| (value != default).then_some(value);
+        self.inner.add_component(Component {
+            base: Some(base),
+            x_scale: non_default(t.xx, glif_default::X_SCALE),
+            xy_scale: non_default(t.xy, glif_default::XY_SCALE),
+            yx_scale: non_default(t.yx, glif_default::YX_SCALE),
+            y_scale: non_default(t.yy, glif_default::Y_SCALE),
+            x_offset: non_default(t.dx, glif_default::X_OFFSET),
+            y_offset: non_default(t.dy, glif_default::Y_OFFSET),
+            identifier,
+        })?;
+        let len = self.inner.outline()?.map_or(0, |o| o.components.len());
+        Ok(PyComponent {
+            glyph: Rc::clone(&self.inner),
+            index: len - 1,
+        })
+    }
+
+    /// Remove the component at `index`.
+    pub fn remove_component(&self, index: usize) -> PyResult<()> {
+        match self.inner.remove_component(index)? {
+            Some(_) => Ok(()),
+            None => Err(PyIndexError::new_err("component index out of range")),
+        }
+    }
+
+    /// Get the transforms of the glyph's components, in outline order.
+    ///
+    /// Omitted transformation attributes are resolved to their specification defaults.
+    #[getter]
+    pub fn component_transforms(&self) -> PyResult<Vec<PyTransform>> {
+        let outline = self.inner.outline()?;
+        Ok(outline
+            .iter()
+            .flat_map(|o| &o.components)
+            .map(|c| PyTransform {
+                inner: c.transform(),
+            })
+            .collect())
+    }
+
+    /// Get the transform of the glyph's image, if present.
+    ///
+    /// Omitted transformation attributes are resolved to their specification defaults.
+    #[getter]
+    pub fn image_transform(&self) -> PyResult<Option<PyTransform>> {
+        let image = self.inner.image()?;
+        Ok(image.map(|i| PyTransform {
+            inner: i.transform(),
+        }))
+    }
+
     /// Get the glyph's lib dictionary (if present).
     #[getter]
     pub fn lib(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
         let value = self.inner.lib()?;
         Ok(Some(PyGlifData::to_pyobject(py, &value)?))
     }
+
+    /// Replace the glyph's lib with a dict of property list values, or remove it with `None`.
+    #[setter]
+    pub fn set_lib(&self, lib: Option<Bound<'_, PyDict>>) -> PyResult<()> {
+        let lib = lib.map(|lib| pyobject_to_plist(lib.as_any())).transpose()?;
+        Ok(self.inner.set_lib(lib)?)
+    }
+
+    /// Get the glyph's mark color from the `public.markColor` lib key as an `(r, g, b, a)`
+    /// tuple.
+    ///
+    /// An invalid color returns `None` and is reported by the `invalid-color` lint rule.
+    #[getter]
+    pub fn mark_color(&self) -> PyResult<Option<(f64, f64, f64, f64)>> {
+        Ok(self.inner.mark_color()?.map(|c| c.to_tuple()))
+    }
+
+    /// Get the glyph's `public.objectLibs` mapping of object identifiers to lib dicts.
+    #[getter]
+    pub fn object_libs(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.object_libs()?)
+    }
+
+    /// Return the object lib of the anchor, guideline or other object with the given
+    /// identifier, or `None`.
+    pub fn object_lib(&self, py: Python<'_>, identifier: &str) -> PyResult<Option<PyObject>> {
+        self.inner
+            .object_lib(identifier)?
+            .map(|lib| PyGlifData::to_pyobject(py, &lib))
+            .transpose()
+    }
+
+    /// Return the `public.objectLibs` keys that don't refer to an object in the glyph.
+    pub fn validate_object_libs(&self) -> PyResult<Vec<String>> {
+        Ok(self.inner.validate_object_libs()?)
+    }
+
+    /// Check the glyph's components, returning a list of dicts like `Font.check_components`.
+    pub fn check_components(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.check_components()?)
+    }
+
+    /// Get the bounds of the glyph's outline, with components decomposed, as an
+    /// `(x_min, y_min, x_max, y_max)` tuple, or `None` if the glyph draws nothing.
+    #[getter]
+    pub fn bounds(&self) -> PyResult<Option<(f64, f64, f64, f64)>> {
+        Ok(self
+            .inner
+            .bounds()?
+            .map(|b| (b.x_min, b.y_min, b.x_max, b.y_max)))
+    }
+
+    /// Return the glyph's sidebearings as a dict with `left`, `right`, `top` and `bottom` keys,
+    /// or `None` if the glyph draws nothing.
+    ///
+    /// Components are resolved. `top` and `bottom` are measured from the glyph's
+    /// `public.verticalOrigin`, or from its advance height if that is not set.
+    pub fn sidebearings(&self, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        self.inner
+            .sidebearings()?
+            .map(|sidebearings| PyGlifData::to_pyobject(py, &sidebearings))
+            .transpose()
+    }
+
+    /// Return the left and right sidebearings measured along the horizontal line at `y`, or
+    /// `None` if the line misses the outline.
+    pub fn sidebearings_at(&self, y: f64) -> PyResult<Option<(f64, f64)>> {
+        Ok(self.inner.sidebearings_at(y)?)
+    }
+
+    /// Return the glyph's outline as SVG path data in glyph coordinates, with components
+    /// decomposed unless `decompose` is false.
+    #[pyo3(signature = (decompose = true))]
+    pub fn to_svg_path(&self, decompose: bool) -> PyResult<String> {
+        Ok(self.inner.to_svg_path(decompose)?)
+    }
+
+    /// Render the glyph, with components decomposed, to an anti-aliased grayscale bitmap.
+    ///
+    /// `size` is the em height in pixels, at most `MAX_RASTER_SIZE`, and the bitmap can have at
+    /// most `MAX_RASTER_PIXELS` pixels; other sizes raise `ValueError`. `frame` is `"advance"`,
+    /// showing the advance width by the em from ascender to descender, or `"bounds"`, showing
+    /// the outline's bounds. Returns `(width, height, data)`, where `data` is
+    /// a `bytes` object of coverage values from 0 to 255, row by row from the top;
+    /// `numpy.frombuffer(data, numpy.uint8).reshape(height, width)` views it as an array.
+    #[pyo3(signature = (size = 64.0, frame = "advance"))]
+    pub fn rasterize<'py>(
+        &self,
+        py: Python<'py>,
+        size: f64,
+        frame: &str,
+    ) -> PyResult<(u32, u32, Bound<'py, PyBytes>)> {
+        let bitmap = self.inner.rasterize(raster_options(size, frame)?)?;
+        Ok((bitmap.width, bitmap.height, PyBytes::new(py, &bitmap.data)))
+    }
+
+    /// Render the glyph as `rasterize` does and return it as PNG data, in black on white.
+    #[pyo3(signature = (size = 64.0, frame = "advance"))]
+    pub fn to_png<'py>(
+        &self,
+        py: Python<'py>,
+        size: f64,
+        frame: &str,
+    ) -> PyResult<Bound<'py, PyBytes>> {
+        let bitmap = self.inner.rasterize(raster_options(size, frame)?)?;
+        Ok(PyBytes::new(py, &bitmap.to_png()?))
+    }
+
+    /// Return a SHA-256 hex digest of the glyph's outline, with components decomposed.
+    ///
+    /// The hash covers point types and coordinates rounded to three decimal places, and
+    /// optionally the advance and the anchors. It is stable across runs, so it can key
+    /// incremental builds.
+    #[pyo3(signature = (advance = false, anchors = false))]
+    pub fn outline_hash(&self, advance: bool, anchors: bool) -> PyResult<String> {
+        let options = HashOptions::default()
+            .with_advance(advance)
+            .with_anchors(anchors);
+        Ok(self.inner.outline_hash(options)?)
+    }
+
+    /// Compare this glyph, as the old version, with `other`.
+    ///
+    /// Returns a list of dicts with a `kind` key, such as `"advanceWidth"`, `"anchorMoved"`,
+    /// `"componentTransform"`, `"pointChanged"` or `"libKeyChanged"`, and the old and new values.
+    pub fn diff(&self, py: Python<'_>, other: PyRef<PyGlyph>) -> PyResult<PyObject> {
+        let diff = diff_glyphs(&self.inner.data()?, &other.inner.data()?);
+        PyGlifData::to_pyobject(py, &diff)
+    }
+
+    /// Return the identifier problems in the glyph as a list of dicts with `kind`
+    /// (`"duplicate"` or `"invalid"`) and `identifier` keys.
+    pub fn validate_identifiers(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.validate_identifiers()?)
+    }
+
+    /// Assign a random, unique identifier to every object in the glyph that lacks one, and
+    /// return the number of identifiers assigned. Format 1 glyphs are left unchanged.
+    pub fn assign_missing_identifiers(&self) -> PyResult<usize> {
+        Ok(self.inner.assign_missing_identifiers()?)
+    }
+}
+
+/// Builds [`RasterOptions`] from the `size` and `frame` arguments of the rasterizing methods.
+fn raster_options(size: f64, frame: &str) -> PyResult<RasterOptions> {
+    let frame = match frame {
+        "advance" => RasterFrame::Advance,
+        "bounds" => RasterFrame::Bounds,
+        _ => {
+            return Err(PyValueError::new_err(format!(
+                "frame must be 'advance' or 'bounds', not '{frame}'"
+            )));
+        }
+    };
+    Ok(RasterOptions::default().with_size(size).with_frame(frame))
 }
@@ -3,6 +3,7 @@ use pyo3::types::PyDict;
 
 use crate::bindings::{PyFileProvider, PyGlifData, PyProvider};
 use crate::gleaner::Gleaner;
+use crate::glif::{LibMode, ParseOptions};
 
 /// Batch-parse UFO GLIF files Eagerly.
 ///
@@ -20,6 +21,8 @@ impl PyGleaner {
     /// # Arguments
     ///
     /// * `provider` – A Python object implementing the provider interface (e.g., `FileProvider`).
+    /// * `apply_defaults` – Fill omitted GLIF attributes with their specification defaults.
+    /// * `lib` – `"eager"` to parse glyph libs, or `"skip"` to leave them out.
     ///
     /// # Example
     ///
@@ -27,20 +30,29 @@ impl PyGleaner {
     /// from ufo_gleaner import Gleaner, FileProvider
     ///
     /// provider = FileProvider("/path/to/myfont.ufo")
-    /// gleaner = Gleaner(provider)
+    /// gleaner = Gleaner(provider, apply_defaults=True)
     /// ```
     #[new]
-    pub fn new(py: Python<'_>, provider: Py<PyAny>) -> PyResult<Self> {
+    #[pyo3(signature = (provider, apply_defaults = false, lib = "eager"))]
+    pub fn new(
+        py: Python<'_>,
+        provider: Py<PyAny>,
+        apply_defaults: bool,
+        lib: &str,
+    ) -> PyResult<Self> {
+        let options = ParseOptions::default()
+            .with_apply_defaults(apply_defaults)
+            .with_lib_mode(lib.parse::<LibMode>()?);
         // Try to downcast to PyFileProvider.
         // Return if Ok. If not, assume it's a custom PyProvider implementation.
         match provider.extract::<PyRef<PyFileProvider>>(py) {
             Ok(file_provider) => {
-                let gleaner = Gleaner::new(file_provider.inner.clone())?;
+                let gleaner = Gleaner::with_options(file_provider.inner.clone(), options)?;
                 Ok(Self { inner: gleaner })
             }
             Err(_) => {
                 let provider = PyProvider::new(py, provider)?;
-                let gleaner = Gleaner::new(provider)?;
+                let gleaner = Gleaner::with_options(provider, options)?;
                 Ok(Self { inner: gleaner })
             }
         }
@@ -0,0 +1,202 @@
+use std::rc::Rc;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyTuple};
+
+use crate::bindings::{PyErrExt, PyFont, PyGlifData, pyobject_to_plist};
+use crate::error::Result;
+use crate::font::Font;
+use crate::glif::GlifData;
+use crate::lint::{Context, Linter, Rule, Severity};
+
+/// A [`Rule`] implemented by a Python object.
+///
+/// The object must have an `id` attribute and may have a `severity` attribute and
+/// `check_glyph(name, glyph, options)` and `check_font(font, options)` methods, where `options`
+/// is the dict of options configured for the rule. The methods return an iterable of message
+/// strings or dicts with `message`, `contour` and `point` keys, or `None`.
+struct PyRule {
+    id: String,
+    severity: Severity,
+    object: PyObject,
+}
+
+impl PyRule {
+    fn new(py: Python<'_>, object: PyObject) -> PyResult<Self> {
+        let bound = object.bind(py);
+        let id = bound.getattr("id")?.extract::<String>()?;
+        let severity = match bound.getattr_opt("severity")? {
+            Some(severity) if !severity.is_none() => severity.extract::<String>()?.parse()?,
+            _ => Severity::Warning,
+        };
+        Ok(Self {
+            id,
+            severity,
+            object,
+        })
+    }
+
+    /// Calls `method` if the object has it and reports the returned problems.
+    fn call<'py>(
+        &self,
+        py: Python<'py>,
+        method: &str,
+        args: Bound<'py, PyTuple>,
+        ctx: &mut Context,
+    ) -> PyResult<()> {
+        let object = self.object.bind(py);
+        if !object.hasattr(method)? {
+            return Ok(());
+        }
+        let results = object.call_method1(method, args)?;
+        if results.is_none() {
+            return Ok(());
+        }
+        for result in results.try_iter()? {
+            let result = result?;
+            match result.downcast::<PyDict>() {
+                Ok(dict) => {
+                    let message = dict
+                        .get_item("message")?
+                        .map(|m| m.extract::<String>())
+                        .transpose()?
+                        .unwrap_or_default();
+                    let contour = dict.get_item("contour")?.map(|c| c.extract()).transpose()?;
+                    let point = dict.get_item("point")?.map(|p| p.extract()).transpose()?;
+                    match contour {
+                        Some(contour) => ctx.report_at(message, contour, point),
+                        None => ctx.report(message),
+                    }
+                }
+                Err(_) => ctx.report(result.extract::<String>()?),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Rule for PyRule {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn default_severity(&self) -> Severity {
+        self.severity
+    }
+
+    fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+        Python::with_gil(|py| {
+            let name = ctx.glyph().unwrap_or_default().to_owned();
+            let data = PyGlifData::to_pyobject(py, glyph)?;
+            let options = PyGlifData::to_pyobject(py, ctx.options())?;
+            self.call(
+                py,
+                "check_glyph",
+                (name, data, options).into_pyobject(py)?,
+                ctx,
+            )
+        })
+        .map_err(|e| e.to_ufo())
+    }
+
+    fn check_font(&self, font: &Rc<Font>, ctx: &mut Context) -> Result<()> {
+        Python::with_gil(|py| {
+            let font = Py::new(
+                py,
+                PyFont {
+                    inner: Rc::clone(font),
+                },
+            )?;
+            let options = PyGlifData::to_pyobject(py, ctx.options())?;
+            self.call(py, "check_font", (font, options).into_pyobject(py)?, ctx)
+        })
+        .map_err(|e| e.to_ufo())
+    }
+}
+
+/// Run lint rules over a font.
+///
+/// By default the linter includes the built-in rules: `open-contour`, `single-point-contour`,
+/// `duplicate-point`, `non-integer-coordinate`, `empty-glyph-with-unicode`,
+/// `overlapping-anchors`, `unsupported-format-version` and `invalid-color`. Custom rules are
+/// registered with `register`.
+#[pyclass(unsendable, name = "Linter")]
+pub struct PyLinter {
+    inner: Linter,
+}
+
+#[pymethods]
+impl PyLinter {
+    /// Create a new `Linter`, with the built-in rules unless `default_rules` is `False`.
+    #[new]
+    #[pyo3(signature = (default_rules = true))]
+    pub fn new(default_rules: bool) -> Self {
+        let inner = match default_rules {
+            true => Linter::with_default_rules(),
+            false => Linter::new(),
+        };
+        Self { inner }
+    }
+
+    /// Get the ids of all registered rules, in the order they run.
+    #[getter]
+    pub fn rule_ids(&self) -> Vec<String> {
+        self.inner
+            .rule_ids()
+            .into_iter()
+            .map(str::to_owned)
+            .collect()
+    }
+
+    /// Register a custom rule, replacing any rule with the same id.
+    ///
+    /// `rule` must have an `id` attribute and may have a `severity` attribute (`"info"`,
+    /// `"warning"` or `"error"`). It may implement `check_glyph(name, glyph, options)`, which
+    /// receives the glyph data as a dict, and `check_font(font, options)`, which receives the
+    /// `Font`. `options` is the dict set with `configure`. Both return an iterable of message
+    /// strings or dicts with `message` and optional `contour` and `point` keys.
+    pub fn register(&mut self, py: Python<'_>, rule: PyObject) -> PyResult<()> {
+        self.inner.add_rule(Box::new(PyRule::new(py, rule)?));
+        Ok(())
+    }
+
+    /// Configure a rule: enable or disable it, override its severity, or set rule-specific
+    /// options such as `{"tolerance": 2}` for `overlapping-anchors`.
+    #[pyo3(signature = (rule_id, enabled = None, severity = None, options = None))]
+    pub fn configure(
+        &mut self,
+        rule_id: &str,
+        enabled: Option<bool>,
+        severity: Option<&str>,
+        options: Option<&Bound<'_, PyDict>>,
+    ) -> PyResult<()> {
+        let mut config = self.inner.config(rule_id);
+        if let Some(enabled) = enabled {
+            config.enabled = enabled;
+        }
+        if let Some(severity) = severity {
+            config.severity = Some(severity.parse()?);
+        }
+        if let Some(options) = options {
+            config.options = pyobject_to_plist(options.as_any())?
+                .into_dictionary()
+                .ok_or_else(|| PyValueError::new_err("options must be a dict"))?;
+        }
+        self.inner.set_config(rule_id, config);
+        Ok(())
+    }
+
+    /// Run all enabled rules over the font.
+    ///
+    /// Returns a list of dicts with `rule`, `severity`, `glyph`, `message`, `contour` and
+    /// `point` keys.
+    pub fn lint(&self, py: Python<'_>, font: PyRef<PyFont>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.lint(&font.inner)?)
+    }
+
+    /// Run all enabled rules over the font and return the diagnostics as a JSON string.
+    pub fn lint_json(&self, font: PyRef<PyFont>) -> PyResult<String> {
+        Ok(crate::lint::to_json(&self.inner.lint(&font.inner)?)?)
+    }
+}
@@ -0,0 +1,559 @@
+//! Mutable views of the objects inside a glyph.
+//!
+//! Each view refers to its object by position in the parent glyph. Reading an attribute reads
+//! the glyph's current data, and setting one edits the glyph in place and marks it as dirty.
+//! Removing an object shifts the positions of the objects after it, so views obtained before a
+//! removal may refer to a different object or raise `IndexError` afterwards.
+
+use std::rc::Rc;
+
+use pyo3::exceptions::PyIndexError;
+use pyo3::prelude::*;
+
+use crate::color::Color;
+use crate::font::Glyph;
+use crate::glif::{Anchor, Component, Contour, GlifData, Guideline, Point, PointType};
+
+type RgbaTuple = (f64, f64, f64, f64);
+
+/// Converts an optional `(r, g, b, a)` tuple into a [`Color`].
+fn to_color(value: Option<RgbaTuple>) -> PyResult<Option<Color>> {
+    Ok(value
+        .map(|(r, g, b, a)| Color::new(r, g, b, a))
+        .transpose()?)
+}
+
+fn stale(kind: &str, index: usize) -> PyErr {
+    PyIndexError::new_err(format!("{kind} index {index} is out of range"))
+}
+
+/// Reads an object of the glyph selected by `select`.
+fn read<T, R>(
+    glyph: &Glyph,
+    kind: &str,
+    index: usize,
+    select: impl FnOnce(&GlifData) -> Option<&T>,
+    f: impl FnOnce(&T) -> R,
+) -> PyResult<R> {
+    let data = glyph.data()?;
+    select(&data).map(f).ok_or_else(|| stale(kind, index))
+}
+
+/// Edits an object of the glyph selected by `select`.
+fn write<T>(
+    glyph: &Glyph,
+    kind: &str,
+    index: usize,
+    select: impl FnOnce(&mut GlifData) -> Option<&mut T>,
+    f: impl FnOnce(&mut T),
+) -> PyResult<()> {
+    glyph
+        .edit(|data| select(data).map(f))?
+        .ok_or_else(|| stale(kind, index))
+}
+
+/// A view of an anchor in a glyph.
+#[pyclass(unsendable, name = "Anchor")]
+pub struct PyAnchor {
+    pub glyph: Rc<Glyph>,
+    pub index: usize,
+}
+
+impl PyAnchor {
+    fn get<R>(&self, f: impl FnOnce(&Anchor) -> R) -> PyResult<R> {
+        read(
+            &self.glyph,
+            "anchor",
+            self.index,
+            |d| d.anchors.get(self.index),
+            f,
+        )
+    }
+
+    fn set(&self, f: impl FnOnce(&mut Anchor)) -> PyResult<()> {
+        write(
+            &self.glyph,
+            "anchor",
+            self.index,
+            |d| d.anchors.get_mut(self.index),
+            f,
+        )
+    }
+}
+
+#[pymethods]
+impl PyAnchor {
+    #[getter]
+    pub fn x(&self) -> PyResult<Option<f64>> {
+        self.get(|a| a.x)
+    }
+
+    #[setter]
+    pub fn set_x(&self, x: Option<f64>) -> PyResult<()> {
+        self.set(|a| a.x = x)
+    }
+
+    #[getter]
+    pub fn y(&self) -> PyResult<Option<f64>> {
+        self.get(|a| a.y)
+    }
+
+    #[setter]
+    pub fn set_y(&self, y: Option<f64>) -> PyResult<()> {
+        self.set(|a| a.y = y)
+    }
+
+    #[getter]
+    pub fn name(&self) -> PyResult<Option<String>> {
+        self.get(|a| a.name.clone())
+    }
+
+    #[setter]
+    pub fn set_name(&self, name: Option<String>) -> PyResult<()> {
+        self.set(|a| a.name = name)
+    }
+
+    /// Get or set the color as an `(r, g, b, a)` tuple.
+    #[getter]
+    pub fn color(&self) -> PyResult<Option<RgbaTuple>> {
+        self.get(|a| a.color.map(|c| c.to_tuple()))
+    }
+
+    #[setter]
+    pub fn set_color(&self, color: Option<RgbaTuple>) -> PyResult<()> {
+        let color = to_color(color)?;
+        self.set(|a| {
+            a.color = color;
+            a.invalid_color = None;
+        })
+    }
+
+    #[getter]
+    pub fn identifier(&self) -> PyResult<Option<String>> {
+        self.get(|a| a.identifier.clone())
+    }
+
+    #[setter]
+    pub fn set_identifier(&self, identifier: Option<String>) -> PyResult<()> {
+        self.set(|a| a.identifier = identifier)
+    }
+}
+
+/// A view of a guideline in a glyph.
+#[pyclass(unsendable, name = "Guideline")]
+pub struct PyGuideline {
+    pub glyph: Rc<Glyph>,
+    pub index: usize,
+}
+
+impl PyGuideline {
+    fn get<R>(&self, f: impl FnOnce(&Guideline) -> R) -> PyResult<R> {
+        read(
+            &self.glyph,
+            "guideline",
+            self.index,
+            |d| d.guidelines.get(self.index),
+            f,
+        )
+    }
+
+    fn set(&self, f: impl FnOnce(&mut Guideline)) -> PyResult<()> {
+        write(
+            &self.glyph,
+            "guideline",
+            self.index,
+            |d| d.guidelines.get_mut(self.index),
+            f,
+        )
+    }
+}
+
+#[pymethods]
+impl PyGuideline {
+    #[getter]
+    pub fn x(&self) -> PyResult<Option<f64>> {
+        self.get(|g| g.x)
+    }
+
+    #[setter]
+    pub fn set_x(&self, x: Option<f64>) -> PyResult<()> {
+        self.set(|g| g.x = x)
+    }
+
+    #[getter]
+    pub fn y(&self) -> PyResult<Option<f64>> {
+        self.get(|g| g.y)
+    }
+
+    #[setter]
+    pub fn set_y(&self, y: Option<f64>) -> PyResult<()> {
+        self.set(|g| g.y = y)
+    }
+
+    #[getter]
+    pub fn angle(&self) -> PyResult<Option<f64>> {
+        self.get(|g| g.angle)
+    }
+
+    #[setter]
+    pub fn set_angle(&self, angle: Option<f64>) -> PyResult<()> {
+        self.set(|g| g.angle = angle)
+    }
+
+    #[getter]
+    pub fn name(&self) -> PyResult<Option<String>> {
+        self.get(|g| g.name.clone())
+    }
+
+    #[setter]
+    pub fn set_name(&self, name: Option<String>) -> PyResult<()> {
+        self.set(|g| g.name = name)
+    }
+
+    /// Get or set the color as an `(r, g, b, a)` tuple.
+    #[getter]
+    pub fn color(&self) -> PyResult<Option<RgbaTuple>> {
+        self.get(|g| g.color.map(|c| c.to_tuple()))
+    }
+
+    #[setter]
+    pub fn set_color(&self, color: Option<RgbaTuple>) -> PyResult<()> {
+        let color = to_color(color)?;
+        self.set(|g| {
+            g.color = color;
+            g.invalid_color = None;
+        })
+    }
+
+    #[getter]
+    pub fn identifier(&self) -> PyResult<Option<String>> {
+        self.get(|g| g.identifier.clone())
+    }
+
+    #[setter]
+    pub fn set_identifier(&self, identifier: Option<String>) -> PyResult<()> {
+        self.set(|g| g.identifier = identifier)
+    }
+}
+
+/// A view of a component in a glyph's outline.
+#[pyclass(unsendable, name = "Component")]
+pub struct PyComponent {
+    pub glyph: Rc<Glyph>,
+    pub index: usize,
+}
+
+impl PyComponent {
+    fn get<R>(&self, f: impl FnOnce(&Component) -> R) -> PyResult<R> {
+        read(
+            &self.glyph,
+            "component",
+            self.index,
+            |d: &GlifData| d.outline.as_ref()?.components.get(self.index),
+            f,
+        )
+    }
+
+    fn set(&self, f: impl FnOnce(&mut Component)) -> PyResult<()> {
+        write(
+            &self.glyph,
+            "component",
+            self.index,
+            |d: &mut GlifData| d.outline.as_mut()?.components.get_mut(self.index),
+            f,
+        )
+    }
+}
+
+#[pymethods]
+impl PyComponent {
+    #[getter]
+    pub fn base(&self) -> PyResult<Option<String>> {
+        self.get(|c| c.base.clone())
+    }
+
+    #[setter]
+    pub fn set_base(&self, base: Option<String>) -> PyResult<()> {
+        self.set(|c| c.base = base)
+    }
+
+    #[getter]
+    pub fn x_scale(&self) -> PyResult<Option<f64>> {
+        self.get(|c| c.x_scale)
+    }
+
+    #[setter]
+    pub fn set_x_scale(&self, value: Option<f64>) -> PyResult<()> {
+        self.set(|c| c.x_scale = value)
+    }
+
+    #[getter]
+    pub fn xy_scale(&self) -> PyResult<Option<f64>> {
+        self.get(|c| c.xy_scale)
+    }
+
+    #[setter]
+    pub fn set_xy_scale(&self, value: Option<f64>) -> PyResult<()> {
+        self.set(|c| c.xy_scale = value)
+    }
+
+    #[getter]
+    pub fn yx_scale(&self) -> PyResult<Option<f64>> {
+        self.get(|c| c.yx_scale)
+    }
+
+    #[setter]
+    pub fn set_yx_scale(&self, value: Option<f64>) -> PyResult<()> {
+        self.set(|c| c.yx_scale = value)
+    }
+
+    #[getter]
+    pub fn y_scale(&self) -> PyResult<Option<f64>> {
+        self.get(|c| c.y_scale)
+    }
+
+    #[setter]
+    pub fn set_y_scale(&self, value: Option<f64>) -> PyResult<()> {
+        self.set(|c| c.y_scale = value)
+    }
+
+    #[getter]
+    pub fn x_offset(&self) -> PyResult<Option<f64>> {
+        self.get(|c| c.x_offset)
+    }
+
+    #[setter]
+    pub fn set_x_offset(&self, value: Option<f64>) -> PyResult<()> {
+        self.set(|c| c.x_offset = value)
+    }
+
+    #[getter]
+    pub fn y_offset(&self) -> PyResult<Option<f64>> {
+        self.get(|c| c.y_offset)
+    }
+
+    #[setter]
+    pub fn set_y_offset(&self, value: Option<f64>) -> PyResult<()> {
+        self.set(|c| c.y_offset = value)
+    }
+
+    #[getter]
+    pub fn identifier(&self) -> PyResult<Option<String>> {
+        self.get(|c| c.identifier.clone())
+    }
+
+    #[setter]
+    pub fn set_identifier(&self, identifier: Option<String>) -> PyResult<()> {
+        self.set(|c| c.identifier = identifier)
+    }
+}
+
+/// A view of a contour in a glyph's outline.
+#[pyclass(unsendable, name = "Contour")]
+pub struct PyContour {
+    pub glyph: Rc<Glyph>,
+    pub index: usize,
+}
+
+impl PyContour {
+    fn get<R>(&self, f: impl FnOnce(&Contour) -> R) -> PyResult<R> {
+        read(
+            &self.glyph,
+            "contour",
+            self.index,
+            |d: &GlifData| d.outline.as_ref()?.contours.get(self.index),
+            f,
+        )
+    }
+
+    fn set(&self, f: impl FnOnce(&mut Contour)) -> PyResult<()> {
+        write(
+            &self.glyph,
+            "contour",
+            self.index,
+            |d: &mut GlifData| d.outline.as_mut()?.contours.get_mut(self.index),
+            f,
+        )
+    }
+}
+
+#[pymethods]
+impl PyContour {
+    #[getter]
+    pub fn identifier(&self) -> PyResult<Option<String>> {
+        self.get(|c| c.identifier.clone())
+    }
+
+    #[setter]
+    pub fn set_identifier(&self, identifier: Option<String>) -> PyResult<()> {
+        self.set(|c| c.identifier = identifier)
+    }
+
+    /// Get the contour's points.
+    #[getter]
+    pub fn points(&self) -> PyResult<Vec<PyPoint>> {
+        let len = self.get(|c| c.points.len())?;
+        Ok((0..len)
+            .map(|index| PyPoint {
+                glyph: Rc::clone(&self.glyph),
+                contour: self.index,
+                index,
+            })
+            .collect())
+    }
+
+    fn __len__(&self) -> PyResult<usize> {
+        self.get(|c| c.points.len())
+    }
+
+    /// Append a point to the contour and return it.
+    #[pyo3(signature = (x, y, r#type = None, smooth = false, name = None, identifier = None))]
+    pub fn add_point(
+        &self,
+        x: f64,
+        y: f64,
+        r#type: Option<String>,
+        smooth: bool,
+        name: Option<String>,
+        identifier: Option<String>,
+    ) -> PyResult<PyPoint> {
+        let point = Point {
+            x: Some(x),
+            y: Some(y),
+            point_type: r#type.map(PointType::from),
+            smooth: smooth.then_some(true),
+            name,
+            identifier,
+        };
+        let mut index = 0;
+        self.set(|c| {
+            c.points.push(point);
+            index = c.points.len() - 1;
+        })?;
+        Ok(PyPoint {
+            glyph: Rc::clone(&self.glyph),
+            contour: self.index,
+            index,
+        })
+    }
+
+    /// Remove the point at `index`.
+    pub fn remove_point(&self, index: usize) -> PyResult<()> {
+        let mut removed = false;
+        self.set(|c| {
+            if index < c.points.len() {
+                c.points.remove(index);
+                removed = true;
+            }
+        })?;
+        removed.then_some(()).ok_or_else(|| stale("point", index))
+    }
+}
+
+/// A view of a point in a contour.
+#[pyclass(unsendable, name = "Point")]
+pub struct PyPoint {
+    pub glyph: Rc<Glyph>,
+    pub contour: usize,
+    pub index: usize,
+}
+
+impl PyPoint {
+    fn get<R>(&self, f: impl FnOnce(&Point) -> R) -> PyResult<R> {
+        read(
+            &self.glyph,
+            "point",
+            self.index,
+            |d: &GlifData| {
+                d.outline
+                    .as_ref()?
+                    .contours
+                    .get(self.contour)?
+                    .points
+                    .get(self.index)
+            },
+            f,
+        )
+    }
+
+    fn set(&self, f: impl FnOnce(&mut Point)) -> PyResult<()> {
+        write(
+            &self.glyph,
+            "point",
+            self.index,
+            |d: &mut GlifData| {
+                d.outline
+                    .as_mut()?
+                    .contours
+                    .get_mut(self.contour)?
+                    .points
+                    .get_mut(self.index)
+            },
+            f,
+        )
+    }
+}
+
+#[pymethods]
+impl PyPoint {
+    #[getter]
+    pub fn x(&self) -> PyResult<Option<f64>> {
+        self.get(|p| p.x)
+    }
+
+    #[setter]
+    pub fn set_x(&self, x: Option<f64>) -> PyResult<()> {
+        self.set(|p| p.x = x)
+    }
+
+    #[getter]
+    pub fn y(&self) -> PyResult<Option<f64>> {
+        self.get(|p| p.y)
+    }
+
+    #[setter]
+    pub fn set_y(&self, y: Option<f64>) -> PyResult<()> {
+        self.set(|p| p.y = y)
+    }
+
+    /// Get or set the point type as its GLIF attribute value, e.g. `"curve"`.
+    #[getter]
+    pub fn r#type(&self) -> PyResult<Option<String>> {
+        self.get(|p| p.point_type.as_ref().map(|t| t.as_str().to_owned()))
+    }
+
+    #[setter]
+    pub fn set_type(&self, point_type: Option<String>) -> PyResult<()> {
+        self.set(|p| p.point_type = point_type.map(PointType::from))
+    }
+
+    #[getter]
+    pub fn smooth(&self) -> PyResult<Option<bool>> {
+        self.get(|p| p.smooth)
+    }
+
+    #[setter]
+    pub fn set_smooth(&self, smooth: Option<bool>) -> PyResult<()> {
+        self.set(|p| p.smooth = smooth)
+    }
+
+    #[getter]
+    pub fn name(&self) -> PyResult<Option<String>> {
+        self.get(|p| p.name.clone())
+    }
+
+    #[setter]
+    pub fn set_name(&self, name: Option<String>) -> PyResult<()> {
+        self.set(|p| p.name = name)
+    }
+
+    #[getter]
+    pub fn identifier(&self) -> PyResult<Option<String>> {
+        self.get(|p| p.identifier.clone())
+    }
+
+    #[setter]
+    pub fn set_identifier(&self, identifier: Option<String>) -> PyResult<()> {
+        self.set(|p| p.identifier = identifier)
+    }
+}
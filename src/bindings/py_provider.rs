@@ -1,11 +1,12 @@
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::{Duration, SystemTime};
 
 use pyo3::prelude::*;
 
 use crate::bindings::PyErrExt;
 use crate::error::Result;
-use crate::provider::{FileProvider, Provider};
+use crate::provider::{FileProvider, Provider, WritableProvider};
 
 pub(crate) struct PyProvider {
     core_root: PathBuf,
@@ -19,6 +20,11 @@ impl PyProvider {
             py_root,
         }))
     }
+
+    /// Returns `true` if the Python object has a method called `name`.
+    fn has_method(&self, name: &str) -> bool {
+        Python::with_gil(|py| self.py_root.bind(py).hasattr(name).unwrap_or(false))
+    }
 }
 
 impl Provider for PyProvider {
@@ -37,6 +43,106 @@ impl Provider for PyProvider {
             Ok(bytes)
         })
     }
+
+    /// Calls the Python object's `exists` method if it has one, and falls back to attempting
+    /// a `read` otherwise.
+    fn exists(&self, path: &Path) -> bool {
+        if !self.has_method("exists") {
+            return self.read(path).is_ok();
+        }
+        Python::with_gil(|py| {
+            self.py_root
+                .call_method1(py, "exists", (path.to_string_lossy().as_ref(),))
+                .and_then(|result| result.extract::<bool>(py))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Calls the Python object's `list_dir(path)` method, which should return a list of file
+    /// names.
+    fn list_dir(&self, path: &Path) -> Result<Vec<String>> {
+        Python::with_gil(|py| {
+            let result = self
+                .py_root
+                .call_method1(py, "list_dir", (path.to_string_lossy().as_ref(),))
+                .map_err(|e| e.to_ufo())?;
+            let mut names: Vec<String> = result.extract(py).map_err(|e| e.to_ufo())?;
+            names.sort();
+            Ok(names)
+        })
+    }
+
+    /// Calls the Python object's `modified(path)` method if it has one, which should return a
+    /// POSIX timestamp or `None`.
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        if !self.has_method("modified") {
+            return None;
+        }
+        Python::with_gil(|py| {
+            let seconds = self
+                .py_root
+                .call_method1(py, "modified", (path.to_string_lossy().as_ref(),))
+                .and_then(|result| result.extract::<Option<f64>>(py))
+                .ok()??;
+            SystemTime::UNIX_EPOCH.checked_add(Duration::try_from_secs_f64(seconds).ok()?)
+        })
+    }
+
+    /// Returns `Some` if the Python object has a `write` method.
+    fn as_writable(&self) -> Option<&dyn WritableProvider> {
+        self.has_method("write")
+            .then_some(self as &dyn WritableProvider)
+    }
+}
+
+/// Writes through the Python object's `write(path, data)`, `remove(path)` and
+/// `create_dir(path)` methods. `rename(from, to)` is used if present.
+impl WritableProvider for PyProvider {
+    fn write(&self, path: &Path, data: &[u8]) -> Result<()> {
+        Python::with_gil(|py| {
+            let data = pyo3::types::PyBytes::new(py, data);
+            self.py_root
+                .call_method1(py, "write", (path.to_string_lossy().as_ref(), data))
+                .map_err(|e| e.to_ufo())?;
+            Ok(())
+        })
+    }
+
+    fn remove(&self, path: &Path) -> Result<()> {
+        Python::with_gil(|py| {
+            self.py_root
+                .call_method1(py, "remove", (path.to_string_lossy().as_ref(),))
+                .map_err(|e| e.to_ufo())?;
+            Ok(())
+        })
+    }
+
+    fn create_dir(&self, path: &Path) -> Result<()> {
+        Python::with_gil(|py| {
+            self.py_root
+                .call_method1(py, "create_dir", (path.to_string_lossy().as_ref(),))
+                .map_err(|e| e.to_ufo())?;
+            Ok(())
+        })
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        if !self.has_method("rename") {
+            let data = self.read(from)?;
+            self.write(to, &data)?;
+            return self.remove(from);
+        }
+        Python::with_gil(|py| {
+            let args = (
+                from.to_string_lossy().into_owned(),
+                to.to_string_lossy().into_owned(),
+            );
+            self.py_root
+                .call_method1(py, "rename", args)
+                .map_err(|e| e.to_ufo())?;
+            Ok(())
+        })
+    }
 }
 
 impl Clone for PyProvider {
@@ -63,7 +169,7 @@ impl PyFileProvider {
     #[new]
     pub fn new(root: String) -> Self {
         let inner = FileProvider::new(root);
-        Self { inner: inner }
+        Self { inner }
     }
 }
 
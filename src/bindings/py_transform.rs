@@ -0,0 +1,115 @@
+use pyo3::prelude::*;
+
+use crate::bindings::PyGlifData;
+use crate::transform::Transform;
+
+/// Represent a 2x3 affine transformation matrix.
+///
+/// The components follow the order of the GLIF transformation attributes:
+/// `(xScale, xyScale, yxScale, yScale, xOffset, yOffset)`.
+#[pyclass(frozen, name = "Transform")]
+#[derive(Clone)]
+pub struct PyTransform {
+    pub inner: Transform,
+}
+
+#[pymethods]
+impl PyTransform {
+    /// Create a new `Transform`. Defaults to the identity transformation.
+    #[new]
+    #[pyo3(signature = (xx = 1.0, xy = 0.0, yx = 0.0, yy = 1.0, dx = 0.0, dy = 0.0))]
+    pub fn new(xx: f64, xy: f64, yx: f64, yy: f64, dx: f64, dy: f64) -> Self {
+        Self {
+            inner: Transform::new(xx, xy, yx, yy, dx, dy),
+        }
+    }
+
+    /// Get the x scale.
+    #[getter]
+    pub fn xx(&self) -> f64 {
+        self.inner.xx
+    }
+
+    /// Get the xy scale.
+    #[getter]
+    pub fn xy(&self) -> f64 {
+        self.inner.xy
+    }
+
+    /// Get the yx scale.
+    #[getter]
+    pub fn yx(&self) -> f64 {
+        self.inner.yx
+    }
+
+    /// Get the y scale.
+    #[getter]
+    pub fn yy(&self) -> f64 {
+        self.inner.yy
+    }
+
+    /// Get the x offset.
+    #[getter]
+    pub fn dx(&self) -> f64 {
+        self.inner.dx
+    }
+
+    /// Get the y offset.
+    #[getter]
+    pub fn dy(&self) -> f64 {
+        self.inner.dy
+    }
+
+    /// Return the determinant of the linear part of the matrix.
+    pub fn determinant(&self) -> f64 {
+        self.inner.determinant()
+    }
+
+    /// Return a transform that applies `other` first and then this transform.
+    pub fn compose(&self, other: &PyTransform) -> Self {
+        Self {
+            inner: self.inner.compose(&other.inner),
+        }
+    }
+
+    /// Return a transform that applies this transform first and then `other`.
+    pub fn then(&self, other: &PyTransform) -> Self {
+        Self {
+            inner: self.inner.then(&other.inner),
+        }
+    }
+
+    /// Return the inverse transform, or `None` if the matrix is degenerate.
+    pub fn invert(&self) -> Option<Self> {
+        self.inner.invert().map(|inner| Self { inner })
+    }
+
+    /// Apply the transform to the point `(x, y)`.
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        self.inner.apply(x, y)
+    }
+
+    /// Decompose the transform into a dict of translation, rotation, scale and skew.
+    ///
+    /// Angles are given in degrees.
+    pub fn decompose(&self, py: Python<'_>) -> PyResult<PyObject> {
+        PyGlifData::to_pyobject(py, &self.inner.decompose())
+    }
+
+    /// Return the matrix components as a tuple.
+    pub fn to_tuple(&self) -> (f64, f64, f64, f64, f64, f64) {
+        self.inner.to_tuple()
+    }
+
+    fn __eq__(&self, other: &PyTransform) -> bool {
+        self.inner == other.inner
+    }
+
+    fn __repr__(&self) -> String {
+        let t = &self.inner;
+        format!(
+            "Transform({}, {}, {}, {}, {}, {})",
+            t.xx, t.xy, t.yx, t.yy, t.dx, t.dy
+        )
+    }
+}
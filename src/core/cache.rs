@@ -0,0 +1,142 @@
+//! Glyph cache policies and least-recently-used bookkeeping for [`crate::font::Font`].
+
+use std::collections::{BTreeMap, HashMap};
+
+/// Controls how much parsed glyph data a [`crate::font::Font`] keeps in memory.
+///
+/// Bounded policies evict the least recently used glyphs once the limit is exceeded. Glyphs with
+/// unsaved edits are never evicted, but count towards the limit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CachePolicy {
+    /// Keep every glyph that has been loaded.
+    #[default]
+    Unbounded,
+    /// Keep at most this many parsed glyphs.
+    Glyphs(usize),
+    /// Keep parsed glyphs up to this many points in total, counting each component as one
+    /// point.
+    Points(usize),
+    /// Do not keep parsed glyphs; every access reads the `.glif` file again.
+    None,
+}
+
+impl CachePolicy {
+    /// Returns `true` if a cache holding `glyphs` glyphs with `points` points exceeds the policy.
+    fn exceeded(&self, glyphs: usize, points: usize) -> bool {
+        match *self {
+            Self::Unbounded => false,
+            Self::Glyphs(max) => glyphs > max,
+            Self::Points(max) => points > max,
+            Self::None => glyphs > 0,
+        }
+    }
+}
+
+/// Tracks which glyphs are cached, in order of use, and their cost under [`CachePolicy::Points`].
+#[derive(Clone, Debug, Default)]
+pub(crate) struct CacheTracker {
+    policy: CachePolicy,
+    tick: u64,
+    order: BTreeMap<u64, String>,
+    entries: HashMap<String, (u64, usize)>,
+    points: usize,
+}
+
+impl CacheTracker {
+    pub fn policy(&self) -> CachePolicy {
+        self.policy
+    }
+
+    pub fn set_policy(&mut self, policy: CachePolicy) {
+        self.policy = policy;
+    }
+
+    /// Returns the number of tracked glyphs.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Marks `name` as the most recently used glyph, with the given point count.
+    pub fn touch(&mut self, name: &str, points: usize) {
+        self.remove(name);
+        self.tick += 1;
+        self.order.insert(self.tick, name.to_owned());
+        self.entries.insert(name.to_owned(), (self.tick, points));
+        self.points += points;
+    }
+
+    /// Stops tracking all glyphs, keeping the policy.
+    pub fn clear(&mut self) {
+        self.order.clear();
+        self.entries.clear();
+        self.points = 0;
+    }
+
+    /// Stops tracking `name`.
+    pub fn remove(&mut self, name: &str) {
+        if let Some((tick, points)) = self.entries.remove(name) {
+            self.order.remove(&tick);
+            self.points -= points;
+        }
+    }
+
+    /// Returns the glyphs to evict to satisfy the policy, least recently used first.
+    ///
+    /// Glyphs for which `pinned` returns `true` are kept regardless of the policy.
+    pub fn overflow(&self, pinned: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut glyphs = self.entries.len();
+        let mut points = self.points;
+        let mut evict = Vec::new();
+        for name in self.order.values() {
+            if !self.policy.exceeded(glyphs, points) {
+                break;
+            }
+            if pinned(name) {
+                continue;
+            }
+            glyphs -= 1;
+            points -= self.entries[name].1;
+            evict.push(name.clone());
+        }
+        evict
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overflow_glyphs() {
+        let mut tracker = CacheTracker::default();
+        tracker.set_policy(CachePolicy::Glyphs(2));
+        tracker.touch("a", 1);
+        tracker.touch("b", 1);
+        tracker.touch("c", 1);
+        assert_eq!(tracker.overflow(|_| false), vec!["a"]);
+
+        tracker.touch("a", 1);
+        assert_eq!(tracker.overflow(|_| false), vec!["b"]);
+        assert_eq!(tracker.overflow(|name| name == "b"), vec!["c"]);
+
+        tracker.remove("b");
+        assert!(tracker.overflow(|_| false).is_empty());
+        assert_eq!(tracker.len(), 2);
+    }
+
+    #[test]
+    fn test_overflow_points() {
+        let mut tracker = CacheTracker::default();
+        tracker.touch("a", 10);
+        tracker.touch("b", 20);
+        tracker.touch("c", 5);
+        assert!(tracker.overflow(|_| false).is_empty());
+
+        tracker.set_policy(CachePolicy::Points(25));
+        assert_eq!(tracker.overflow(|_| false), vec!["a"]);
+        tracker.set_policy(CachePolicy::Points(24));
+        assert_eq!(tracker.overflow(|_| false), vec!["a", "b"]);
+        tracker.set_policy(CachePolicy::None);
+        assert_eq!(tracker.overflow(|name| name == "c"), vec!["a", "b"]);
+    }
+}
@@ -0,0 +1,97 @@
+//! Unicode character map queries over a font's glyphs.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+/// A map of Unicode code points to the names of the glyphs that claim them, in glyph order.
+///
+/// A code point mapped to more than one glyph is a conflict: a compiled font can map it to only
+/// one of them.
+pub type Cmap = BTreeMap<u32, Vec<String>>;
+
+/// Builds a [`Cmap`] from glyph names and their code points, given in glyph order.
+pub fn build_cmap<'a>(glyphs: impl IntoIterator<Item = (&'a str, &'a [u32])>) -> Cmap {
+    let mut cmap = Cmap::new();
+    for (name, unicodes) in glyphs {
+        for &unicode in unicodes {
+            let names = cmap.entry(unicode).or_default();
+            if !names.iter().any(|n| n == name) {
+                names.push(name.to_owned());
+            }
+        }
+    }
+    cmap
+}
+
+/// Returns the entries of `cmap` whose code point is claimed by more than one glyph.
+pub fn conflicts(cmap: &Cmap) -> Cmap {
+    cmap.iter()
+        .filter(|(_, names)| names.len() > 1)
+        .map(|(&unicode, names)| (unicode, names.clone()))
+        .collect()
+}
+
+/// The result of checking a font's character set against a set of code points.
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct Coverage {
+    /// Requested code points that are mapped to a glyph, in ascending order.
+    pub covered: Vec<u32>,
+    /// Requested code points that are not mapped to any glyph, in ascending order.
+    pub missing: Vec<u32>,
+}
+
+impl Coverage {
+    /// Checks which of `codepoints` are mapped in `cmap`. Duplicates are counted once.
+    pub fn new(cmap: &Cmap, codepoints: impl IntoIterator<Item = u32>) -> Self {
+        let mut codepoints = codepoints.into_iter().collect::<Vec<_>>();
+        codepoints.sort_unstable();
+        codepoints.dedup();
+        let (covered, missing) = codepoints
+            .into_iter()
+            .partition(|unicode| cmap.contains_key(unicode));
+        Self { covered, missing }
+    }
+
+    /// Returns the fraction of the requested code points that are covered, or `1.0` if none
+    /// were requested.
+    pub fn ratio(&self) -> f64 {
+        let total = self.covered.len() + self.missing.len();
+        if total == 0 {
+            return 1.0;
+        }
+        self.covered.len() as f64 / total as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_cmap_and_conflicts() {
+        let cmap = build_cmap([
+            ("A", &[0x41][..]),
+            ("A.alt", &[0x41, 0x41][..]),
+            ("space", &[0x20, 0xA0][..]),
+            (".notdef", &[][..]),
+        ]);
+        assert_eq!(cmap[&0x41], vec!["A", "A.alt"]);
+        assert_eq!(cmap[&0xA0], vec!["space"]);
+        assert_eq!(cmap.len(), 3);
+        assert_eq!(
+            conflicts(&cmap).into_iter().collect::<Vec<_>>(),
+            vec![(0x41, vec!["A".to_string(), "A.alt".to_string()])]
+        );
+    }
+
+    #[test]
+    fn test_coverage() {
+        let cmap = build_cmap([("A", &[0x41][..]), ("B", &[0x42][..])]);
+        let coverage = Coverage::new(&cmap, [0x43, 0x41, 0x41, 0x42, 0x44]);
+        assert_eq!(coverage.covered, vec![0x41, 0x42]);
+        assert_eq!(coverage.missing, vec![0x43, 0x44]);
+        assert_eq!(coverage.ratio(), 0.5);
+        assert_eq!(Coverage::new(&cmap, []).ratio(), 1.0);
+    }
+}
@@ -0,0 +1,116 @@
+//! UFO color values.
+//!
+//! Colors are stored in UFO files as strings of four comma-separated numbers in the range
+//! `0..=1`, representing the red, green, blue and alpha channels, e.g. `"1,0.75,0,0.7"`.
+
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Serialize, Serializer};
+
+use crate::error::{Error, ErrorKind, Result};
+
+/// An RGBA color as defined by the UFO specification.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Color {
+    pub r: f64,
+    pub g: f64,
+    pub b: f64,
+    pub a: f64,
+}
+
+impl Color {
+    /// Creates a new color, validating that each component is in the range `0..=1`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::Parse`] error if any component is out of range or not a number.
+    pub fn new(r: f64, g: f64, b: f64, a: f64) -> Result<Self> {
+        for value in [r, g, b, a] {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(Error::new(ErrorKind::Parse)
+                    .with_context(move || format!("color component {} is not in 0..1", value)));
+            }
+        }
+        Ok(Self { r, g, b, a })
+    }
+
+    /// Returns the components as an `(r, g, b, a)` tuple.
+    pub fn to_tuple(&self) -> (f64, f64, f64, f64) {
+        (self.r, self.g, self.b, self.a)
+    }
+}
+
+impl FromStr for Color {
+    type Err = Error;
+
+    /// Parses a color from the UFO `"r,g,b,a"` string format.
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || {
+            let s = s.to_owned();
+            Error::new(ErrorKind::Parse).with_context(move || format!("invalid color '{}'", s))
+        };
+
+        let components = s
+            .split(',')
+            .map(|c| c.trim().parse::<f64>())
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| invalid().with_cause(e))?;
+
+        match components[..] {
+            [r, g, b, a] => Self::new(r, g, b, a).map_err(|_| invalid()),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    /// Formats the color in its canonical `"r,g,b,a"` form, without trailing zeros.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{},{},{},{}", self.r, self.g, self.b, self.a)
+    }
+}
+
+impl Serialize for Color {
+    /// Serializes the color as its canonical string.
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_color() {
+        let color: Color = "1,0.75,0,0.7".parse().unwrap();
+        assert_eq!(color.to_tuple(), (1.0, 0.75, 0.0, 0.7));
+
+        let color: Color = " 0 , .5 ,1.0, 1 ".parse().unwrap();
+        assert_eq!(color.to_tuple(), (0.0, 0.5, 1.0, 1.0));
+    }
+
+    #[test]
+    fn test_parse_color_invalid() {
+        for s in [
+            "",
+            "1,0,0",
+            "1,0,0,1,0",
+            "red",
+            "1,0,0,1.5",
+            "-0.1,0,0,1",
+            "1,0,0,nan",
+        ] {
+            let err = s.parse::<Color>().unwrap_err();
+            assert_eq!(err.kind(), &ErrorKind::Parse, "{:?}", s);
+        }
+    }
+
+    #[test]
+    fn test_color_canonical_string() {
+        let color: Color = "1.0,0.50,0,0.700".parse().unwrap();
+        assert_eq!(color.to_string(), "1,0.5,0,0.7");
+        assert_eq!(serde_json::to_value(color).unwrap(), "1,0.5,0,0.7");
+    }
+}
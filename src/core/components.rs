@@ -0,0 +1,131 @@
+//! Validation of component references.
+
+use serde::Serialize;
+
+use crate::glif::Component;
+use crate::transform::Transform;
+
+/// A problem with a component found by [`check_components`].
+///
+/// `index` is the position of the component in the glyph's outline.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ComponentIssue {
+    /// The component refers to a glyph that is not in the font.
+    MissingBase {
+        glyph: String,
+        index: usize,
+        base: String,
+    },
+    /// The component has no `base` attribute.
+    NoBase { glyph: String, index: usize },
+    /// The component's transform has a zero determinant, which collapses the base glyph to a
+    /// line or a point.
+    DegenerateTransform {
+        glyph: String,
+        index: usize,
+        base: Option<String>,
+    },
+    /// The glyph cannot be parsed, so its components were not checked.
+    Unreadable { glyph: String, error: String },
+}
+
+/// Checks the components of the glyph named `glyph`.
+///
+/// `exists` reports whether a base glyph name is in the font. Issues are returned in component
+/// order; an empty list means all components are valid.
+pub fn check_components(
+    glyph: &str,
+    components: &[Component],
+    exists: impl Fn(&str) -> bool,
+) -> Vec<ComponentIssue> {
+    let mut issues = Vec::new();
+    for (index, component) in components.iter().enumerate() {
+        match &component.base {
+            None => issues.push(ComponentIssue::NoBase {
+                glyph: glyph.to_owned(),
+                index,
+            }),
+            Some(base) if !exists(base) => issues.push(ComponentIssue::MissingBase {
+                glyph: glyph.to_owned(),
+                index,
+                base: base.clone(),
+            }),
+            Some(_) => {}
+        }
+        if Transform::from(component).determinant() == 0.0 {
+            issues.push(ComponentIssue::DegenerateTransform {
+                glyph: glyph.to_owned(),
+                index,
+                base: component.base.clone(),
+            });
+        }
+    }
+    issues
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn component(base: Option<&str>) -> Component {
+        Component {
+            base: base.map(str::to_owned),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_check_components() {
+        let components = [
+            component(Some("A")),
+            component(Some("missing")),
+            component(None),
+            Component {
+                x_scale: Some(2.0),
+                xy_scale: Some(1.0),
+                yx_scale: Some(4.0),
+                y_scale: Some(2.0),
+                ..component(Some("A"))
+            },
+            Component {
+                y_scale: Some(0.0),
+                ..component(Some("gone"))
+            },
+        ];
+        let issues = check_components("Aacute", &components, |name| name == "A");
+        assert_eq!(
+            issues,
+            vec![
+                ComponentIssue::MissingBase {
+                    glyph: "Aacute".into(),
+                    index: 1,
+                    base: "missing".into()
+                },
+                ComponentIssue::NoBase {
+                    glyph: "Aacute".into(),
+                    index: 2
+                },
+                ComponentIssue::DegenerateTransform {
+                    glyph: "Aacute".into(),
+                    index: 3,
+                    base: Some("A".into())
+                },
+                ComponentIssue::MissingBase {
+                    glyph: "Aacute".into(),
+                    index: 4,
+                    base: "gone".into()
+                },
+                ComponentIssue::DegenerateTransform {
+                    glyph: "Aacute".into(),
+                    index: 4,
+                    base: Some("gone".into())
+                },
+            ]
+        );
+    }
+}
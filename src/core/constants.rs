@@ -6,6 +6,33 @@ pub mod ufo {
         pub const PUBLIC_KERN1_PREFIX: &str = "public.kern1.";
         pub const PUBLIC_KERN2_PREFIX: &str = "public.kern2.";
     }
+    pub mod lib_key {
+        pub const PUBLIC_GLYPH_ORDER: &str = "public.glyphOrder";
+        pub const PUBLIC_MARK_COLOR: &str = "public.markColor";
+        pub const PUBLIC_OBJECT_LIBS: &str = "public.objectLibs";
+        pub const PUBLIC_OPENTYPE_CATEGORIES: &str = "public.openTypeCategories";
+        pub const PUBLIC_POSTSCRIPT_NAMES: &str = "public.postscriptNames";
+        pub const PUBLIC_SKIP_EXPORT_GLYPHS: &str = "public.skipExportGlyphs";
+        pub const PUBLIC_UNICODE_VARIATION_SEQUENCES: &str = "public.unicodeVariationSequences";
+        pub const PUBLIC_VERTICAL_ORIGIN: &str = "public.verticalOrigin";
+        pub const LINT_IGNORE: &str = "com.github.knutnergaard.ufoGleaner.lintIgnore";
+    }
+    pub mod layerinfo_key {
+        pub const COLOR: &str = "color";
+        pub const LIB: &str = "lib";
+    }
+    /// Default values defined by the GLIF specification for omitted attributes.
+    pub mod glif_default {
+        pub const ADVANCE_WIDTH: f64 = 0.0;
+        pub const ADVANCE_HEIGHT: f64 = 0.0;
+        pub const X_SCALE: f64 = 1.0;
+        pub const XY_SCALE: f64 = 0.0;
+        pub const YX_SCALE: f64 = 0.0;
+        pub const Y_SCALE: f64 = 1.0;
+        pub const X_OFFSET: f64 = 0.0;
+        pub const Y_OFFSET: f64 = 0.0;
+        pub const SMOOTH: bool = false;
+    }
 }
 
 pub mod xml {
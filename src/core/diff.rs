@@ -0,0 +1,731 @@
+//! Structural comparison of glyphs and fonts.
+//!
+//! Unlike a textual diff of GLIF XML, the changes reported here ignore formatting and attribute
+//! order, and describe what changed in the terms of the glyph model: advances, code points,
+//! anchors, components, contours, points and lib keys.
+
+use std::fmt;
+use std::rc::Rc;
+
+use indexmap::IndexMap;
+use plist::Dictionary;
+use serde::Serialize;
+
+use crate::error::Result;
+use crate::font::Font;
+use crate::glif::{Anchor, Component, Contour, GlifData, Point};
+use crate::transform::Transform;
+
+/// The changes between two fonts, as returned by [`diff_fonts`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+pub struct FontDiff {
+    /// Glyphs only in the new font, in its glyph order.
+    pub added: Vec<String>,
+    /// Glyphs only in the old font, in its glyph order.
+    pub removed: Vec<String>,
+    /// Glyphs in both fonts that differ, in the old font's glyph order.
+    pub changed: IndexMap<String, GlyphDiff>,
+}
+
+impl FontDiff {
+    /// Returns `true` if the fonts have the same glyphs with the same content.
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    /// Serializes the diff as a JSON object with `added`, `removed` and `changed` keys.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// Formats the diff as text, with one line per added (`+`), removed (`-`) or changed (`~`)
+/// glyph, followed by its indented changes.
+impl fmt::Display for FontDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for name in &self.added {
+            writeln!(f, "+ {name}")?;
+        }
+        for name in &self.removed {
+            writeln!(f, "- {name}")?;
+        }
+        for (name, diff) in &self.changed {
+            writeln!(f, "~ {name}")?;
+            for change in &diff.changes {
+                writeln!(f, "    {change}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The changes between two versions of a glyph, as returned by [`diff_glyphs`].
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
+#[serde(transparent)]
+pub struct GlyphDiff {
+    pub changes: Vec<Change>,
+}
+
+impl GlyphDiff {
+    /// Returns `true` if no changes were found.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Serializes the changes as a JSON array.
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+}
+
+/// Formats the changes as text, one per line.
+impl fmt::Display for GlyphDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A single difference between two versions of a glyph.
+///
+/// Anchors are matched by identifier, then by name, and labeled by their name, or by their
+/// identifier if they have no name. Omitted attributes are compared by their specification
+/// defaults.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum Change {
+    AdvanceWidth {
+        old: f64,
+        new: f64,
+    },
+    AdvanceHeight {
+        old: f64,
+        new: f64,
+    },
+    Unicodes {
+        old: Vec<u32>,
+        new: Vec<u32>,
+    },
+    AnchorAdded {
+        anchor: String,
+        position: (f64, f64),
+    },
+    AnchorRemoved {
+        anchor: String,
+        position: (f64, f64),
+    },
+    AnchorMoved {
+        anchor: String,
+        old: (f64, f64),
+        new: (f64, f64),
+    },
+    /// An anchor kept its identifier but changed its name.
+    AnchorRenamed {
+        old: String,
+        new: String,
+    },
+    ComponentAdded {
+        index: usize,
+        base: Option<String>,
+    },
+    ComponentRemoved {
+        index: usize,
+        base: Option<String>,
+    },
+    ComponentBase {
+        index: usize,
+        old: Option<String>,
+        new: Option<String>,
+    },
+    ComponentTransform {
+        index: usize,
+        base: Option<String>,
+        old: Transform,
+        new: Transform,
+    },
+    ContourAdded {
+        index: usize,
+        points: usize,
+    },
+    ContourRemoved {
+        index: usize,
+        points: usize,
+    },
+    /// A contour gained or lost points. Its individual points are not compared.
+    PointCount {
+        contour: usize,
+        old: usize,
+        new: usize,
+    },
+    PointChanged {
+        contour: usize,
+        point: usize,
+        old: PointState,
+        new: PointState,
+    },
+    LibKeyAdded {
+        key: String,
+    },
+    LibKeyRemoved {
+        key: String,
+    },
+    LibKeyChanged {
+        key: String,
+    },
+    /// The glyph could not be read in the `old` or `new` version of the font, so its content
+    /// was not compared.
+    Unreadable {
+        version: String,
+        error: String,
+    },
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AdvanceWidth { old, new } => write!(f, "advance width: {old} -> {new}"),
+            Self::AdvanceHeight { old, new } => write!(f, "advance height: {old} -> {new}"),
+            Self::Unicodes { old, new } => {
+                write!(f, "unicodes: {} -> {}", unicodes(old), unicodes(new))
+            }
+            Self::AnchorAdded { anchor, position } => {
+                write!(f, "anchor {anchor} added at {}", Position(*position))
+            }
+            Self::AnchorRemoved { anchor, position } => {
+                write!(f, "anchor {anchor} removed from {}", Position(*position))
+            }
+            Self::AnchorMoved { anchor, old, new } => {
+                write!(
+                    f,
+                    "anchor {anchor} moved: {} -> {}",
+                    Position(*old),
+                    Position(*new)
+                )
+            }
+            Self::AnchorRenamed { old, new } => write!(f, "anchor renamed: {old} -> {new}"),
+            Self::ComponentAdded { index, base } => {
+                write!(f, "component {index} ({}) added", base_name(base))
+            }
+            Self::ComponentRemoved { index, base } => {
+                write!(f, "component {index} ({}) removed", base_name(base))
+            }
+            Self::ComponentBase { index, old, new } => write!(
+                f,
+                "component {index} base: {} -> {}",
+                base_name(old),
+                base_name(new)
+            ),
+            Self::ComponentTransform {
+                index,
+                base,
+                old,
+                new,
+            } => write!(
+                f,
+                "component {index} ({}) transform: {} -> {}",
+                base_name(base),
+                Matrix(old),
+                Matrix(new)
+            ),
+            Self::ContourAdded { index, points } => {
+                write!(f, "contour {index} added ({points} points)")
+            }
+            Self::ContourRemoved { index, points } => {
+                write!(f, "contour {index} removed ({points} points)")
+            }
+            Self::PointCount { contour, old, new } => {
+                write!(f, "contour {contour}: {old} points -> {new} points")
+            }
+            Self::PointChanged {
+                contour,
+                point,
+                old,
+                new,
+            } => write!(f, "contour {contour} point {point}: {old} -> {new}"),
+            Self::LibKeyAdded { key } => write!(f, "lib key {key} added"),
+            Self::LibKeyRemoved { key } => write!(f, "lib key {key} removed"),
+            Self::LibKeyChanged { key } => write!(f, "lib key {key} changed"),
+            Self::Unreadable { version, error } => {
+                write!(f, "unreadable in {version} font: {error}")
+            }
+        }
+    }
+}
+
+/// The compared attributes of a contour point.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct PointState {
+    pub x: f64,
+    pub y: f64,
+    #[serde(rename = "type")]
+    pub point_type: String,
+    pub smooth: bool,
+}
+
+impl From<&Point> for PointState {
+    fn from(point: &Point) -> Self {
+        Self {
+            x: point.x.unwrap_or_default(),
+            y: point.y.unwrap_or_default(),
+            point_type: point.point_type_or_default().as_str().to_owned(),
+            smooth: point.smooth_or_default(),
+        }
+    }
+}
+
+impl fmt::Display for PointState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", Position((self.x, self.y)), self.point_type)?;
+        if self.smooth {
+            write!(f, " smooth")?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares two versions of a glyph.
+///
+/// Components, contours and points are compared by position in the outline. The glyph libs are
+/// compared key by key; a lib kept unparsed by [`crate::glif::LibMode::Lazy`] must be resolved
+/// first.
+pub fn diff_glyphs(old: &GlifData, new: &GlifData) -> GlyphDiff {
+    let mut changes = Vec::new();
+
+    let (old_advance, new_advance) = (
+        old.advance.clone().unwrap_or_default(),
+        new.advance.clone().unwrap_or_default(),
+    );
+    let (old_width, new_width) = (
+        old_advance.width_or_default(),
+        new_advance.width_or_default(),
+    );
+    if old_width != new_width {
+        changes.push(Change::AdvanceWidth {
+            old: old_width,
+            new: new_width,
+        });
+    }
+    let (old_height, new_height) = (
+        old_advance.height_or_default(),
+        new_advance.height_or_default(),
+    );
+    if old_height != new_height {
+        changes.push(Change::AdvanceHeight {
+            old: old_height,
+            new: new_height,
+        });
+    }
+
+    if old.unicodes != new.unicodes {
+        changes.push(Change::Unicodes {
+            old: old.unicodes.clone(),
+            new: new.unicodes.clone(),
+        });
+    }
+
+    diff_anchors(&old.anchors, &new.anchors, &mut changes);
+    diff_components(old.components(), new.components(), &mut changes);
+    diff_contours(old.contours(), new.contours(), &mut changes);
+    diff_libs(lib(old), lib(new), &mut changes);
+
+    GlyphDiff { changes }
+}
+
+/// Compares two fonts glyph by glyph.
+///
+/// Glyph libs are resolved by [`crate::font::Glyph::data`], so lib changes are reported for fonts
+/// created with [`Font::new`] as well. A glyph that cannot be read in either font is reported as
+/// changed, with a [`Change::Unreadable`] for each font it cannot be read in.
+pub fn diff_fonts(old: &Rc<Font>, new: &Rc<Font>) -> FontDiff {
+    let mut diff = FontDiff::default();
+    for name in old.glyph_order() {
+        if !new.contains(&name) {
+            diff.removed.push(name);
+            continue;
+        }
+        let data = |font: &Rc<Font>| font.try_glyph(&name).and_then(|glyph| glyph.data());
+        let glyph_diff = match (data(old), data(new)) {
+            (Ok(old_data), Ok(new_data)) => diff_glyphs(&old_data, &new_data),
+            (old_data, new_data) => GlyphDiff {
+                changes: [("old", old_data.err()), ("new", new_data.err())]
+                    .into_iter()
+                    .filter_map(|(version, err)| {
+                        err.map(|err| Change::Unreadable {
+                            version: version.to_owned(),
+                            error: err.to_string(),
+                        })
+                    })
+                    .collect(),
+            },
+        };
+        if !glyph_diff.is_empty() {
+            diff.changed.insert(name, glyph_diff);
+        }
+    }
+    diff.added = new
+        .glyph_order()
+        .into_iter()
+        .filter(|name| !old.contains(name))
+        .collect();
+    diff
+}
+
+fn diff_anchors(old: &[Anchor], new: &[Anchor], changes: &mut Vec<Change>) {
+    let mut unmatched = (0..new.len()).collect::<Vec<_>>();
+    for anchor in old {
+        // Match by identifier first, then by name; anchors with neither match only an
+        // identical anchor.
+        let found = unmatched
+            .iter()
+            .position(|&i| anchor.identifier.is_some() && new[i].identifier == anchor.identifier)
+            .or_else(|| {
+                unmatched.iter().position(|&i| {
+                    let other = &new[i];
+                    match (&anchor.name, &anchor.identifier) {
+                        (Some(_), _) => other.name == anchor.name,
+                        (None, None) => {
+                            other.name.is_none()
+                                && other.identifier.is_none()
+                                && position(other) == position(anchor)
+                        }
+                        (None, Some(_)) => false,
+                    }
+                })
+            })
+            .map(|index| unmatched.remove(index));
+        let Some(found) = found else {
+            changes.push(Change::AnchorRemoved {
+                anchor: anchor_label(anchor),
+                position: position(anchor),
+            });
+            continue;
+        };
+        let other = &new[found];
+        if other.name != anchor.name {
+            changes.push(Change::AnchorRenamed {
+                old: anchor_label(anchor),
+                new: anchor_label(other),
+            });
+        }
+        if position(other) != position(anchor) {
+            changes.push(Change::AnchorMoved {
+                anchor: anchor_label(other),
+                old: position(anchor),
+                new: position(other),
+            });
+        }
+    }
+    for i in unmatched {
+        changes.push(Change::AnchorAdded {
+            anchor: anchor_label(&new[i]),
+            position: position(&new[i]),
+        });
+    }
+}
+
+fn diff_components(old: &[Component], new: &[Component], changes: &mut Vec<Change>) {
+    for (index, (a, b)) in old.iter().zip(new).enumerate() {
+        if a.base != b.base {
+            changes.push(Change::ComponentBase {
+                index,
+                old: a.base.clone(),
+                new: b.base.clone(),
+            });
+        }
+        if a.transform() != b.transform() {
+            changes.push(Change::ComponentTransform {
+                index,
+                base: b.base.clone(),
+                old: a.transform(),
+                new: b.transform(),
+            });
+        }
+    }
+    for (index, component) in old.iter().enumerate().skip(new.len()) {
+        changes.push(Change::ComponentRemoved {
+            index,
+            base: component.base.clone(),
+        });
+    }
+    for (index, component) in new.iter().enumerate().skip(old.len()) {
+        changes.push(Change::ComponentAdded {
+            index,
+            base: component.base.clone(),
+        });
+    }
+}
+
+fn diff_contours(old: &[Contour], new: &[Contour], changes: &mut Vec<Change>) {
+    for (contour, (a, b)) in old.iter().zip(new).enumerate() {
+        if a.points.len() != b.points.len() {
+            changes.push(Change::PointCount {
+                contour,
+                old: a.points.len(),
+                new: b.points.len(),
+            });
+            continue;
+        }
+        for (point, (p, q)) in a.points.iter().zip(&b.points).enumerate() {
+            let (old, new) = (PointState::from(p), PointState::from(q));
+            if old != new {
+                changes.push(Change::PointChanged {
+                    contour,
+                    point,
+                    old,
+                    new,
+                });
+            }
+        }
+    }
+    for (index, contour) in old.iter().enumerate().skip(new.len()) {
+        changes.push(Change::ContourRemoved {
+            index,
+            points: contour.points.len(),
+        });
+    }
+    for (index, contour) in new.iter().enumerate().skip(old.len()) {
+        changes.push(Change::ContourAdded {
+            index,
+            points: contour.points.len(),
+        });
+    }
+}
+
+fn diff_libs(old: Option<&Dictionary>, new: Option<&Dictionary>, changes: &mut Vec<Change>) {
+    let empty = Dictionary::new();
+    let (old, new) = (old.unwrap_or(&empty), new.unwrap_or(&empty));
+    for (key, value) in old {
+        match new.get(key) {
+            None => changes.push(Change::LibKeyRemoved { key: key.clone() }),
+            Some(other) if other != value => {
+                changes.push(Change::LibKeyChanged { key: key.clone() })
+            }
+            Some(_) => {}
+        }
+    }
+    for key in new.keys().filter(|key| !old.contains_key(key)) {
+        changes.push(Change::LibKeyAdded { key: key.clone() });
+    }
+}
+
+fn lib(glyph: &GlifData) -> Option<&Dictionary> {
+    glyph.lib.as_ref().and_then(|lib| lib.as_dictionary())
+}
+
+fn position(anchor: &Anchor) -> (f64, f64) {
+    (anchor.x.unwrap_or_default(), anchor.y.unwrap_or_default())
+}
+
+fn anchor_label(anchor: &Anchor) -> String {
+    anchor
+        .name
+        .clone()
+        .or_else(|| anchor.identifier.clone())
+        .unwrap_or_else(|| "<unnamed>".to_owned())
+}
+
+fn base_name(base: &Option<String>) -> &str {
+    base.as_deref().unwrap_or("<no base>")
+}
+
+fn unicodes(unicodes: &[u32]) -> String {
+    if unicodes.is_empty() {
+        return "none".to_owned();
+    }
+    unicodes
+        .iter()
+        .map(|unicode| format!("U+{unicode:04X}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+struct Position((f64, f64));
+
+impl fmt::Display for Position {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (x, y) = self.0;
+        write!(f, "({x}, {y})")
+    }
+}
+
+struct Matrix<'a>(&'a Transform);
+
+impl fmt::Display for Matrix<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (xx, xy, yx, yy, dx, dy) = self.0.to_tuple();
+        write!(f, "[{xx} {xy} {yx} {yy} {dx} {dy}]")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::glif::{GlifParser, ParseOptions};
+    use crate::paths::UfoRelativePath;
+    use crate::provider::{MemoryProvider, WritableProvider};
+    use crate::test_utils::MockProvider;
+
+    fn parse(glif: &str) -> GlifData {
+        let path = UfoRelativePath::GlifFile("a.glif".to_string()).to_pathbuf();
+        let provider = MockProvider::new();
+        provider.with_file(&path, glif.as_bytes());
+        GlifParser::with_options(provider, ParseOptions::default())
+            .unwrap()
+            .parse_glif("a.glif")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_diff_glyphs() {
+        let old = parse(
+            r#"<glyph name="a" format="2">
+                <advance width="500"/>
+                <unicode hex="0061"/>
+                <anchor x="250" y="0" name="bottom"/>
+                <anchor x="250" y="500" name="top" identifier="t1"/>
+                <anchor x="0" y="0" name="gone"/>
+                <outline>
+                    <component base="b"/>
+                    <component base="c"/>
+                    <contour>
+                        <point x="0" y="0" type="line"/>
+                        <point x="10" y="0" type="line"/>
+                    </contour>
+                    <contour><point x="0" y="0" type="line"/></contour>
+                </outline>
+                <lib><dict>
+                    <key>kept</key><integer>1</integer>
+                    <key>changed</key><integer>1</integer>
+                    <key>removed</key><true/>
+                </dict></lib>
+            </glyph>"#,
+        );
+        let new = parse(
+            r#"<glyph name="a" format="2">
+                <advance width="520"/>
+                <unicode hex="0061"/>
+                <unicode hex="00AA"/>
+                <anchor x="250" y="0" name="bottom"/>
+                <anchor x="260" y="500" name="top_1" identifier="t1"/>
+                <anchor x="1" y="1" name="new"/>
+                <outline>
+                    <component base="b" xOffset="20"/>
+                    <component base="d"/>
+                    <component base="e"/>
+                    <contour>
+                        <point x="0" y="0" type="line"/>
+                        <point x="10" y="5" type="curve" smooth="yes"/>
+                    </contour>
+                    <contour>
+                        <point x="0" y="0" type="line"/>
+                        <point x="1" y="1" type="line"/>
+                    </contour>
+                    <contour><point x="0" y="0" type="move"/></contour>
+                </outline>
+                <lib><dict>
+                    <key>kept</key><integer>1</integer>
+                    <key>changed</key><integer>2</integer>
+                    <key>added</key><true/>
+                </dict></lib>
+            </glyph>"#,
+        );
+        let diff = diff_glyphs(&old, &new);
+        let lines = diff.to_string();
+        assert_eq!(
+            lines.lines().collect::<Vec<_>>(),
+            [
+                "advance width: 500 -> 520",
+                "unicodes: U+0061 -> U+0061, U+00AA",
+                "anchor renamed: top -> top_1",
+                "anchor top_1 moved: (250, 500) -> (260, 500)",
+                "anchor gone removed from (0, 0)",
+                "anchor new added at (1, 1)",
+                "component 0 (b) transform: [1 0 0 1 0 0] -> [1 0 0 1 20 0]",
+                "component 1 base: c -> d",
+                "component 2 (e) added",
+                "contour 0 point 1: (10, 0) line -> (10, 5) curve smooth",
+                "contour 1: 1 points -> 2 points",
+                "contour 2 added (1 points)",
+                "lib key changed changed",
+                "lib key removed removed",
+                "lib key added added",
+            ]
+        );
+        assert!(diff_glyphs(&old, &old).is_empty());
+
+        let json = diff.to_json().unwrap();
+        assert!(json.starts_with(r#"[{"kind":"advanceWidth","old":500.0,"new":520.0}"#));
+    }
+
+    #[test]
+    fn test_diff_fonts() {
+        fn font(glyphs: &[(&str, &str)]) -> Rc<Font> {
+            let provider = MemoryProvider::new();
+            let mut contents = String::from(r#"<plist version="1.0"><dict>"#);
+            for (name, glif) in glyphs {
+                let file_name = format!("{name}.glif");
+                contents.push_str(&format!("<key>{name}</key><string>{file_name}</string>"));
+                provider
+                    .write(
+                        &UfoRelativePath::GlifFile(file_name).to_pathbuf(),
+                        glif.as_bytes(),
+                    )
+                    .unwrap();
+            }
+            contents.push_str("</dict></plist>");
+            provider
+                .write(&UfoRelativePath::Contents.to_pathbuf(), contents.as_bytes())
+                .unwrap();
+            Font::new(provider).unwrap()
+        }
+
+        let old = font(&[
+            (
+                "a",
+                r#"<glyph name="a" format="2"><advance width="500"/></glyph>"#,
+            ),
+            ("b", r#"<glyph name="b" format="2"/>"#),
+            ("c", r#"<glyph name="c" format="2"/>"#),
+            ("e", r#"<glyph name="e" format="2"/>"#),
+        ]);
+        let new = font(&[
+            (
+                "a",
+                r#"<glyph name="a" format="2"><advance width="510"/></glyph>"#,
+            ),
+            (
+                "c",
+                r#"<glyph name="c" format="2"><lib><dict>
+                    <key>com.example.flag</key><true/>
+                </dict></lib></glyph>"#,
+            ),
+            ("d", r#"<glyph name="d" format="2"/>"#),
+            ("e", r#"<glyph name="e" format="2"><advance"#),
+        ]);
+        let diff = diff_fonts(&old, &new);
+        assert_eq!(diff.added, ["d"]);
+        assert_eq!(diff.removed, ["b"]);
+        assert_eq!(diff.changed.keys().collect::<Vec<_>>(), ["a", "c", "e"]);
+        assert!(matches!(
+            diff.changed["e"].changes.as_slice(),
+            [Change::Unreadable { version, .. }] if version == "new"
+        ));
+        assert_eq!(
+            diff.to_string(),
+            concat!(
+                "+ d\n- b\n",
+                "~ a\n    advance width: 500 -> 510\n",
+                "~ c\n    lib key com.example.flag added\n",
+                "~ e\n    unreadable in new font: Xml parsing error\n",
+            )
+        );
+        assert!(diff_fonts(&old, &old).is_empty());
+    }
+}
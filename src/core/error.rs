@@ -10,7 +10,7 @@ use std::str::Utf8Error;
 pub type Result<T> = std::result::Result<T, Error>;
 
 /// Categories of errors that can occur while parsing UFO or GLIF data.
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum ErrorKind {
     /// Underlying I/O error (file access, reading, etc.).
     Io,
@@ -24,6 +24,14 @@ pub enum ErrorKind {
     FileNotFound,
     /// An expected XML or property list attribute was missing.
     MissingAttribute(String),
+    /// A requested glyph is not listed in `contents.plist`.
+    GlyphNotFound(String),
+    /// A file declares a format version this crate cannot read.
+    UnsupportedFormat(String),
+    /// A glyph or other user-facing name cannot be used as given.
+    InvalidName(String),
+    /// An argument is outside the range of values an operation accepts.
+    InvalidValue(String),
     /// Any other error not covered by the above kinds.
     Other(String),
 }
@@ -37,6 +45,12 @@ impl fmt::Display for ErrorKind {
             Self::Parse => write!(f, "Parsing error"),
             Self::FileNotFound => write!(f, "File not found"),
             Self::MissingAttribute(attr) => write!(f, "Missing attribute: {}", attr),
+            Self::GlyphNotFound(name) => write!(f, "Glyph not found: {}", name),
+            Self::UnsupportedFormat(version) => {
+                write!(f, "Unsupported format version: {}", version)
+            }
+            Self::InvalidName(reason) => write!(f, "Invalid name: {}", reason),
+            Self::InvalidValue(reason) => write!(f, "Invalid value: {}", reason),
             Self::Other(msg) => write!(f, "{}", msg),
         }
     }
@@ -92,6 +106,19 @@ impl Error {
         self
     }
 
+    /// Returns a copy of this error without its underlying cause.
+    ///
+    /// Used to report an error that is kept around, e.g. to defer it until the data it concerns
+    /// is accessed.
+    pub(crate) fn duplicate(&self) -> Self {
+        Self {
+            kind: self.kind.clone(),
+            path: self.path.clone(),
+            context: self.context.clone(),
+            cause: None,
+        }
+    }
+
     /// Returns the kind of this error.
     pub fn kind(&self) -> &ErrorKind {
         &self.kind
@@ -177,6 +204,13 @@ impl From<Utf8Error> for Error {
     }
 }
 
+impl From<serde_json::Error> for Error {
+    /// Converts a JSON serialization error into [`ErrorKind::Parse`].
+    fn from(err: serde_json::Error) -> Self {
+        Self::new(ErrorKind::Parse).with_cause(err)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -209,6 +243,22 @@ mod tests {
             ErrorKind::MissingAttribute("attr".into()).to_string(),
             "Missing attribute: attr"
         );
+        assert_eq!(
+            ErrorKind::GlyphNotFound("a".into()).to_string(),
+            "Glyph not found: a"
+        );
+        assert_eq!(
+            ErrorKind::UnsupportedFormat("3.0".into()).to_string(),
+            "Unsupported format version: 3.0"
+        );
+        assert_eq!(
+            ErrorKind::InvalidName("empty".into()).to_string(),
+            "Invalid name: empty"
+        );
+        assert_eq!(
+            ErrorKind::InvalidValue("size".into()).to_string(),
+            "Invalid value: size"
+        );
         assert_eq!(
             ErrorKind::Other("something bad".into()).to_string(),
             "something bad"
@@ -250,7 +300,7 @@ mod tests {
 
     #[test]
     fn error_source_returns_cause() {
-        let io_err = io::Error::new(io::ErrorKind::Other, "disk failed");
+        let io_err = io::Error::other("disk failed");
         let err = Error::new(ErrorKind::Io).with_cause(io_err);
         let src = err.source().unwrap();
         assert_eq!(src.to_string(), "disk failed");
@@ -258,7 +308,7 @@ mod tests {
 
     #[test]
     fn from_io_error_other() {
-        let io_err = io::Error::new(io::ErrorKind::Other, "oh no");
+        let io_err = io::Error::other("oh no");
         let err: Error = io_err.into();
         assert_eq!(err.kind(), &ErrorKind::Io);
         assert!(err.source().is_some());
@@ -315,4 +365,12 @@ mod tests {
         assert_eq!(err.kind(), &ErrorKind::Parse);
         assert!(err.source().is_some());
     }
+
+    #[test]
+    fn from_serde_json_error() {
+        let json_err = serde_json::from_str::<u32>("not json").unwrap_err();
+        let err: Error = json_err.into();
+        assert_eq!(err.kind(), &ErrorKind::Parse);
+        assert!(err.source().is_some());
+    }
 }
@@ -1,17 +1,34 @@
 //! Lazy UFO font data model.
 
-use std::cell::RefCell;
+use std::cell::{Cell, Ref, RefCell};
 use std::collections::HashMap;
+use std::ops::RangeInclusive;
 use std::rc::Rc;
 
-use plist::Value;
+use indexmap::{IndexMap, IndexSet};
+use plist::{Dictionary, Value};
 
+use crate::cache::{CachePolicy, CacheTracker};
+use crate::cmap::{Cmap, Coverage};
+use crate::color::Color;
+use crate::components::ComponentIssue;
 use crate::error::Result;
 use crate::error::{Error, ErrorKind};
-use crate::glif::{Advance, Anchor, GlifData, GlifParser, Guideline, Image, Outline};
-use crate::paths::UfoRelativePath;
+use crate::fontlib::FontLib;
+use crate::glif::{
+    Advance, Anchor, Component, Contour, GlifData, GlifFormatVersion, GlifParser, Guideline,
+    IdentifierIssue, Image, LibMode, Outline, ParseOptions, write_glif,
+};
+use crate::hash::HashOptions;
+use crate::metrics::Sidebearings;
+use crate::path::{self, Bounds};
+use crate::paths::{ConsistencyIssue, FileNameIssue, UfoRelativePath};
+use crate::plist::LayerInfo;
 use crate::provider::Provider;
-use crate::provider::ProviderHandle;
+use crate::provider::{FileStamp, ProviderHandle};
+use crate::raster::{Bitmap, RasterFrame, RasterOptions};
+use crate::svg::{ProofGlyph, ProofMetrics, SvgOptions};
+use crate::transform::Transform;
 
 /// A UFO font object that loads glyph data on demand.
 ///
@@ -26,20 +43,76 @@ use crate::provider::ProviderHandle;
 #[derive(Clone)]
 pub struct Font {
     provider: ProviderHandle,
-    contents: HashMap<String, String>,
+    contents: RefCell<IndexMap<String, String>>,
+    lib: RefCell<FontLib>,
+    lib_error: RefCell<Option<Rc<Error>>>,
+    contents_stamp: Cell<Option<FileStamp>>,
+    lib_stamp: Cell<Option<FileStamp>>,
+    proof_metrics: Cell<Option<ProofMetrics>>,
+    cmap: RefCell<Option<Rc<Cmap>>>,
     glyphs: RefCell<HashMap<String, Rc<Glyph>>>,
+    cache: RefCell<CacheTracker>,
+    options: ParseOptions,
 }
 
 impl Font {
     /// Creates a new [`Font`] object by parsing `contents.plist` via the given provider.
+    ///
+    /// Glyphs are ordered by the `public.glyphOrder` key of `lib.plist`, with unlisted glyphs
+    /// appended in sorted order. A malformed `lib.plist` does not prevent the font from being
+    /// read; its error is returned by [`Font::lib`] instead.
+    ///
+    /// Glyph libs are parsed lazily, on the first call to [`Glyph::lib`].
     pub fn new(provider: ProviderHandle) -> Result<Rc<Self>> {
-        let contents = crate::plist::parse_contents(provider.clone())?;
-        let contents_len = contents.len();
-        Ok(Rc::new(Self {
+        Self::with_options(
+            provider,
+            ParseOptions::default().with_lib_mode(LibMode::Lazy),
+        )
+    }
+
+    /// Creates a new [`Font`] object whose glyphs are parsed with the given [`ParseOptions`].
+    pub fn with_options(provider: ProviderHandle, options: ParseOptions) -> Result<Rc<Self>> {
+        let font = Self {
             provider,
-            contents,
-            glyphs: RefCell::new(HashMap::with_capacity(contents_len)),
-        }))
+            contents: RefCell::default(),
+            lib: RefCell::default(),
+            lib_error: RefCell::default(),
+            contents_stamp: Cell::new(None),
+            lib_stamp: Cell::new(None),
+            proof_metrics: Cell::new(None),
+            cmap: RefCell::default(),
+            glyphs: RefCell::default(),
+            cache: RefCell::default(),
+            options,
+        };
+        font.read_metadata()?;
+        font.glyphs.borrow_mut().reserve(font.len());
+        Ok(Rc::new(font))
+    }
+
+    /// Reads `contents.plist` and `lib.plist` and records their stamps.
+    fn read_metadata(&self) -> Result<()> {
+        let contents_path = UfoRelativePath::Contents.to_pathbuf();
+        let lib_path = UfoRelativePath::Lib.to_pathbuf();
+        let contents_stamp = FileStamp::current(&*self.provider, &contents_path)?;
+        let lib_stamp = FileStamp::current(&*self.provider, &lib_path)?;
+
+        let contents = crate::plist::parse_contents(self.provider.clone())?;
+        let (lib, lib_error) = match crate::plist::parse_lib(self.provider.clone()) {
+            Ok(lib) => (lib, None),
+            Err(err) => (FontLib::default(), Some(Rc::new(err))),
+        };
+        let contents =
+            crate::plist::order_contents(contents, &lib.glyph_order().unwrap_or_default());
+
+        *self.contents.borrow_mut() = contents;
+        *self.lib.borrow_mut() = lib;
+        *self.lib_error.borrow_mut() = lib_error;
+        self.proof_metrics.set(None);
+        self.cmap.take();
+        self.contents_stamp.set(contents_stamp);
+        self.lib_stamp.set(lib_stamp);
+        Ok(())
     }
 
     /// Returns a reference to the provider handle used by this font.
@@ -47,9 +120,451 @@ impl Font {
         &self.provider
     }
 
-    /// Returns a reference to the glyph contents map (`contents.plist`).
-    pub fn contents(&self) -> &HashMap<String, String> {
-        &self.contents
+    /// Returns the options used to parse the font's glyphs.
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Returns a reference to the glyph contents map (`contents.plist`), in glyph order.
+    pub fn contents(&self) -> Ref<'_, IndexMap<String, String>> {
+        self.contents.borrow()
+    }
+
+    /// Returns the names of all glyphs in the font, in glyph order.
+    ///
+    /// See [`crate::plist::order_contents`] for the ordering rules.
+    pub fn glyph_order(&self) -> Vec<String> {
+        self.contents.borrow().keys().cloned().collect()
+    }
+
+    /// Returns the font's `lib.plist`, which is read when the font is created or reloaded.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error encountered while reading `lib.plist` if it could not be parsed.
+    pub fn lib(&self) -> Result<Ref<'_, FontLib>> {
+        match &*self.lib_error.borrow() {
+            Some(err) => Err(err.duplicate()),
+            None => Ok(self.lib.borrow()),
+        }
+    }
+
+    /// Reads and returns the font's `fontinfo.plist`, or an empty dictionary if there is none.
+    pub fn font_info(&self) -> Result<Dictionary> {
+        crate::plist::parse_fontinfo(self.provider.clone())
+    }
+
+    /// Reads and returns the default layer's `layerinfo.plist`.
+    pub fn layer_info(&self) -> Result<LayerInfo> {
+        crate::plist::parse_layerinfo(self.provider.clone())
+    }
+
+    /// Checks that the file names in `contents.plist` follow the UFO 3 glyph file name
+    /// algorithm and do not clash on case-insensitive file systems.
+    ///
+    /// See [`crate::paths::check_contents`].
+    pub fn check_file_names(&self) -> Vec<FileNameIssue> {
+        crate::paths::check_contents(&self.contents.borrow())
+    }
+
+    /// Cross-checks `contents.plist` against the files in the `glyphs` directory.
+    ///
+    /// Reports the issues found by [`crate::paths::check_glyphs_dir`], followed by glyphs whose
+    /// GLIF file cannot be parsed or declares a different name than its `contents.plist` key.
+    /// This parses every glyph that has a file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provider cannot list the `glyphs` directory (see
+    /// [`Provider::list_dir`]).
+    pub fn check_consistency(self: &Rc<Self>) -> Result<Vec<ConsistencyIssue>> {
+        let files = self
+            .provider
+            .list_dir(&UfoRelativePath::Glyphs.to_pathbuf())?;
+        let mut issues = crate::paths::check_glyphs_dir(&self.contents.borrow(), &files);
+
+        for (name, glyph) in self.glyphs() {
+            let Some(file_name) = self.contents.borrow().get(&name).cloned() else {
+                continue;
+            };
+            if !files.contains(&file_name) {
+                continue;
+            }
+            match glyph.data() {
+                Ok(data) if data.name != name => issues.push(ConsistencyIssue::NameMismatch {
+                    glyph: name,
+                    file_name,
+                    name: data.name,
+                }),
+                Ok(_) => {}
+                Err(err) => issues.push(ConsistencyIssue::Unreadable {
+                    glyph: name,
+                    file_name,
+                    error: err.to_string(),
+                }),
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Checks the components of every glyph for missing or absent base glyphs and degenerate
+    /// transforms, in glyph order.
+    ///
+    /// Glyphs that cannot be parsed are reported as [`ComponentIssue::Unreadable`] and skipped.
+    /// See [`crate::components::check_components`].
+    pub fn check_components(self: &Rc<Self>) -> Result<Vec<ComponentIssue>> {
+        let mut issues = Vec::new();
+        for glyph in self.iter() {
+            match glyph.check_components() {
+                Ok(glyph_issues) => issues.extend(glyph_issues),
+                Err(err) => issues.push(ComponentIssue::Unreadable {
+                    glyph: glyph.name.clone(),
+                    error: err.to_string(),
+                }),
+            }
+        }
+        Ok(issues)
+    }
+
+    /// Groups the glyphs whose outlines have the same [`Glyph::outline_hash`].
+    ///
+    /// Only groups of two or more glyphs are returned, each in glyph order. Glyphs without
+    /// contours after decomposition are skipped, as all empty glyphs would otherwise match, and
+    /// so are glyphs that cannot be read or decomposed.
+    pub fn duplicate_outlines(self: &Rc<Self>, options: HashOptions) -> Vec<Vec<String>> {
+        let mut groups = IndexMap::<String, Vec<String>>::new();
+        for glyph in self.iter() {
+            let (Ok(data), Ok(contours)) = (glyph.load(), glyph.decomposed_contours()) else {
+                continue;
+            };
+            if contours.is_empty() {
+                continue;
+            }
+            let hash = crate::hash::outline_hash(&contours, &data, options);
+            groups.entry(hash).or_default().push(glyph.name.clone());
+        }
+        groups
+            .into_values()
+            .filter(|names| names.len() > 1)
+            .collect()
+    }
+
+    /// Returns the `unitsPerEm` and `descender` of `fontinfo.plist`, which default to 1000 and
+    /// a quarter em below the baseline.
+    ///
+    /// `fontinfo.plist` is read on the first call, and the metrics are cached until the font is
+    /// reloaded.
+    pub fn proof_metrics(&self) -> Result<ProofMetrics> {
+        if let Some(metrics) = self.proof_metrics.get() {
+            return Ok(metrics);
+        }
+        let info = self.font_info()?;
+        let number = |key: &str| {
+            info.get(key).and_then(|value| {
+                value
+                    .as_real()
+                    .or_else(|| value.as_signed_integer().map(|v| v as f64))
+            })
+        };
+        let units_per_em = number("unitsPerEm")
+            .filter(|upm| *upm > 0.0)
+            .unwrap_or(1000.0);
+        let metrics = ProofMetrics {
+            units_per_em,
+            descender: number("descender").unwrap_or(-units_per_em / 4.0),
+        };
+        self.proof_metrics.set(Some(metrics));
+        Ok(metrics)
+    }
+
+    /// Creates an SVG proof of the named glyphs, with components resolved.
+    ///
+    /// Cells are sized by the font's vertical metrics; see [`Font::proof_metrics`] and
+    /// [`crate::svg::document`]. The anchors of format 1 glyphs, stored as contours, are marked
+    /// like other anchors.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `fontinfo.plist` cannot be read, or if a name is not in the font or
+    /// its glyph cannot be parsed.
+    pub fn to_svg_document<S: AsRef<str>>(
+        self: &Rc<Self>,
+        names: impl IntoIterator<Item = S>,
+        options: SvgOptions,
+    ) -> Result<String> {
+        let metrics = self.proof_metrics()?;
+        let glyphs = names
+            .into_iter()
+            .map(|name| self.try_glyph(name.as_ref())?.proof_glyph())
+            .collect::<Result<Vec<_>>>()?;
+        Ok(crate::svg::document(&glyphs, metrics, options))
+    }
+
+    /// Creates an SVG proof of all glyphs in glyph order, leaving out glyphs that cannot be read
+    /// or decomposed. See [`Font::to_svg_document`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `fontinfo.plist` cannot be read.
+    pub fn to_svg_document_all(self: &Rc<Self>, options: SvgOptions) -> Result<String> {
+        let metrics = self.proof_metrics()?;
+        let glyphs = self
+            .iter()
+            .filter_map(|glyph| glyph.proof_glyph().ok())
+            .collect::<Vec<_>>();
+        Ok(crate::svg::document(&glyphs, metrics, options))
+    }
+
+    /// Returns the map of Unicode code points to the glyphs that claim them.
+    ///
+    /// Glyphs that cannot be read are left out. Code points mapped to more than one glyph are
+    /// listed by [`Font::cmap_conflicts`].
+    pub fn cmap(self: &Rc<Self>) -> Cmap {
+        Cmap::clone(&self.cached_cmap())
+    }
+
+    /// Returns the cmap, building it on first use.
+    ///
+    /// This loads every glyph. The cmap is kept until a glyph's code points are edited or glyph
+    /// data is invalidated, unless a glyph could not be read, so that it is retried next time.
+    fn cached_cmap(self: &Rc<Self>) -> Rc<Cmap> {
+        if let Some(cmap) = &*self.cmap.borrow() {
+            return Rc::clone(cmap);
+        }
+        let glyphs = self.glyphs();
+        let unicodes = glyphs
+            .iter()
+            .filter_map(|(name, glyph)| Some((name.as_str(), glyph.unicodes().ok()?)))
+            .collect::<Vec<_>>();
+        let cmap = Rc::new(crate::cmap::build_cmap(
+            unicodes
+                .iter()
+                .map(|(name, unicodes)| (*name, unicodes.as_slice())),
+        ));
+        if unicodes.len() == glyphs.len() {
+            *self.cmap.borrow_mut() = Some(Rc::clone(&cmap));
+        }
+        cmap
+    }
+
+    /// Returns the code points that are claimed by more than one glyph.
+    pub fn cmap_conflicts(self: &Rc<Self>) -> Cmap {
+        crate::cmap::conflicts(&self.cached_cmap())
+    }
+
+    /// Returns the names of the glyphs mapped to `codepoint`, in glyph order.
+    pub fn glyphs_for(self: &Rc<Self>, codepoint: u32) -> Vec<String> {
+        self.cached_cmap()
+            .get(&codepoint)
+            .cloned()
+            .unwrap_or_default()
+    }
+
+    /// Returns the names of the glyphs mapped to any code point in `range`, ordered by code
+    /// point.
+    pub fn glyphs_in_range(self: &Rc<Self>, range: RangeInclusive<u32>) -> Vec<String> {
+        let cmap = self.cached_cmap();
+        let names = cmap
+            .range(range)
+            .flat_map(|(_, glyphs)| glyphs)
+            .collect::<IndexSet<_>>();
+        names.into_iter().cloned().collect()
+    }
+
+    /// Returns the names of the glyphs without Unicode code points, in glyph order.
+    pub fn unencoded_glyphs(self: &Rc<Self>) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for (name, glyph) in self.glyphs() {
+            if glyph.unicodes()?.is_empty() {
+                names.push(name);
+            }
+        }
+        Ok(names)
+    }
+
+    /// Checks which of `codepoints` are mapped to a glyph in the font.
+    pub fn coverage(self: &Rc<Self>, codepoints: impl IntoIterator<Item = u32>) -> Coverage {
+        Coverage::new(&self.cached_cmap(), codepoints)
+    }
+
+    /// Returns the policy that limits how much parsed glyph data is kept in memory.
+    pub fn cache_policy(&self) -> CachePolicy {
+        self.cache.borrow().policy()
+    }
+
+    /// Sets the [`CachePolicy`], evicting glyphs as needed. The default is
+    /// [`CachePolicy::Unbounded`].
+    pub fn set_cache_policy(&self, policy: CachePolicy) {
+        self.cache.borrow_mut().set_policy(policy);
+        self.evict();
+    }
+
+    /// Returns the number of glyphs whose parsed data is currently cached.
+    pub fn cache_len(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    /// Evicts the least recently used glyphs that exceed the cache policy, keeping dirty glyphs.
+    fn evict(&self) {
+        let glyphs = self.glyphs.borrow();
+        let evicted = self
+            .cache
+            .borrow()
+            .overflow(|name| glyphs.get(name).is_some_and(|glyph| glyph.is_dirty()));
+        for name in evicted {
+            self.cache.borrow_mut().remove(&name);
+            if let Some(glyph) = glyphs.get(&name) {
+                glyph.evict();
+            }
+        }
+    }
+
+    /// Re-reads `contents.plist` and `lib.plist` and discards all cached glyph data.
+    ///
+    /// Unsaved edits are lost. Glyphs no longer listed in `contents.plist` are dropped from the
+    /// cache.
+    pub fn reload(&self) -> Result<()> {
+        self.read_metadata()?;
+        let contents = self.contents.borrow();
+        let mut glyphs = self.glyphs.borrow_mut();
+        glyphs.retain(|name, _| contents.contains_key(name));
+        for glyph in glyphs.values() {
+            glyph.invalidate();
+        }
+        self.cache.borrow_mut().clear();
+        Ok(())
+    }
+
+    /// Returns `true` if `contents.plist` or `lib.plist` changed since they were read.
+    pub fn is_stale(&self) -> Result<bool> {
+        let contents_path = UfoRelativePath::Contents.to_pathbuf();
+        let lib_path = UfoRelativePath::Lib.to_pathbuf();
+        Ok(
+            FileStamp::current(&*self.provider, &contents_path)? != self.contents_stamp.get()
+                || FileStamp::current(&*self.provider, &lib_path)? != self.lib_stamp.get(),
+        )
+    }
+
+    /// Returns the names of the loaded glyphs whose `.glif` file changed since it was read, in
+    /// glyph order.
+    pub fn stale_glyphs(&self) -> Result<Vec<String>> {
+        let glyphs = self.glyphs.borrow();
+        let mut stale = Vec::new();
+        for name in self.contents.borrow().keys() {
+            if let Some(glyph) = glyphs.get(name)
+                && glyph.is_stale()?
+            {
+                stale.push(name.clone());
+            }
+        }
+        Ok(stale)
+    }
+
+    /// Brings the font up to date with its files and returns the names of the glyphs whose
+    /// cached data was discarded, in glyph order.
+    ///
+    /// If `contents.plist` or `lib.plist` changed, this is a full [`Font::reload`]. Otherwise only
+    /// the glyphs reported by [`Font::stale_glyphs`] are invalidated, and are read again on next
+    /// access. Unsaved edits to invalidated glyphs are lost.
+    ///
+    /// This is intended to be called before serving a request, or from a file watcher.
+    pub fn refresh(&self) -> Result<Vec<String>> {
+        if self.is_stale()? {
+            let loaded: Vec<String> = {
+                let glyphs = self.glyphs.borrow();
+                self.contents
+                    .borrow()
+                    .keys()
+                    .filter(|name| glyphs.get(*name).is_some_and(|glyph| glyph.is_loaded()))
+                    .cloned()
+                    .collect()
+            };
+            self.reload()?;
+            return Ok(loaded);
+        }
+        let stale = self.stale_glyphs()?;
+        let glyphs = self.glyphs.borrow();
+        for name in &stale {
+            glyphs[name].invalidate();
+        }
+        Ok(stale)
+    }
+
+    /// Writes modified glyphs and `contents.plist` back through the font's provider.
+    ///
+    /// Only glyphs reporting [`Glyph::is_dirty`] are written, and `contents.plist` is only
+    /// rewritten if its mapping differs from the font's, so untouched files stay byte-identical.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::Io`] error if the provider is not a [`WritableProvider`], or any
+    /// error raised while serializing or writing a file.
+    ///
+    /// [`WritableProvider`]: crate::provider::WritableProvider
+    pub fn save(&self) -> Result<()> {
+        let provider = self.provider.as_writable().ok_or_else(|| {
+            Error::new(ErrorKind::Io)
+                .with_context(|| "provider is not writable")
+                .with_path(self.provider.root().to_string_lossy())
+        })?;
+        let contents_path = UfoRelativePath::Contents.to_pathbuf();
+        if let Some(dir) = contents_path.parent() {
+            provider.create_dir(dir)?;
+        }
+
+        let dirty: Vec<Rc<Glyph>> = self
+            .glyphs
+            .borrow()
+            .values()
+            .filter(|glyph| glyph.is_dirty())
+            .cloned()
+            .collect();
+        for glyph in dirty {
+            let Some(file_name) = self.contents.borrow().get(glyph.name()).cloned() else {
+                continue;
+            };
+            let data = write_glif(&glyph.load_with_lib()?)?;
+            let path = UfoRelativePath::GlifFile(file_name).to_pathbuf();
+            provider.write(&path, &data)?;
+            glyph.dirty.set(false);
+            glyph
+                .stamp
+                .set(Some(FileStamp::new(&*self.provider, &path, &data)));
+        }
+        self.evict();
+
+        let contents = self.contents.borrow();
+        let unchanged = self.provider.exists(&contents_path) && {
+            let saved = crate::plist::parse_contents(self.provider.clone())?;
+            saved.len() == contents.len()
+                && saved
+                    .iter()
+                    .all(|(name, file_name)| contents.get(name) == Some(file_name))
+        };
+        if !unchanged {
+            let data = crate::plist::write_contents(&contents)?;
+            provider.write(&contents_path, &data)?;
+            self.contents_stamp
+                .set(Some(FileStamp::new(&*self.provider, &contents_path, &data)));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if any glyph was edited since it was loaded or last saved.
+    pub fn is_dirty(&self) -> bool {
+        self.glyphs.borrow().values().any(|glyph| glyph.is_dirty())
+    }
+
+    /// Returns the names of the glyphs edited since they were loaded or last saved, in glyph
+    /// order.
+    pub fn dirty_glyphs(&self) -> Vec<String> {
+        let glyphs = self.glyphs.borrow();
+        self.contents
+            .borrow()
+            .keys()
+            .filter(|name| glyphs.get(*name).is_some_and(|glyph| glyph.is_dirty()))
+            .cloned()
+            .collect()
     }
 
     /// Returns a new [`Iter`] object.
@@ -57,11 +572,26 @@ impl Font {
         Iter::new(Rc::clone(self))
     }
 
-    /// Returns a single glyph by name, if present in the font.
-    ///
-    /// This ensures that the glyph cache is initialized first.
+    /// Returns `true` if `contents.plist` lists a glyph with the given name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.contents.borrow().contains_key(name)
+    }
+
+    /// Returns the number of glyphs listed in `contents.plist`.
+    pub fn len(&self) -> usize {
+        self.contents.borrow().len()
+    }
+
+    /// Returns `true` if `contents.plist` lists no glyphs.
+    pub fn is_empty(&self) -> bool {
+        self.contents.borrow().is_empty()
+    }
+
+    /// Returns a single glyph by name, or `None` if it is not listed in `contents.plist`.
     pub fn glyph(self: &Rc<Self>, name: &str) -> Option<Rc<Glyph>> {
-        // Ensure the glyph map is initialized.
+        if !self.contains(name) {
+            return None;
+        }
         let mut cache = self.glyphs.borrow_mut();
         Some(
             cache
@@ -71,19 +601,39 @@ impl Font {
         )
     }
 
-    /// Returns a map of all glyphs in the font.
+    /// Returns a single glyph by name.
+    ///
+    /// # Errors
     ///
-    /// The glyph cache is initialized on first call by creating a [`Glyph`] for each entry in
+    /// Returns an [`ErrorKind::GlyphNotFound`] error if the glyph is not listed in
     /// `contents.plist`.
-    pub fn glyphs(self: &Rc<Self>) -> HashMap<String, Rc<Glyph>> {
-        let mut glyph_map = self.glyphs.borrow_mut();
-        if glyph_map.is_empty() {
-            for name in self.contents.keys() {
-                let glyph = Glyph::new(&self, name.clone());
-                glyph_map.insert(name.clone(), glyph);
-            }
-        }
-        glyph_map.clone()
+    pub fn try_glyph(self: &Rc<Self>, name: &str) -> Result<Rc<Glyph>> {
+        self.glyph(name).ok_or_else(|| {
+            Error::new(ErrorKind::GlyphNotFound(name.to_owned())).with_path(
+                self.provider
+                    .root()
+                    .join(UfoRelativePath::Contents.to_pathbuf())
+                    .to_string_lossy(),
+            )
+        })
+    }
+
+    /// Returns a map of all glyphs in the font, in glyph order.
+    ///
+    /// Creates a cached [`Glyph`] for each entry in `contents.plist` that has not been accessed
+    /// yet.
+    pub fn glyphs(self: &Rc<Self>) -> IndexMap<String, Rc<Glyph>> {
+        let mut cache = self.glyphs.borrow_mut();
+        self.contents
+            .borrow()
+            .keys()
+            .map(|name| {
+                let glyph = cache
+                    .entry(name.clone())
+                    .or_insert_with(|| Glyph::new(self, name.clone()));
+                (name.clone(), Rc::clone(glyph))
+            })
+            .collect()
     }
 }
 
@@ -130,6 +680,8 @@ pub struct Glyph {
     font: Rc<Font>,
     name: String,
     cache: RefCell<Option<GlifData>>,
+    dirty: Cell<bool>,
+    stamp: Cell<Option<FileStamp>>,
 }
 
 impl Glyph {
@@ -139,6 +691,8 @@ impl Glyph {
             font: Rc::clone(font),
             name,
             cache: RefCell::new(None),
+            dirty: Cell::new(false),
+            stamp: Cell::new(None),
         })
     }
 
@@ -147,14 +701,58 @@ impl Glyph {
         &self.name
     }
 
-    /// Returns the major format version string of the glyph's GLIF file.
-    pub fn format(&self) -> Result<String> {
+    /// Returns the format version of the glyph's GLIF file.
+    pub fn format(&self) -> Result<GlifFormatVersion> {
         Ok(self.load()?.format)
     }
 
-    /// Returns the minor format version string of the glyph's GLIF file.
-    pub fn format_minor(&self) -> Result<Option<String>> {
-        Ok(self.load()?.format_minor)
+    /// Returns `true` if the glyph's data is loaded and cached.
+    pub fn is_loaded(&self) -> bool {
+        self.cache.borrow().is_some()
+    }
+
+    /// Discards the cached glyph data, including unsaved edits, so that it is read again on
+    /// next access.
+    pub fn invalidate(&self) {
+        self.font.cache.borrow_mut().remove(&self.name);
+        self.font.cmap.take();
+        self.dirty.set(false);
+        self.evict();
+    }
+
+    /// Drops the cached glyph data without updating the font's cache bookkeeping.
+    fn evict(&self) {
+        *self.cache.borrow_mut() = None;
+        self.stamp.set(None);
+    }
+
+    /// Discards the cached glyph data, including unsaved edits, and reads it again.
+    pub fn reload(&self) -> Result<()> {
+        self.invalidate();
+        self.load().map(|_| ())
+    }
+
+    /// Returns `true` if the glyph's `.glif` file changed since the cached data was read.
+    ///
+    /// Changes are detected by modification time, or by content hash if the provider does not
+    /// report modification times (see [`Provider::modified`]). A glyph that is not loaded is
+    /// never stale.
+    pub fn is_stale(&self) -> Result<bool> {
+        let Some(stamp) = self.stamp.get() else {
+            return Ok(false);
+        };
+        let Some(file_name) = self.font.contents().get(&self.name).cloned() else {
+            return Ok(true);
+        };
+        let path = UfoRelativePath::GlifFile(file_name).to_pathbuf();
+        Ok(FileStamp::current(&*self.font.provider, &path)? != Some(stamp))
+    }
+
+    /// Returns a copy of the glyph's data, loading it on first access.
+    ///
+    /// A lazily parsed lib is resolved, so the returned data always includes the glyph's lib.
+    pub fn data(&self) -> Result<GlifData> {
+        self.load_with_lib()
     }
 
     /// Returns the glyph's advance width/height data (if any).
@@ -193,8 +791,357 @@ impl Glyph {
     }
 
     /// Returns the glyph's lib dictionary (if present).
+    ///
+    /// If the font was created with [`LibMode::Lazy`], the lib is parsed on first call and the
+    /// result is cached.
     pub fn lib(&self) -> Result<Option<Value>> {
-        Ok(self.load()?.lib)
+        Ok(self.load_with_lib()?.lib)
+    }
+
+    /// Returns the glyph's mark color from the `public.markColor` lib key, if set to a valid
+    /// color string; see [`GlifData::mark_color`].
+    pub fn mark_color(&self) -> Result<Option<Color>> {
+        Ok(self.load_with_lib()?.mark_color())
+    }
+
+    /// Returns the glyph's object libs from the `public.objectLibs` lib key, keyed by identifier.
+    pub fn object_libs(&self) -> Result<IndexMap<String, Dictionary>> {
+        self.load_with_lib()?.object_libs()
+    }
+
+    /// Returns the object lib for the object with the given identifier, if any.
+    pub fn object_lib(&self, identifier: &str) -> Result<Option<Dictionary>> {
+        self.load_with_lib()?.object_lib(identifier)
+    }
+
+    /// Returns the `public.objectLibs` keys that don't refer to an object in the glyph.
+    pub fn validate_object_libs(&self) -> Result<Vec<String>> {
+        self.load_with_lib()?.validate_object_libs()
+    }
+
+    /// Checks that the glyph's components refer to glyphs in the font and have invertible
+    /// transforms.
+    pub fn check_components(&self) -> Result<Vec<ComponentIssue>> {
+        let outline = self.load()?.outline.unwrap_or_default();
+        Ok(crate::components::check_components(
+            &self.name,
+            &outline.components,
+            |base| self.font.contains(base),
+        ))
+    }
+
+    /// Returns the glyph's contours followed by the contours of its components, transformed
+    /// and resolved recursively.
+    ///
+    /// The single-point contours that format 1 glyphs use as anchors are left out; see
+    /// [`GlifData::drawn_contours`].
+    ///
+    /// Components without a base, with a base glyph that is not in the font, or that refer
+    /// back to a glyph already being decomposed are skipped; see [`Glyph::check_components`].
+    pub fn decomposed_contours(&self) -> Result<Vec<Contour>> {
+        let mut contours = Vec::new();
+        let mut stack = vec![self.name.clone()];
+        self.decompose_into(&Transform::IDENTITY, &mut stack, &mut contours)?;
+        Ok(contours)
+    }
+
+    /// Returns the bounds of the glyph's decomposed outline, including curve extrema, or `None`
+    /// if the glyph draws nothing.
+    pub fn bounds(&self) -> Result<Option<Bounds>> {
+        Ok(path::bounds(&path::to_path(&self.decomposed_contours()?)))
+    }
+
+    /// Returns the glyph's sidebearings, or `None` if the glyph draws nothing.
+    ///
+    /// Components are resolved, and the top and bottom sidebearings are measured from the
+    /// `public.verticalOrigin` lib key; see [`Sidebearings`].
+    pub fn sidebearings(&self) -> Result<Option<Sidebearings>> {
+        let data = self.load_with_lib()?;
+        let Some(bounds) = self.bounds()? else {
+            return Ok(None);
+        };
+        let advance = data.advance.clone().unwrap_or_default();
+        Ok(Some(Sidebearings::new(
+            &bounds,
+            &advance,
+            data.vertical_origin()?,
+        )))
+    }
+
+    /// Returns the left and right sidebearings along the horizontal line at `y`, or `None` if
+    /// the line misses the glyph's outline.
+    ///
+    /// Curves are measured to within 0.01 units. See [`crate::metrics::sidebearings_at`].
+    pub fn sidebearings_at(&self, y: f64) -> Result<Option<(f64, f64)>> {
+        let width = self.load()?.advance.unwrap_or_default().width_or_default();
+        let path = path::to_path(&self.decomposed_contours()?);
+        Ok(crate::metrics::sidebearings_at(
+            &path::flatten(&path, 0.01),
+            width,
+            y,
+        ))
+    }
+
+    /// Returns the glyph's outline as the `d` attribute of an SVG path, in glyph coordinates.
+    ///
+    /// With `decompose`, components are resolved as in [`Glyph::decomposed_contours`];
+    /// otherwise only the glyph's own contours are drawn. See [`crate::svg::path_data`].
+    pub fn to_svg_path(&self, decompose: bool) -> Result<String> {
+        let contours = if decompose {
+            self.decomposed_contours()?
+        } else {
+            self.load()?.drawn_contours().cloned().collect()
+        };
+        Ok(crate::svg::path_data(&path::to_path(&contours)))
+    }
+
+    /// Returns the glyph as drawn in an SVG proof, with components resolved.
+    fn proof_glyph(&self) -> Result<ProofGlyph> {
+        let data = self.load()?;
+        let anchors = data.all_anchors();
+        Ok(ProofGlyph {
+            name: self.name.clone(),
+            advance: data.advance.unwrap_or_default().width_or_default(),
+            path: path::to_path(&self.decomposed_contours()?),
+            anchors,
+            guidelines: data.guidelines,
+        })
+    }
+
+    /// Renders the glyph's decomposed outline to an anti-aliased grayscale bitmap.
+    ///
+    /// The em is `options.size` pixels high and is measured by [`Font::proof_metrics`]. See
+    /// [`RasterFrame`] for the area shown; an empty frame yields a bitmap one pixel wide or high.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::InvalidValue`] error if `options.size` is not a positive number
+    /// of at most [`crate::raster::MAX_SIZE`] pixels, or if the bitmap would have more than
+    /// [`crate::raster::MAX_PIXELS`] pixels.
+    pub fn rasterize(&self, options: RasterOptions) -> Result<Bitmap> {
+        if !(options.size > 0.0 && options.size <= crate::raster::MAX_SIZE) {
+            return Err(Error::new(ErrorKind::InvalidValue(format!(
+                "raster size must be greater than 0 and at most {}, not {}",
+                crate::raster::MAX_SIZE,
+                options.size
+            ))));
+        }
+        let metrics = self.font.proof_metrics()?;
+        let scale = options.size / metrics.units_per_em;
+        let path = path::to_path(&self.decomposed_contours()?);
+        let pixels = |value: f64| value.round().max(1.0) as u32;
+
+        // The glyph-space position of the bitmap's top left corner, in pixels.
+        let (left, top, width, height) = match options.frame {
+            RasterFrame::Advance => {
+                let width = self.load()?.advance.unwrap_or_default().width_or_default();
+                let ascender = metrics.units_per_em + metrics.descender;
+                (
+                    0.0,
+                    (ascender * scale).round(),
+                    pixels(width * scale),
+                    pixels(metrics.units_per_em * scale),
+                )
+            }
+            RasterFrame::Bounds => {
+                let bounds = path::bounds(&path).unwrap_or(Bounds::at((0.0, 0.0)));
+                let (left, right) = (
+                    (bounds.x_min * scale).floor(),
+                    (bounds.x_max * scale).ceil(),
+                );
+                let (bottom, top) = (
+                    (bounds.y_min * scale).floor(),
+                    (bounds.y_max * scale).ceil(),
+                );
+                (left, top, pixels(right - left), pixels(top - bottom))
+            }
+        };
+        if u64::from(width) * u64::from(height) > crate::raster::MAX_PIXELS {
+            return Err(Error::new(ErrorKind::InvalidValue(format!(
+                "a {width}x{height} bitmap exceeds the limit of {} pixels",
+                crate::raster::MAX_PIXELS
+            ))));
+        }
+
+        let polylines = path::flatten(&path, 0.1 / scale)
+            .into_iter()
+            .map(|polyline| {
+                polyline
+                    .into_iter()
+                    .map(|(x, y)| (x * scale - left, top - y * scale))
+                    .collect()
+            })
+            .collect::<Vec<_>>();
+        Ok(crate::raster::rasterize(&polylines, width, height))
+    }
+
+    /// Returns a canonical hash of the glyph's decomposed outline, optionally including its
+    /// advance and anchors.
+    ///
+    /// See [`crate::hash::outline_hash`].
+    pub fn outline_hash(&self, options: HashOptions) -> Result<String> {
+        let data = self.load()?;
+        let contours = self.decomposed_contours()?;
+        Ok(crate::hash::outline_hash(&contours, &data, options))
+    }
+
+    /// Checks that all identifiers in the glyph are valid and unique.
+    pub fn validate_identifiers(&self) -> Result<Vec<IdentifierIssue>> {
+        Ok(self.load()?.validate_identifiers())
+    }
+
+    /// Assigns a random, unique identifier to every object in the cached glyph data that lacks
+    /// one, and returns the number of identifiers assigned.
+    ///
+    /// Format 1 glyphs have no identifiers and are left unchanged. The glyph is marked as dirty
+    /// if any identifier was assigned.
+    pub fn assign_missing_identifiers(&self) -> Result<usize> {
+        let mut data = self.load_with_lib()?;
+        let assigned = data.assign_missing_identifiers();
+        if assigned > 0 {
+            self.dirty.set(true);
+            self.store(data);
+        }
+        Ok(assigned)
+    }
+
+    /// Returns `true` if the cached glyph data was modified since it was loaded or last saved.
+    pub fn is_dirty(&self) -> bool {
+        self.dirty.get()
+    }
+
+    /// Applies `f` to the cached glyph data and marks the glyph as dirty if `f` changed it.
+    ///
+    /// The glyph is loaded, including its lib, before `f` is called. This is the general editing
+    /// entry point; the `set_*`, `add_*` and `remove_*` methods are shorthands built on it.
+    pub fn edit<R>(&self, f: impl FnOnce(&mut GlifData) -> R) -> Result<R> {
+        let original = self.load_with_lib()?;
+        let mut data = original.clone();
+        let result = f(&mut data);
+        if data != original {
+            if data.unicodes != original.unicodes {
+                self.font.cmap.take();
+            }
+            self.dirty.set(true);
+            self.store(data);
+        }
+        Ok(result)
+    }
+
+    /// Sets the glyph's advance.
+    pub fn set_advance(&self, advance: Option<Advance>) -> Result<()> {
+        self.edit(|data| data.advance = advance)
+    }
+
+    /// Sets the glyph's Unicode code points.
+    pub fn set_unicodes(&self, unicodes: Vec<u32>) -> Result<()> {
+        self.edit(|data| data.unicodes = unicodes)
+    }
+
+    /// Sets the glyph's note.
+    pub fn set_note(&self, note: Option<String>) -> Result<()> {
+        self.edit(|data| data.note = note)
+    }
+
+    /// Replaces the glyph's lib.
+    pub fn set_lib(&self, lib: Option<Value>) -> Result<()> {
+        self.edit(|data| data.lib = lib)
+    }
+
+    /// Appends an anchor to the glyph.
+    pub fn add_anchor(&self, anchor: Anchor) -> Result<()> {
+        self.edit(|data| data.anchors.push(anchor))
+    }
+
+    /// Removes and returns the anchor at `index`, or returns `None` if it is out of range.
+    pub fn remove_anchor(&self, index: usize) -> Result<Option<Anchor>> {
+        self.edit(|data| remove_at(&mut data.anchors, index))
+    }
+
+    /// Appends a guideline to the glyph.
+    pub fn add_guideline(&self, guideline: Guideline) -> Result<()> {
+        self.edit(|data| data.guidelines.push(guideline))
+    }
+
+    /// Removes and returns the guideline at `index`, or returns `None` if it is out of range.
+    pub fn remove_guideline(&self, index: usize) -> Result<Option<Guideline>> {
+        self.edit(|data| remove_at(&mut data.guidelines, index))
+    }
+
+    /// Appends a contour to the glyph's outline, creating the outline if needed.
+    pub fn add_contour(&self, contour: Contour) -> Result<()> {
+        self.edit(|data| data.outline.get_or_insert_default().contours.push(contour))
+    }
+
+    /// Removes and returns the contour at `index`, or returns `None` if it is out of range.
+    pub fn remove_contour(&self, index: usize) -> Result<Option<Contour>> {
+        self.edit(|data| {
+            data.outline
+                .as_mut()
+                .and_then(|outline| remove_at(&mut outline.contours, index))
+        })
+    }
+
+    /// Appends a component to the glyph's outline, creating the outline if needed.
+    pub fn add_component(&self, component: Component) -> Result<()> {
+        self.edit(|data| {
+            data.outline
+                .get_or_insert_default()
+                .components
+                .push(component)
+        })
+    }
+
+    /// Removes and returns the component at `index`, or returns `None` if it is out of range.
+    pub fn remove_component(&self, index: usize) -> Result<Option<Component>> {
+        self.edit(|data| {
+            data.outline
+                .as_mut()
+                .and_then(|outline| remove_at(&mut outline.components, index))
+        })
+    }
+
+    /// Loads glyph data like [`Glyph::load`] and resolves a lazily parsed lib in the cache.
+    fn load_with_lib(&self) -> Result<GlifData> {
+        let mut data = self.load()?;
+        if data.lib_source.is_some() {
+            data.resolve_lib()?;
+            if let Some(cached) = self.cache.borrow_mut().as_mut() {
+                cached.lib = data.lib.clone();
+                cached.lib_source = None;
+            }
+        }
+        Ok(data)
+    }
+
+    /// Appends the glyph's contours and those of its components to `contours`, transformed by
+    /// `transform`.
+    ///
+    /// `stack` holds the names of the glyphs being decomposed, so that components referring back
+    /// to one of them are skipped instead of recursing forever.
+    fn decompose_into(
+        &self,
+        transform: &Transform,
+        stack: &mut Vec<String>,
+        contours: &mut Vec<Contour>,
+    ) -> Result<()> {
+        let data = self.load()?;
+        contours.extend(
+            data.drawn_contours()
+                .map(|contour| contour.transformed(transform)),
+        );
+        for component in data.components() {
+            let Some(base) = &component.base else {
+                continue;
+            };
+            let Some(glyph) = self.font.glyph(base).filter(|_| !stack.contains(base)) else {
+                continue;
+            };
+            stack.push(base.clone());
+            glyph.decompose_into(&transform.compose(&component.transform()), stack, contours)?;
+            stack.pop();
+        }
+        Ok(())
     }
 
     /// Loads and caches glyph data on first access.
@@ -202,12 +1149,13 @@ impl Glyph {
     /// If the glyph has already been loaded, returns the cached data. Otherwise, parses the
     /// corresponding `.glif` file using the font's provider and stores it for subsequent use.
     fn load(&self) -> Result<GlifData> {
-        if let Some(data) = &*self.cache.borrow() {
-            return Ok(data.clone());
+        let cached = self.cache.borrow().clone();
+        if let Some(data) = cached {
+            self.font.cache.borrow_mut().touch(&self.name, cost(&data));
+            return Ok(data);
         }
 
-        let contents = self.font.contents();
-        let file_name = contents.get(&self.name).ok_or(
+        let file_name = self.font.contents().get(&self.name).cloned().ok_or(
             Error::new(ErrorKind::MissingAttribute(self.name().to_owned())).with_path(
                 self.font
                     .provider()
@@ -217,10 +1165,718 @@ impl Glyph {
             ),
         )?;
 
-        let parser = GlifParser::new(self.font.provider().clone())?;
-        let parsed = parser.parse_glif(file_name)?;
+        let path = UfoRelativePath::GlifFile(file_name).to_pathbuf();
+        let provider = self.font.provider();
+        let (stamp, data) = FileStamp::read(&**provider, &path)?;
+        let parser = GlifParser::with_options(provider.clone(), self.font.options)?;
+        let parsed = parser.parse_glif_bytes(&data)?;
 
-        *self.cache.borrow_mut() = Some(parsed.clone());
+        self.stamp.set(Some(stamp));
+        self.store(parsed.clone());
         Ok(parsed)
     }
+
+    /// Caches `data` as the most recently used glyph and applies the font's cache policy.
+    fn store(&self, data: GlifData) {
+        self.font.cache.borrow_mut().touch(&self.name, cost(&data));
+        *self.cache.borrow_mut() = Some(data);
+        self.font.evict();
+    }
+}
+
+/// Returns the weight of a glyph under [`CachePolicy::Points`]: its number of points plus its
+/// number of components.
+fn cost(data: &GlifData) -> usize {
+    data.outline.as_ref().map_or(0, |outline| {
+        outline
+            .contours
+            .iter()
+            .map(|contour| contour.points.len())
+            .sum::<usize>()
+            + outline.components.len()
+    })
+}
+
+/// Removes the item at `index` from `items`, or returns `None` if it is out of range.
+fn remove_at<T>(items: &mut Vec<T>, index: usize) -> Option<T> {
+    (index < items.len()).then(|| items.remove(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::provider::{MemoryProvider, WritableProvider};
+    use crate::test_utils::MockProvider;
+
+    const CONTENTS: &[u8] = br#"<?xml version='1.0'?><plist version='1.0'><dict>
+        <key>A</key><string>A_.glif</string>
+        <key>B</key><string>B_.glif</string>
+    </dict></plist>"#;
+
+    fn font() -> Rc<Font> {
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="2"><advance width="500"/>
+                    <lib><dict><key>public.markColor</key><string>0,1,0,1</string></dict></lib>
+                </glyph>"#,
+            );
+        Font::new(provider).unwrap()
+    }
+
+    #[test]
+    fn test_glyph_lookup() {
+        let font = font();
+        assert_eq!(font.len(), 2);
+        assert!(font.contains("A"));
+        assert!(!font.contains("nonexistent"));
+        assert!(font.glyph("A").is_some());
+        assert!(font.glyph("nonexistent").is_none());
+        assert!(font.glyphs.borrow().get("nonexistent").is_none());
+
+        let err = font.try_glyph("nonexistent").err().unwrap();
+        assert_eq!(err.kind(), &ErrorKind::GlyphNotFound("nonexistent".into()));
+    }
+
+    #[test]
+    fn test_malformed_lib() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(&UfoRelativePath::Lib.to_pathbuf(), b"<plist><dict>");
+        let font = Font::new(provider).unwrap();
+        assert_eq!(font.glyph_order(), ["A", "B"]);
+        assert_eq!(font.lib().err().unwrap().kind(), &ErrorKind::Plist);
+        assert!(font.glyph("A").is_some());
+    }
+
+    #[test]
+    fn test_glyph_order() {
+        let font = font();
+        assert_eq!(font.glyph_order(), ["A", "B"]);
+
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(
+                &UfoRelativePath::Lib.to_pathbuf(),
+                br#"<?xml version='1.0'?><plist version='1.0'><dict>
+                    <key>public.glyphOrder</key><array><string>B</string></array>
+                </dict></plist>"#,
+            );
+        let font = Font::new(provider).unwrap();
+        assert_eq!(font.glyph_order(), ["B", "A"]);
+        assert_eq!(font.glyphs().keys().collect::<Vec<_>>(), ["B", "A"]);
+        assert_eq!(
+            font.iter().map(|g| g.name().to_owned()).collect::<Vec<_>>(),
+            ["B", "A"]
+        );
+    }
+
+    #[test]
+    fn test_glyphs_fills_partial_cache() {
+        let font = font();
+        let a = font.glyph("A").unwrap();
+        let glyphs = font.glyphs();
+        assert_eq!(glyphs.len(), 2);
+        assert!(Rc::ptr_eq(&a, &glyphs["A"]));
+    }
+
+    #[test]
+    fn test_save_writes_dirty_glyphs() {
+        let provider = MemoryProvider::new();
+        provider
+            .write(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .unwrap();
+        let a_path = UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf();
+        let b_path = UfoRelativePath::GlifFile("B_.glif".into()).to_pathbuf();
+        let b_source = br#"<glyph name="B" format="2"><anchor x="0" y="0"/></glyph>"#;
+        provider
+            .write(
+                &a_path,
+                br#"<glyph name="A" format="2"><anchor x="0" y="0"/></glyph>"#,
+            )
+            .unwrap();
+        provider.write(&b_path, b_source).unwrap();
+
+        let font = Font::new(provider.clone()).unwrap();
+        font.glyph("B").unwrap().load().unwrap();
+        let a = font.glyph("A").unwrap();
+        assert_eq!(a.assign_missing_identifiers().unwrap(), 1);
+        assert!(a.is_dirty());
+
+        font.save().unwrap();
+        assert!(!a.is_dirty());
+        assert_eq!(provider.read(&b_path).unwrap(), b_source);
+        assert_eq!(
+            provider
+                .read(&UfoRelativePath::Contents.to_pathbuf())
+                .unwrap(),
+            CONTENTS
+        );
+
+        let saved = Font::new(provider).unwrap();
+        let anchors = saved.glyph("A").unwrap().anchors().unwrap();
+        assert_eq!(anchors[0].identifier, a.anchors().unwrap()[0].identifier);
+    }
+
+    #[test]
+    fn test_glyph_editing() {
+        let font = font();
+        let a = font.glyph("A").unwrap();
+        assert!(!font.is_dirty());
+
+        assert!(a.remove_anchor(0).unwrap().is_none());
+        a.set_note(a.note().unwrap()).unwrap();
+        assert!(!font.is_dirty());
+
+        a.set_unicodes(vec![0x41]).unwrap();
+        a.add_anchor(Anchor {
+            x: Some(250.0),
+            y: Some(700.0),
+            name: Some("top".into()),
+            ..Default::default()
+        })
+        .unwrap();
+        a.add_contour(Contour::default()).unwrap();
+        a.edit(|data| data.anchors[0].x = Some(300.0)).unwrap();
+
+        assert_eq!(font.dirty_glyphs(), ["A"]);
+        assert_eq!(a.unicodes().unwrap(), [0x41]);
+        assert_eq!(a.anchors().unwrap()[0].x, Some(300.0));
+        assert_eq!(a.outline().unwrap().unwrap().contours.len(), 1);
+        assert!(a.mark_color().unwrap().is_some());
+
+        assert!(a.remove_anchor(1).unwrap().is_none());
+        assert!(a.remove_anchor(0).unwrap().is_some());
+        assert!(a.anchors().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_save_read_only_provider() {
+        let err = font().save().unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::Io);
+    }
+
+    #[test]
+    fn test_glyph_lazy_lib() {
+        let font = font();
+        let glyph = font.glyph("A").unwrap();
+        assert_eq!(glyph.advance().unwrap().unwrap().width, Some(500.0));
+        assert!(glyph.cache.borrow().as_ref().unwrap().lib.is_none());
+
+        let color = glyph.mark_color().unwrap().unwrap();
+        assert_eq!(color.to_tuple(), (0.0, 1.0, 0.0, 1.0));
+        assert!(glyph.cache.borrow().as_ref().unwrap().lib.is_some());
+
+        let font = self::font();
+        assert!(font.glyph("A").unwrap().data().unwrap().lib.is_some());
+    }
+
+    #[test]
+    fn test_check_consistency() {
+        let provider = MemoryProvider::new();
+        provider
+            .write(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .unwrap();
+        for (file_name, glif) in [
+            ("A_.glif", "<glyph name='A.alt' format='2'/>"),
+            ("B_.glif", "<glyph name='B' format='2'><<<<<<< HEAD"),
+            ("C_.glif", "<glyph name='C' format='2'/>"),
+        ] {
+            provider
+                .write(
+                    &UfoRelativePath::GlifFile(file_name.into()).to_pathbuf(),
+                    glif.as_bytes(),
+                )
+                .unwrap();
+        }
+        let font = Font::new(provider.clone()).unwrap();
+
+        let issues = font.check_consistency().unwrap();
+        assert_eq!(issues.len(), 3);
+        assert_eq!(
+            issues[0],
+            ConsistencyIssue::OrphanFile {
+                file_name: "C_.glif".into()
+            }
+        );
+        assert_eq!(
+            issues[1],
+            ConsistencyIssue::NameMismatch {
+                glyph: "A".into(),
+                file_name: "A_.glif".into(),
+                name: "A.alt".into()
+            }
+        );
+        assert!(matches!(&issues[2], ConsistencyIssue::Unreadable { glyph, .. } if glyph == "B"));
+
+        assert!(self::font().check_consistency().is_err());
+    }
+
+    #[test]
+    fn test_check_components() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="2"/>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("B_.glif".into()).to_pathbuf(),
+                br#"<glyph name="B" format="2"><outline>
+                    <component base="A"/><component base="C" xScale="0"/>
+                </outline></glyph>"#,
+            );
+        let font = Font::new(provider).unwrap();
+
+        let issues = font.check_components().unwrap();
+        assert_eq!(
+            issues,
+            vec![
+                ComponentIssue::MissingBase {
+                    glyph: "B".into(),
+                    index: 1,
+                    base: "C".into()
+                },
+                ComponentIssue::DegenerateTransform {
+                    glyph: "B".into(),
+                    index: 1,
+                    base: Some("C".into())
+                },
+            ]
+        );
+        let issues = self::font().check_components().unwrap();
+        assert_eq!(issues.len(), 1);
+        assert!(matches!(&issues[0], ComponentIssue::Unreadable { glyph, .. } if glyph == "B"));
+    }
+
+    #[test]
+    fn test_decompose_and_outline_hashes() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(
+                &UfoRelativePath::Contents.to_pathbuf(),
+                br#"<plist version='1.0'><dict>
+                    <key>A</key><string>A_.glif</string>
+                    <key>B</key><string>B_.glif</string>
+                    <key>C</key><string>C_.glif</string>
+                    <key>space</key><string>space.glif</string>
+                    <key>broken</key><string>broken.glif</string>
+                </dict></plist>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="2"><outline>
+                    <contour><point x="0" y="0" type="line"/><point x="10" y="20" type="line"/></contour>
+                    <component base="C"/>
+                </outline></glyph>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("B_.glif".into()).to_pathbuf(),
+                br#"<glyph name="B" format="2"><outline>
+                    <component base="A" xScale="2" xOffset="5"/><component base="missing"/>
+                </outline></glyph>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("C_.glif".into()).to_pathbuf(),
+                br#"<glyph name="C" format="2"><outline><component base="A"/></outline></glyph>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("space.glif".into()).to_pathbuf(),
+                br#"<glyph name="space" format="2"/>"#,
+            );
+        let font = Font::new(provider).unwrap();
+
+        // A -> C -> A is a cycle, so C draws only the contour of A.
+        let b = font.glyph("B").unwrap().decomposed_contours().unwrap();
+        assert_eq!(b.len(), 1);
+        let points = b[0].points.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>();
+        assert_eq!(points, [(Some(5.0), Some(0.0)), (Some(25.0), Some(20.0))]);
+
+        let options = HashOptions::default();
+        assert_eq!(
+            font.glyph("A").unwrap().outline_hash(options).unwrap(),
+            font.glyph("C").unwrap().outline_hash(options).unwrap()
+        );
+        assert_eq!(font.duplicate_outlines(options), [["A", "C"]]);
+        let options = options.with_advance(true);
+        assert_eq!(font.duplicate_outlines(options), [["A", "C"]]);
+    }
+
+    #[test]
+    fn test_decompose_skips_format_1_anchors() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="1"><outline>
+                    <contour><point x="250" y="700" type="move" name="top"/></contour>
+                    <contour><point x="0" y="0" type="line"/><point x="10" y="20" type="line"/></contour>
+                </outline></glyph>"#,
+            );
+        let font = Font::new(provider).unwrap();
+        let a = font.glyph("A").unwrap();
+
+        assert_eq!(a.decomposed_contours().unwrap().len(), 1);
+        let bounds = a.bounds().unwrap().unwrap();
+        assert_eq!((bounds.x_max, bounds.y_max), (10.0, 20.0));
+        assert_eq!(a.to_svg_path(false).unwrap(), "M0 0L10 20Z");
+    }
+
+    #[test]
+    fn test_sidebearings() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="2"><advance width="300" height="1000"/><outline>
+                    <contour>
+                        <point x="50" y="0" type="line"/>
+                        <point x="250" y="0" type="line"/>
+                        <point x="150" y="600" type="line"/>
+                    </contour>
+                </outline>
+                <lib><dict><key>public.verticalOrigin</key><integer>800</integer></dict></lib>
+                </glyph>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("B_.glif".into()).to_pathbuf(),
+                br#"<glyph name="B" format="2"><advance width="400"/><outline>
+                    <component base="A" xOffset="20"/>
+                </outline></glyph>"#,
+            );
+        let font = Font::new(provider).unwrap();
+        let a = font.glyph("A").unwrap();
+        assert_eq!(
+            a.sidebearings().unwrap(),
+            Some(Sidebearings {
+                left: 50.0,
+                right: 50.0,
+                top: 200.0,
+                bottom: 200.0
+            })
+        );
+        assert_eq!(a.sidebearings_at(300.0).unwrap(), Some((100.0, 100.0)));
+
+        let b = font.glyph("B").unwrap();
+        assert_eq!(b.bounds().unwrap().unwrap().x_min, 70.0);
+        let sidebearings = b.sidebearings().unwrap().unwrap();
+        assert_eq!((sidebearings.left, sidebearings.right), (70.0, 130.0));
+        assert_eq!(sidebearings.top, -600.0);
+        assert_eq!(b.sidebearings_at(700.0).unwrap(), None);
+    }
+
+    #[test]
+    fn test_svg_export() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(
+                &UfoRelativePath::FontInfo.to_pathbuf(),
+                br#"<plist version='1.0'><dict>
+                    <key>unitsPerEm</key><integer>2000</integer>
+                </dict></plist>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="2"><advance width="1000"/><outline>
+                    <contour>
+                        <point x="0" y="0" type="line"/>
+                        <point x="100" y="0" type="line"/>
+                        <point x="50" y="100" type="line"/>
+                    </contour>
+                </outline></glyph>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("B_.glif".into()).to_pathbuf(),
+                br#"<glyph name="B" format="2"><outline>
+                    <component base="A" xOffset="10"/>
+                </outline></glyph>"#,
+            );
+        let font = Font::new(provider).unwrap();
+        let b = font.glyph("B").unwrap();
+        assert_eq!(b.to_svg_path(true).unwrap(), "M10 0L110 0L60 100Z");
+        assert_eq!(b.to_svg_path(false).unwrap(), "");
+
+        let svg = font
+            .to_svg_document(["A", "B"], SvgOptions::default())
+            .unwrap();
+        assert!(svg.contains(r#"viewBox="0 0 4000 2000""#));
+        assert!(svg.contains(r#"<g transform="translate(500 1500) scale(1 -1)"><title>A</title>"#));
+        assert!(svg.contains(r#"<path class="glyph" d="M10 0L110 0L60 100Z"/>"#));
+        let err = font
+            .to_svg_document(["A", "missing"], SvgOptions::default())
+            .unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::GlyphNotFound(_)));
+    }
+
+    #[test]
+    fn test_svg_export_all() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="1"><outline>
+                    <contour><point x="250" y="700" type="move" name="top"/></contour>
+                </outline></glyph>"#,
+            );
+        let font = Font::new(provider).unwrap();
+
+        // B has no file, so it is left out.
+        let svg = font
+            .to_svg_document_all(SvgOptions::default().with_anchors(true))
+            .unwrap();
+        assert!(svg.contains("<title>A</title>"));
+        assert!(!svg.contains("<title>B</title>"));
+        assert!(svg.contains(r#"<circle class="anchor" cx="250" cy="700""#));
+        assert!(font.to_svg_document(["B"], SvgOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_proof_metrics_are_cached() {
+        let provider = MemoryProvider::new();
+        let info = |upm: u32| {
+            format!("<plist><dict><key>unitsPerEm</key><integer>{upm}</integer></dict></plist>")
+        };
+        let info_path = UfoRelativePath::FontInfo.to_pathbuf();
+        provider
+            .write(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .unwrap();
+        provider.write(&info_path, info(2000).as_bytes()).unwrap();
+        let font = Font::new(provider.clone()).unwrap();
+        assert_eq!(font.proof_metrics().unwrap().units_per_em, 2000.0);
+
+        provider.write(&info_path, info(1000).as_bytes()).unwrap();
+        assert_eq!(font.proof_metrics().unwrap().units_per_em, 2000.0);
+        font.reload().unwrap();
+        assert_eq!(font.proof_metrics().unwrap().units_per_em, 1000.0);
+    }
+
+    #[test]
+    fn test_rasterize() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(
+                &UfoRelativePath::FontInfo.to_pathbuf(),
+                br#"<plist version='1.0'><dict>
+                    <key>descender</key><integer>-200</integer>
+                </dict></plist>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="2"><advance width="1000"/><outline>
+                    <contour>
+                        <point x="0" y="0" type="line"/>
+                        <point x="0" y="500" type="line"/>
+                        <point x="500" y="500" type="line"/>
+                        <point x="500" y="0" type="line"/>
+                    </contour>
+                </outline></glyph>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("B_.glif".into()).to_pathbuf(),
+                br#"<glyph name="B" format="2"><advance width="600"/><outline>
+                    <component base="A" xOffset="100" yOffset="-200"/>
+                </outline></glyph>"#,
+            );
+        let font = Font::new(provider).unwrap();
+        let options = RasterOptions::default().with_size(10.0);
+
+        // The baseline is 8 pixels down, below the ascender of 800 units.
+        let a = font.glyph("A").unwrap().rasterize(options).unwrap();
+        assert_eq!((a.width, a.height), (10, 10));
+        let ink = |bitmap: &Bitmap, x, y| bitmap.get(x, y) == Some(255);
+        assert!(ink(&a, 0, 3) && ink(&a, 4, 7));
+        assert!(!ink(&a, 5, 3) && !ink(&a, 0, 2) && !ink(&a, 0, 8));
+
+        let b = font.glyph("B").unwrap().rasterize(options).unwrap();
+        assert_eq!((b.width, b.height), (6, 10));
+        assert!(ink(&b, 1, 5) && ink(&b, 5, 9) && !ink(&b, 0, 5));
+
+        let glyph = font.glyph("A").unwrap();
+        for size in [0.0, f64::NAN, crate::raster::MAX_SIZE + 1.0] {
+            let err = glyph.rasterize(options.with_size(size)).unwrap_err();
+            assert!(matches!(err.kind(), ErrorKind::InvalidValue(_)));
+        }
+
+        let options = options.with_frame(RasterFrame::Bounds);
+        let b = font.glyph("B").unwrap().rasterize(options).unwrap();
+        assert_eq!((b.width, b.height), (5, 5));
+        assert!(b.data.iter().all(|&c| c == 255));
+    }
+
+    #[test]
+    fn test_rasterize_rejects_large_bitmaps() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="2"><advance width="100000"/></glyph>"#,
+            );
+        let font = Font::new(provider).unwrap();
+        let options = RasterOptions::default().with_size(crate::raster::MAX_SIZE);
+        let err = font.glyph("A").unwrap().rasterize(options).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::InvalidValue(_)));
+    }
+
+    #[test]
+    fn test_cmap_queries() {
+        let provider = MemoryProvider::new();
+        provider
+            .write(
+                &UfoRelativePath::Contents.to_pathbuf(),
+                br#"<plist version='1.0'><dict>
+                    <key>A</key><string>A_.glif</string>
+                    <key>A.alt</key><string>A_.alt.glif</string>
+                    <key>B</key><string>B_.glif</string>
+                    <key>ring</key><string>ring.glif</string>
+                </dict></plist>"#,
+            )
+            .unwrap();
+        for (file_name, unicodes) in [
+            ("A_.glif", "<unicode hex='0041'/>"),
+            ("A_.alt.glif", "<unicode hex='0041'/><unicode hex='0061'/>"),
+            ("B_.glif", "<unicode hex='0042'/>"),
+            ("ring.glif", ""),
+        ] {
+            provider
+                .write(
+                    &UfoRelativePath::GlifFile(file_name.into()).to_pathbuf(),
+                    format!("<glyph name='x' format='2'>{unicodes}</glyph>").as_bytes(),
+                )
+                .unwrap();
+        }
+        let font = Font::new(provider.clone()).unwrap();
+
+        let cmap = font.cmap();
+        assert_eq!(cmap.keys().copied().collect::<Vec<_>>(), [0x41, 0x42, 0x61]);
+        assert_eq!(
+            font.cmap_conflicts().keys().copied().collect::<Vec<_>>(),
+            [0x41]
+        );
+        assert_eq!(font.glyphs_for(0x41), ["A", "A.alt"]);
+        assert!(font.glyphs_for(0x43).is_empty());
+        assert_eq!(font.glyphs_in_range(0x42..=0x7A), ["B", "A.alt"]);
+        assert_eq!(font.unencoded_glyphs().unwrap(), ["ring"]);
+        assert_eq!(font.coverage([0x41, 0x43]).missing, [0x43]);
+
+        font.glyph("B").unwrap().set_unicodes(vec![0x43]).unwrap();
+        assert_eq!(font.glyphs_for(0x43), ["B"]);
+        assert!(font.glyphs_for(0x42).is_empty());
+
+        let path = UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf();
+        provider.write(&path, b"<glyph").unwrap();
+        font.glyph("A").unwrap().invalidate();
+        assert_eq!(font.glyphs_for(0x41), ["A.alt"]);
+    }
+
+    #[test]
+    fn test_cache_policy() {
+        let provider = MemoryProvider::new();
+        provider
+            .write(&UfoRelativePath::Contents.to_pathbuf(), CONTENTS)
+            .unwrap();
+        for name in ["A", "B"] {
+            provider
+                .write(
+                    &UfoRelativePath::GlifFile(format!("{name}_.glif")).to_pathbuf(),
+                    format!(r#"<glyph name="{name}" format="2"><anchor x="0" y="0"/></glyph>"#)
+                        .as_bytes(),
+                )
+                .unwrap();
+        }
+        let font = Font::new(provider).unwrap();
+        let a = font.glyph("A").unwrap();
+        let b = font.glyph("B").unwrap();
+        a.load().unwrap();
+        b.load().unwrap();
+        assert_eq!(font.cache_len(), 2);
+
+        font.set_cache_policy(CachePolicy::Glyphs(1));
+        assert!(!a.is_loaded());
+        assert!(b.is_loaded());
+        a.load().unwrap();
+        assert!(a.is_loaded());
+        assert!(!b.is_loaded());
+
+        a.set_unicodes(vec![0x41]).unwrap();
+        font.set_cache_policy(CachePolicy::None);
+        assert_eq!(b.unicodes().unwrap(), Vec::<u32>::new());
+        assert!(!b.is_loaded());
+        assert_eq!(a.unicodes().unwrap(), vec![0x41]);
+        assert_eq!(font.cache_len(), 1);
+
+        assert_eq!(b.assign_missing_identifiers().unwrap(), 1);
+        assert!(b.is_dirty());
+        assert!(b.anchors().unwrap()[0].identifier.is_some());
+    }
+
+    #[test]
+    fn test_reload_and_refresh() {
+        let provider = MemoryProvider::new();
+        let contents_path = UfoRelativePath::Contents.to_pathbuf();
+        let a_path = UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf();
+        provider.write(&contents_path, CONTENTS).unwrap();
+        provider
+            .write(
+                &a_path,
+                br#"<glyph name="A" format="2"><advance width="500"/></glyph>"#,
+            )
+            .unwrap();
+        provider
+            .write(
+                &UfoRelativePath::GlifFile("B_.glif".into()).to_pathbuf(),
+                br#"<glyph name="B" format="2"/>"#,
+            )
+            .unwrap();
+
+        let font = Font::new(provider.clone()).unwrap();
+        let a = font.glyph("A").unwrap();
+        let b = font.glyph("B").unwrap();
+        assert!(!a.is_stale().unwrap());
+        assert_eq!(a.advance().unwrap().unwrap().width, Some(500.0));
+        b.load().unwrap();
+        assert!(!font.is_stale().unwrap());
+        assert!(font.refresh().unwrap().is_empty());
+
+        provider
+            .write(
+                &a_path,
+                br#"<glyph name="A" format="2"><advance width="600"/></glyph>"#,
+            )
+            .unwrap();
+        assert!(a.is_stale().unwrap());
+        assert_eq!(font.stale_glyphs().unwrap(), vec!["A"]);
+        assert_eq!(font.refresh().unwrap(), vec!["A"]);
+        assert!(!a.is_loaded());
+        assert!(b.is_loaded());
+        assert_eq!(a.advance().unwrap().unwrap().width, Some(600.0));
+
+        a.set_advance(None).unwrap();
+        a.reload().unwrap();
+        assert!(!a.is_dirty());
+        assert_eq!(a.advance().unwrap().unwrap().width, Some(600.0));
+
+        provider
+            .write(
+                &contents_path,
+                br#"<plist version='1.0'><dict><key>A</key><string>A_.glif</string></dict></plist>"#,
+            )
+            .unwrap();
+        assert!(font.is_stale().unwrap());
+        assert_eq!(font.refresh().unwrap(), vec!["A", "B"]);
+        assert_eq!(font.glyph_order(), vec!["A"]);
+        assert!(font.glyph("B").is_none());
+        assert!(!font.is_stale().unwrap());
+    }
 }
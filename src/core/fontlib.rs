@@ -0,0 +1,327 @@
+//! Typed access to a font's `lib.plist`.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use indexmap::IndexMap;
+use plist::{Dictionary, Value};
+use serde::Serialize;
+
+use crate::constants::ufo::lib_key;
+use crate::error::{Error, ErrorKind, Result};
+use crate::paths::UfoRelativePath;
+
+/// The contents of a font's `lib.plist`, with typed accessors for the standard public keys.
+///
+/// The accessors return an empty value if their key is absent, and an error if it is present
+/// but does not match the structure required by the UFO specification.
+#[derive(Clone, Debug, Default, Serialize)]
+#[serde(transparent)]
+pub struct FontLib {
+    dict: Dictionary,
+}
+
+/// The OpenType glyph class of a glyph in `public.openTypeCategories`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum OpenTypeCategory {
+    Unassigned,
+    Base,
+    Ligature,
+    Mark,
+    Component,
+}
+
+impl FontLib {
+    /// Wraps a parsed `lib.plist` dictionary.
+    pub fn new(dict: Dictionary) -> Self {
+        Self { dict }
+    }
+
+    /// Returns the underlying dictionary.
+    pub fn as_dictionary(&self) -> &Dictionary {
+        &self.dict
+    }
+
+    /// Consumes the lib and returns the underlying dictionary.
+    pub fn into_dictionary(self) -> Dictionary {
+        self.dict
+    }
+
+    /// Returns the raw value of a lib key.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        self.dict.get(key)
+    }
+
+    /// Returns the glyph names listed under `public.glyphOrder`.
+    ///
+    /// Entries that are not strings are skipped, so one bad entry does not discard the order.
+    pub fn glyph_order(&self) -> Result<Vec<String>> {
+        let key = lib_key::PUBLIC_GLYPH_ORDER;
+        match self.dict.get(key) {
+            None => Ok(Vec::new()),
+            Some(Value::Array(values)) => Ok(values
+                .iter()
+                .filter_map(Value::as_string)
+                .map(str::to_owned)
+                .collect()),
+            Some(_) => Err(invalid(key, "expected an array")),
+        }
+    }
+
+    /// Returns the glyph names listed under `public.skipExportGlyphs`.
+    pub fn skip_export_glyphs(&self) -> Result<Vec<String>> {
+        self.string_array(lib_key::PUBLIC_SKIP_EXPORT_GLYPHS)
+    }
+
+    /// Returns the mapping of glyph names to production names under `public.postscriptNames`.
+    pub fn postscript_names(&self) -> Result<IndexMap<String, String>> {
+        let key = lib_key::PUBLIC_POSTSCRIPT_NAMES;
+        self.dictionary(key)?
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| match value {
+                Value::String(s) => Ok((name.clone(), s.clone())),
+                _ => Err(invalid(key, "values must be strings")),
+            })
+            .collect()
+    }
+
+    /// Returns the mapping of glyph names to OpenType categories under
+    /// `public.openTypeCategories`.
+    pub fn opentype_categories(&self) -> Result<IndexMap<String, OpenTypeCategory>> {
+        let key = lib_key::PUBLIC_OPENTYPE_CATEGORIES;
+        self.dictionary(key)?
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| match value {
+                Value::String(s) => Ok((name.clone(), s.parse()?)),
+                _ => Err(invalid(key, "values must be strings")),
+            })
+            .collect()
+    }
+
+    /// Returns the mapping under `public.unicodeVariationSequences`, from variation selector to
+    /// base code point to glyph name.
+    pub fn unicode_variation_sequences(&self) -> Result<BTreeMap<u32, BTreeMap<u32, String>>> {
+        let key = lib_key::PUBLIC_UNICODE_VARIATION_SEQUENCES;
+        let parse_hex = |s: &str| {
+            u32::from_str_radix(s, 16)
+                .map_err(|e| invalid(key, "keys must be hex code points").with_cause(e))
+        };
+        self.dictionary(key)?
+            .into_iter()
+            .flatten()
+            .map(|(selector, sequences)| {
+                let sequences = sequences
+                    .as_dictionary()
+                    .ok_or_else(|| invalid(key, "values must be dictionaries"))?
+                    .iter()
+                    .map(|(base, name)| match name {
+                        Value::String(name) => Ok((parse_hex(base)?, name.clone())),
+                        _ => Err(invalid(key, "glyph names must be strings")),
+                    })
+                    .collect::<Result<_>>()?;
+                Ok((parse_hex(selector)?, sequences))
+            })
+            .collect()
+    }
+
+    /// Returns the object libs under `public.objectLibs`, keyed by object identifier.
+    pub fn object_libs(&self) -> Result<IndexMap<String, Dictionary>> {
+        object_libs(self.dict.get(lib_key::PUBLIC_OBJECT_LIBS))
+    }
+
+    /// Returns the object lib for the font-level object with the given identifier.
+    pub fn object_lib(&self, identifier: &str) -> Result<Option<Dictionary>> {
+        object_lib(self.dict.get(lib_key::PUBLIC_OBJECT_LIBS), identifier)
+    }
+
+    /// Returns the dictionary stored under `key`, if present.
+    fn dictionary(&self, key: &str) -> Result<Option<&Dictionary>> {
+        match self.dict.get(key) {
+            None => Ok(None),
+            Some(Value::Dictionary(dict)) => Ok(Some(dict)),
+            Some(_) => Err(invalid(key, "expected a dictionary")),
+        }
+    }
+
+    /// Returns the array of strings stored under `key`, or an empty one if the key is absent.
+    fn string_array(&self, key: &str) -> Result<Vec<String>> {
+        match self.dict.get(key) {
+            None => Ok(Vec::new()),
+            Some(Value::Array(values)) => values
+                .iter()
+                .map(|v| {
+                    v.as_string()
+                        .map(str::to_owned)
+                        .ok_or_else(|| invalid(key, "values must be strings"))
+                })
+                .collect(),
+            Some(_) => Err(invalid(key, "expected an array")),
+        }
+    }
+}
+
+impl From<Dictionary> for FontLib {
+    fn from(dict: Dictionary) -> Self {
+        Self::new(dict)
+    }
+}
+
+/// Reads a `public.objectLibs` value into a map of object identifiers to libs.
+pub(crate) fn object_libs(value: Option<&Value>) -> Result<IndexMap<String, Dictionary>> {
+    let key = lib_key::PUBLIC_OBJECT_LIBS;
+    match value {
+        None => Ok(IndexMap::new()),
+        Some(Value::Dictionary(dict)) => dict
+            .iter()
+            .map(|(identifier, lib)| match lib {
+                Value::Dictionary(lib) => Ok((identifier.clone(), lib.clone())),
+                _ => Err(invalid(key, "values must be dictionaries")),
+            })
+            .collect(),
+        Some(_) => Err(invalid(key, "expected a dictionary")),
+    }
+}
+
+/// Looks up a single object lib in a `public.objectLibs` value.
+pub(crate) fn object_lib(value: Option<&Value>, identifier: &str) -> Result<Option<Dictionary>> {
+    let key = lib_key::PUBLIC_OBJECT_LIBS;
+    match value {
+        None => Ok(None),
+        Some(Value::Dictionary(dict)) => match dict.get(identifier) {
+            None => Ok(None),
+            Some(Value::Dictionary(lib)) => Ok(Some(lib.clone())),
+            Some(_) => Err(invalid(key, "values must be dictionaries")),
+        },
+        Some(_) => Err(invalid(key, "expected a dictionary")),
+    }
+}
+
+/// Creates an error for a lib key whose value does not match the specification.
+fn invalid(key: &str, reason: &str) -> Error {
+    let message = format!("invalid {}: {}", key, reason);
+    Error::new(ErrorKind::Plist)
+        .with_context(move || message)
+        .with_path(UfoRelativePath::Lib.to_pathbuf().to_string_lossy())
+}
+
+impl FromStr for OpenTypeCategory {
+    type Err = Error;
+
+    /// Parses a category name as used in `public.openTypeCategories`.
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "unassigned" => Ok(Self::Unassigned),
+            "base" => Ok(Self::Base),
+            "ligature" => Ok(Self::Ligature),
+            "mark" => Ok(Self::Mark),
+            "component" => Ok(Self::Component),
+            _ => Err(invalid(
+                lib_key::PUBLIC_OPENTYPE_CATEGORIES,
+                &format!("unknown category '{}'", s),
+            )),
+        }
+    }
+}
+
+impl fmt::Display for OpenTypeCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Unassigned => "unassigned",
+            Self::Base => "base",
+            Self::Ligature => "ligature",
+            Self::Mark => "mark",
+            Self::Component => "component",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lib(xml: &str) -> FontLib {
+        let plist = format!(
+            "<?xml version='1.0'?><plist version='1.0'><dict>{}</dict></plist>",
+            xml
+        );
+        let value = Value::from_reader_xml(plist.as_bytes()).unwrap();
+        FontLib::new(value.into_dictionary().unwrap())
+    }
+
+    #[test]
+    fn test_empty_lib() {
+        let lib = FontLib::default();
+        assert!(lib.glyph_order().unwrap().is_empty());
+        assert!(lib.skip_export_glyphs().unwrap().is_empty());
+        assert!(lib.postscript_names().unwrap().is_empty());
+        assert!(lib.opentype_categories().unwrap().is_empty());
+        assert!(lib.unicode_variation_sequences().unwrap().is_empty());
+        assert!(lib.object_libs().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_public_keys() {
+        let lib = lib(r#"
+            <key>public.glyphOrder</key><array><string>b</string><string>a</string></array>
+            <key>public.skipExportGlyphs</key><array><string>a.draft</string></array>
+            <key>public.postscriptNames</key><dict><key>a.alt</key><string>uni0061.alt</string></dict>
+            <key>public.openTypeCategories</key><dict>
+                <key>a</key><string>base</string>
+                <key>acutecomb</key><string>mark</string>
+            </dict>
+            <key>public.unicodeVariationSequences</key><dict>
+                <key>FE0E</key><dict><key>263A</key><string>smileface.text</string></dict>
+            </dict>
+            <key>public.objectLibs</key><dict>
+                <key>abc</key><dict><key>com.example</key><integer>1</integer></dict>
+            </dict>
+        "#);
+
+        assert_eq!(lib.glyph_order().unwrap(), ["b", "a"]);
+        assert_eq!(lib.skip_export_glyphs().unwrap(), ["a.draft"]);
+        assert_eq!(lib.postscript_names().unwrap()["a.alt"], "uni0061.alt");
+
+        let categories = lib.opentype_categories().unwrap();
+        assert_eq!(categories["a"], OpenTypeCategory::Base);
+        assert_eq!(categories["acutecomb"], OpenTypeCategory::Mark);
+
+        let uvs = lib.unicode_variation_sequences().unwrap();
+        assert_eq!(uvs[&0xFE0E][&0x263A], "smileface.text");
+
+        let object_libs = lib.object_libs().unwrap();
+        assert!(object_libs["abc"].contains_key("com.example"));
+        assert!(lib.object_lib("abc").unwrap().is_some());
+        assert!(lib.object_lib("xyz").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_invalid_public_keys() {
+        let lib = lib(r#"
+            <key>public.glyphOrder</key><string>a</string>
+            <key>public.skipExportGlyphs</key><array><integer>1</integer></array>
+            <key>public.openTypeCategories</key><dict><key>a</key><string>spacing</string></dict>
+            <key>public.unicodeVariationSequences</key><dict>
+                <key>XYZ</key><dict/>
+            </dict>
+        "#);
+
+        assert_eq!(lib.glyph_order().unwrap_err().kind(), &ErrorKind::Plist);
+        assert!(lib.skip_export_glyphs().is_err());
+        assert!(lib.opentype_categories().is_err());
+        assert!(lib.unicode_variation_sequences().is_err());
+    }
+
+    #[test]
+    fn test_glyph_order_skips_non_strings() {
+        let lib = lib(r#"
+            <key>public.glyphOrder</key>
+            <array><string>b</string><integer>1</integer><string>a</string></array>
+        "#);
+        assert_eq!(lib.glyph_order().unwrap(), ["b", "a"]);
+    }
+}
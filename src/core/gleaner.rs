@@ -1,9 +1,9 @@
 //! High-level interface for eagerly parsing UFO GLIF files.
 
-use std::collections::HashMap;
+use indexmap::IndexMap;
 
 use crate::error::Result;
-use crate::glif::{GlifData, GlifParser};
+use crate::glif::{GlifData, GlifParser, ParseOptions};
 use crate::provider::ProviderHandle;
 
 /// Eager batch parser for UFO GLIF files.
@@ -31,16 +31,21 @@ use crate::provider::ProviderHandle;
 /// Returns an [`Error`] if the `contents.plist` cannot be read or parsed,
 /// or if the GLIF parser cannot be initialized.
 pub struct Gleaner {
-    contents: HashMap<String, String>,
+    contents: IndexMap<String, String>,
     glif_parser: GlifParser,
 }
 
 impl Gleaner {
     /// Constructs a new [`Gleaner`] from a given [`Provider`] implementation.
     pub fn new(provider: ProviderHandle) -> Result<Self> {
+        Self::with_options(provider, ParseOptions::default())
+    }
+
+    /// Constructs a new [`Gleaner`] that parses GLIF files with the given [`ParseOptions`].
+    pub fn with_options(provider: ProviderHandle, options: ParseOptions) -> Result<Self> {
         // fs is cheap to clone.
-        let contents = crate::plist::parse_contents(provider.clone())?;
-        let glif_parser = GlifParser::new(provider)?;
+        let contents = crate::plist::parse_ordered_contents(provider.clone())?;
+        let glif_parser = GlifParser::with_options(provider, options)?;
         Ok(Self {
             contents,
             glif_parser,
@@ -50,15 +55,18 @@ impl Gleaner {
     /// Parses all glyphs defined in `contents.plist` and returns a mapping from glyph
     /// names to their corresponding [`GlifData`].
     ///
+    /// Glyphs are ordered by the `public.glyphOrder` key of `lib.plist`, with unlisted glyphs
+    /// appended in sorted order.
+    ///
     /// # Returns
     ///
-    /// A [`HashMap<String, Option<GlifData>>`] where each key is a glyph name and each
+    /// An [`IndexMap<String, Option<GlifData>>`] where each key is a glyph name and each
     /// value is `Some(GlifData)` if the glyph was successfully parsed, or `None`
     /// if the `.glif` file could not be read or parsed.
-    pub fn glean(&self) -> Result<HashMap<String, Option<GlifData>>> {
+    pub fn glean(&self) -> Result<IndexMap<String, Option<GlifData>>> {
         // TODO: Implement logging of parsing errors.
         // TODO: Implement optional validation.
-        let mut glyphs_map = HashMap::with_capacity(self.contents.len());
+        let mut glyphs_map = IndexMap::with_capacity(self.contents.len());
         for (glyph_name, file_name) in &self.contents {
             let data = self.glif_parser.parse_glif(file_name).ok();
             glyphs_map.insert(glyph_name.clone(), data);
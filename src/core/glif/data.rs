@@ -1,16 +1,27 @@
 //! Structured representation of GLIF glyph data.
 
-use plist::Value;
-use serde::Serialize;
 use std::default::Default;
+use std::fmt;
+use std::io::Cursor;
+
+use indexmap::IndexMap;
+use plist::{Dictionary, Value};
+use serde::Serialize;
+
+use crate::color::Color;
+use crate::constants::ufo::{glif_default, lib_key};
+use crate::constants::xml::val;
+use crate::error::{Error, ErrorKind, Result};
+use crate::fontlib::{self, FontLib};
+use crate::transform::Transform;
 
 /// Represents all data contained in a single `.glif` glyph file.
-#[derive(Clone, Debug, Default, Serialize)]
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct GlifData {
     pub name: String,
-    pub format: String,
-    pub format_minor: Option<String>,
+    #[serde(flatten)]
+    pub format: GlifFormatVersion,
     pub advance: Option<Advance>,
     pub unicodes: Vec<u32>,
     pub note: Option<String>,
@@ -19,9 +30,235 @@ pub struct GlifData {
     pub anchors: Vec<Anchor>,
     pub outline: Option<Outline>,
     pub lib: Option<Value>,
+    /// The raw XML content of the `<lib>` element, pending [`GlifData::resolve_lib`].
+    #[serde(skip)]
+    pub lib_source: Option<Vec<u8>>,
+}
+
+impl GlifData {
+    /// Parses the raw `<lib>` content kept by [`crate::glif::LibMode::Lazy`] into
+    /// [`GlifData::lib`].
+    ///
+    /// Does nothing if there is no unparsed lib content.
+    pub fn resolve_lib(&mut self) -> Result<()> {
+        if let Some(source) = self.lib_source.take() {
+            if source.iter().all(u8::is_ascii_whitespace) {
+                return Ok(());
+            }
+            self.lib = Some(Value::from_reader_xml(Cursor::new(source))?);
+        }
+        Ok(())
+    }
+
+    /// Returns the glyph's mark color from the `public.markColor` lib key, if set to a valid
+    /// color string.
+    ///
+    /// Any other value is kept as it was read; see [`GlifData::invalid_mark_color`].
+    pub fn mark_color(&self) -> Option<Color> {
+        match self.lib_value(lib_key::PUBLIC_MARK_COLOR) {
+            Some(Value::String(s)) => s.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Returns the value of the `public.markColor` lib key if it is set but is not a valid color
+    /// string.
+    pub fn invalid_mark_color(&self) -> Option<&Value> {
+        self.lib_value(lib_key::PUBLIC_MARK_COLOR)
+            .filter(|_| self.mark_color().is_none())
+    }
+
+    /// Returns the y coordinate of the glyph's vertical origin from the `public.verticalOrigin`
+    /// lib key, if set.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is present but is not a number.
+    pub fn vertical_origin(&self) -> Result<Option<f64>> {
+        match self.lib_value(lib_key::PUBLIC_VERTICAL_ORIGIN) {
+            None => Ok(None),
+            Some(Value::Real(value)) => Ok(Some(*value)),
+            Some(Value::Integer(value)) => Ok(value.as_signed().map(|value| value as f64)),
+            Some(_) => Err(Error::new(ErrorKind::Parse)
+                .with_context(|| format!("{} is not a number", lib_key::PUBLIC_VERTICAL_ORIGIN))),
+        }
+    }
+
+    /// Returns the glyph's object libs from the `public.objectLibs` lib key, keyed by identifier.
+    ///
+    /// Lazily parsed libs must be resolved first, see [`GlifData::resolve_lib`].
+    pub fn object_libs(&self) -> Result<IndexMap<String, Dictionary>> {
+        fontlib::object_libs(self.lib_value(lib_key::PUBLIC_OBJECT_LIBS))
+    }
+
+    /// Returns the object lib for the object with the given identifier, if any.
+    ///
+    /// Lazily parsed libs must be resolved first, see [`GlifData::resolve_lib`].
+    pub fn object_lib(&self, identifier: &str) -> Result<Option<Dictionary>> {
+        fontlib::object_lib(self.lib_value(lib_key::PUBLIC_OBJECT_LIBS), identifier)
+    }
+
+    /// Returns the identifiers of all objects in the glyph: guidelines, anchors, components,
+    /// contours and points.
+    pub fn identifiers(&self) -> impl Iterator<Item = &str> {
+        let guidelines = self.guidelines.iter().map(|g| &g.identifier);
+        let anchors = self.anchors.iter().map(|a| &a.identifier);
+        let (components, contours): (&[Component], &[Contour]) = match &self.outline {
+            Some(o) => (&o.components, &o.contours),
+            None => (&[], &[]),
+        };
+        let points = contours.iter().flat_map(|c| &c.points);
+
+        guidelines
+            .chain(anchors)
+            .chain(components.iter().map(|c| &c.identifier))
+            .chain(contours.iter().map(|c| &c.identifier))
+            .chain(points.map(|p| &p.identifier))
+            .filter_map(|id| id.as_deref())
+    }
+
+    /// Returns the contours of the glyph's outline, or an empty slice if it has no outline.
+    pub fn contours(&self) -> &[Contour] {
+        self.outline
+            .as_ref()
+            .map_or(&[], |outline| outline.contours.as_slice())
+    }
+
+    /// Returns the components of the glyph's outline, or an empty slice if it has no outline.
+    pub fn components(&self) -> &[Component] {
+        self.outline
+            .as_ref()
+            .map_or(&[], |outline| outline.components.as_slice())
+    }
+
+    /// Returns `true` if `contour` is one of the anchors of a format 1 glyph; see
+    /// [`Contour::format_1_anchor`].
+    pub fn is_anchor_contour(&self, contour: &Contour) -> bool {
+        self.format.major == 1 && contour.format_1_anchor().is_some()
+    }
+
+    /// Returns the contours of the glyph's outline without the anchors of format 1 glyphs, i.e.
+    /// the contours that are drawn.
+    pub fn drawn_contours(&self) -> impl Iterator<Item = &Contour> {
+        self.contours()
+            .iter()
+            .filter(|contour| !self.is_anchor_contour(contour))
+    }
+
+    /// Returns the glyph's anchors followed by the anchors that format 1 glyphs store as
+    /// contours.
+    pub fn all_anchors(&self) -> Vec<Anchor> {
+        let format_1_anchors = self
+            .contours()
+            .iter()
+            .filter(|contour| self.is_anchor_contour(contour))
+            .filter_map(Contour::format_1_anchor);
+        self.anchors
+            .iter()
+            .cloned()
+            .chain(format_1_anchors)
+            .collect()
+    }
+
+    /// Checks that every key of `public.objectLibs` refers to an object in the glyph.
+    ///
+    /// Returns the keys that don't match any identifier; an empty list means the object libs
+    /// are valid.
+    pub fn validate_object_libs(&self) -> Result<Vec<String>> {
+        let identifiers: std::collections::HashSet<&str> = self.identifiers().collect();
+        Ok(self
+            .object_libs()?
+            .into_keys()
+            .filter(|key| !identifiers.contains(key.as_str()))
+            .collect())
+    }
+
+    /// Returns the value of a key in the glyph's lib.
+    fn lib_value(&self, key: &str) -> Option<&Value> {
+        self.lib
+            .as_ref()
+            .and_then(Value::as_dictionary)
+            .and_then(|lib| lib.get(key))
+    }
+
+    /// Fills every omitted attribute that has a default in the GLIF specification.
+    ///
+    /// A missing `<advance>` element is replaced by a zero advance.
+    pub fn apply_defaults(&mut self) {
+        self.advance
+            .get_or_insert_with(Advance::default)
+            .apply_defaults();
+        if let Some(image) = &mut self.image {
+            image.apply_defaults();
+        }
+        if let Some(outline) = &mut self.outline {
+            outline.apply_defaults();
+        }
+    }
 }
 
-#[derive(Clone, Debug, Serialize)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+/// Represents the GLIF format version declared by the `<glyph>` element.
+///
+/// Serializes as the `format` and `formatMinor` keys of the enclosing [`GlifData`].
+pub struct GlifFormatVersion {
+    #[serde(rename = "format")]
+    pub major: u8,
+    #[serde(rename = "formatMinor")]
+    pub minor: u8,
+}
+
+impl GlifFormatVersion {
+    /// GLIF format 1.0, as used by UFO 1 and UFO 2.
+    pub const V1: Self = Self::new(1, 0);
+    /// GLIF format 2.0, as used by UFO 3.
+    pub const V2: Self = Self::new(2, 0);
+    /// All format versions this crate knows how to read.
+    pub const SUPPORTED: &'static [Self] = &[Self::V1, Self::V2];
+
+    /// Creates a new format version.
+    pub const fn new(major: u8, minor: u8) -> Self {
+        Self { major, minor }
+    }
+
+    /// Returns `true` if the version is one of [`GlifFormatVersion::SUPPORTED`].
+    ///
+    /// Glyphs with other versions are reported by the
+    /// [`crate::lint::UnsupportedFormatVersion`] lint rule.
+    pub fn is_supported(&self) -> bool {
+        Self::SUPPORTED.contains(self)
+    }
+
+    /// Returns `true` if the major version is known to this crate.
+    ///
+    /// A file with a known major version but a newer minor version is still readable, as minor
+    /// revisions may only add optional data.
+    pub fn is_major_supported(&self) -> bool {
+        Self::SUPPORTED.iter().any(|v| v.major == self.major)
+    }
+
+    /// Validates the version against the supported format versions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::UnsupportedFormat`] error if the major version is unknown, i.e.
+    /// the file was written for a future (or invalid) GLIF format.
+    pub fn validate(&self) -> Result<()> {
+        if self.is_major_supported() {
+            Ok(())
+        } else {
+            Err(Error::new(ErrorKind::UnsupportedFormat(self.to_string())))
+        }
+    }
+}
+
+impl fmt::Display for GlifFormatVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Represents advance width/height information for a glyph.
 pub struct Advance {
@@ -29,7 +266,25 @@ pub struct Advance {
     pub height: Option<f64>,
 }
 
-#[derive(Clone, Debug, Default, Serialize)]
+impl Advance {
+    /// Returns the advance width, or the spec default of `0`.
+    pub fn width_or_default(&self) -> f64 {
+        self.width.unwrap_or(glif_default::ADVANCE_WIDTH)
+    }
+
+    /// Returns the advance height, or the spec default of `0`.
+    pub fn height_or_default(&self) -> f64 {
+        self.height.unwrap_or(glif_default::ADVANCE_HEIGHT)
+    }
+
+    /// Fills omitted attributes with their spec defaults.
+    pub fn apply_defaults(&mut self) {
+        self.width = Some(self.width_or_default());
+        self.height = Some(self.height_or_default());
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Represents optional image data embedded in a glyph.
 pub struct Image {
@@ -40,10 +295,60 @@ pub struct Image {
     pub y_scale: Option<f64>,
     pub x_offset: Option<f64>,
     pub y_offset: Option<f64>,
-    pub color: Option<String>,
+    pub color: Option<Color>,
+    /// The raw `color` attribute if it is not a valid color, in which case `color` is `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_color: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, Serialize)]
+impl Image {
+    /// Returns the x scale, or the spec default of `1`.
+    pub fn x_scale_or_default(&self) -> f64 {
+        self.x_scale.unwrap_or(glif_default::X_SCALE)
+    }
+
+    /// Returns the xy scale, or the spec default of `0`.
+    pub fn xy_scale_or_default(&self) -> f64 {
+        self.xy_scale.unwrap_or(glif_default::XY_SCALE)
+    }
+
+    /// Returns the yx scale, or the spec default of `0`.
+    pub fn yx_scale_or_default(&self) -> f64 {
+        self.yx_scale.unwrap_or(glif_default::YX_SCALE)
+    }
+
+    /// Returns the y scale, or the spec default of `1`.
+    pub fn y_scale_or_default(&self) -> f64 {
+        self.y_scale.unwrap_or(glif_default::Y_SCALE)
+    }
+
+    /// Returns the x offset, or the spec default of `0`.
+    pub fn x_offset_or_default(&self) -> f64 {
+        self.x_offset.unwrap_or(glif_default::X_OFFSET)
+    }
+
+    /// Returns the y offset, or the spec default of `0`.
+    pub fn y_offset_or_default(&self) -> f64 {
+        self.y_offset.unwrap_or(glif_default::Y_OFFSET)
+    }
+
+    /// Returns the image's transformation, applying spec defaults for omitted attributes.
+    pub fn transform(&self) -> Transform {
+        Transform::from(self)
+    }
+
+    /// Fills omitted transformation attributes with their spec defaults.
+    pub fn apply_defaults(&mut self) {
+        self.x_scale = Some(self.x_scale_or_default());
+        self.xy_scale = Some(self.xy_scale_or_default());
+        self.yx_scale = Some(self.yx_scale_or_default());
+        self.y_scale = Some(self.y_scale_or_default());
+        self.x_offset = Some(self.x_offset_or_default());
+        self.y_offset = Some(self.y_offset_or_default());
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Represents a guideline element within a glyph.
 pub struct Guideline {
@@ -51,22 +356,56 @@ pub struct Guideline {
     pub x: Option<f64>,
     pub angle: Option<f64>,
     pub name: Option<String>,
-    pub color: Option<String>,
+    pub color: Option<Color>,
+    /// The raw `color` attribute if it is not a valid color, in which case `color` is `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_color: Option<String>,
     pub identifier: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, Serialize)]
+impl Guideline {
+    /// Returns the guideline's object lib from the owning glyph's `public.objectLibs`.
+    pub fn lib(&self, glyph: &GlifData) -> Result<Option<Dictionary>> {
+        match &self.identifier {
+            Some(identifier) => glyph.object_lib(identifier),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns a font-level guideline's object lib from the font's `public.objectLibs`.
+    pub fn font_level_lib(&self, lib: &FontLib) -> Result<Option<Dictionary>> {
+        match &self.identifier {
+            Some(identifier) => lib.object_lib(identifier),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Represents an anchor point in the glyph.
 pub struct Anchor {
     pub x: Option<f64>,
     pub y: Option<f64>,
     pub name: Option<String>,
-    pub color: Option<String>,
+    pub color: Option<Color>,
+    /// The raw `color` attribute if it is not a valid color, in which case `color` is `None`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_color: Option<String>,
     pub identifier: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, Serialize)]
+impl Anchor {
+    /// Returns the anchor's object lib from the owning glyph's `public.objectLibs`.
+    pub fn lib(&self, glyph: &GlifData) -> Result<Option<Dictionary>> {
+        match &self.identifier {
+            Some(identifier) => glyph.object_lib(identifier),
+            None => Ok(None),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Represents the full outline of a glyph: components + contours.
 pub struct Outline {
@@ -74,7 +413,19 @@ pub struct Outline {
     pub contours: Vec<Contour>,
 }
 
-#[derive(Clone, Debug, Default, Serialize)]
+impl Outline {
+    /// Fills omitted component and point attributes with their spec defaults.
+    pub fn apply_defaults(&mut self) {
+        for component in &mut self.components {
+            component.apply_defaults();
+        }
+        for point in self.contours.iter_mut().flat_map(|c| c.points.iter_mut()) {
+            point.apply_defaults();
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Represents a single component reference within an outline.
 pub struct Component {
@@ -88,7 +439,54 @@ pub struct Component {
     pub identifier: Option<String>,
 }
 
-#[derive(Clone, Debug, Default, Serialize)]
+impl Component {
+    /// Returns the x scale, or the spec default of `1`.
+    pub fn x_scale_or_default(&self) -> f64 {
+        self.x_scale.unwrap_or(glif_default::X_SCALE)
+    }
+
+    /// Returns the xy scale, or the spec default of `0`.
+    pub fn xy_scale_or_default(&self) -> f64 {
+        self.xy_scale.unwrap_or(glif_default::XY_SCALE)
+    }
+
+    /// Returns the yx scale, or the spec default of `0`.
+    pub fn yx_scale_or_default(&self) -> f64 {
+        self.yx_scale.unwrap_or(glif_default::YX_SCALE)
+    }
+
+    /// Returns the y scale, or the spec default of `1`.
+    pub fn y_scale_or_default(&self) -> f64 {
+        self.y_scale.unwrap_or(glif_default::Y_SCALE)
+    }
+
+    /// Returns the x offset, or the spec default of `0`.
+    pub fn x_offset_or_default(&self) -> f64 {
+        self.x_offset.unwrap_or(glif_default::X_OFFSET)
+    }
+
+    /// Returns the y offset, or the spec default of `0`.
+    pub fn y_offset_or_default(&self) -> f64 {
+        self.y_offset.unwrap_or(glif_default::Y_OFFSET)
+    }
+
+    /// Returns the component's transformation, applying spec defaults for omitted attributes.
+    pub fn transform(&self) -> Transform {
+        Transform::from(self)
+    }
+
+    /// Fills omitted transformation attributes with their spec defaults.
+    pub fn apply_defaults(&mut self) {
+        self.x_scale = Some(self.x_scale_or_default());
+        self.xy_scale = Some(self.xy_scale_or_default());
+        self.yx_scale = Some(self.yx_scale_or_default());
+        self.y_scale = Some(self.y_scale_or_default());
+        self.x_offset = Some(self.x_offset_or_default());
+        self.y_offset = Some(self.y_offset_or_default());
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Represents a contour composed of individual points.
 pub struct Contour {
@@ -96,7 +494,51 @@ pub struct Contour {
     pub points: Vec<Point>,
 }
 
-#[derive(Debug, Default, Clone, Serialize)]
+impl Contour {
+    /// Returns the anchor the contour stands for in GLIF format 1, if it is one.
+    ///
+    /// Format 1 has no `<anchor>` element and stores anchors as contours with a single named
+    /// `move` point instead. Callers must check that the glyph is a format 1 glyph, as such
+    /// contours are ordinary open contours in later formats.
+    pub fn format_1_anchor(&self) -> Option<Anchor> {
+        match self.points.as_slice() {
+            [point] if point.point_type == Some(PointType::Move) && point.name.is_some() => {
+                Some(Anchor {
+                    x: point.x,
+                    y: point.y,
+                    name: point.name.clone(),
+                    ..Default::default()
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns a copy of the contour with the transformation applied to every point.
+    ///
+    /// Points without coordinates are transformed as if at the origin.
+    pub fn transformed(&self, transform: &Transform) -> Contour {
+        let points = self
+            .points
+            .iter()
+            .map(|point| {
+                let (x, y) =
+                    transform.apply(point.x.unwrap_or_default(), point.y.unwrap_or_default());
+                Point {
+                    x: Some(x),
+                    y: Some(y),
+                    ..point.clone()
+                }
+            })
+            .collect();
+        Contour {
+            identifier: self.identifier.clone(),
+            points,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Represents a single point in a glyph contour.
 pub struct Point {
@@ -108,13 +550,251 @@ pub struct Point {
     pub identifier: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize)]
+impl Point {
+    /// Returns the point type, treating an omitted `type` as [`PointType::OffCurve`].
+    pub fn point_type_or_default(&self) -> PointType {
+        self.point_type.clone().unwrap_or_default()
+    }
+
+    /// Returns the smooth flag, or the spec default of `false`.
+    pub fn smooth_or_default(&self) -> bool {
+        self.smooth.unwrap_or(glif_default::SMOOTH)
+    }
+
+    /// Fills omitted attributes with their spec defaults.
+    ///
+    /// An unrecognized `type` is kept as is.
+    pub fn apply_defaults(&mut self) {
+        self.point_type = Some(self.point_type_or_default());
+        self.smooth = Some(self.smooth_or_default());
+    }
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize)]
 #[serde(rename_all = "camelCase")]
 /// Enumeration of valid point types in a glyph contour.
+///
+/// A `type` attribute that is present but not defined by the GLIF specification is preserved as
+/// [`PointType::Unrecognized`], as opposed to an omitted `type`, which is represented by `None`
+/// on [`Point::point_type`].
 pub enum PointType {
     Move,
     Line,
+    #[default]
     OffCurve,
     Curve,
     QCurve,
+    #[serde(untagged)]
+    Unrecognized(String),
+}
+
+impl PointType {
+    /// Returns `true` unless this is [`PointType::Unrecognized`].
+    pub fn is_recognized(&self) -> bool {
+        !matches!(self, Self::Unrecognized(_))
+    }
+
+    /// Returns the value of the GLIF `type` attribute for this point type.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Move => val::MOVE,
+            Self::Line => val::LINE,
+            Self::OffCurve => val::OFFCURVE,
+            Self::Curve => val::CURVE,
+            Self::QCurve => val::QCURVE,
+            Self::Unrecognized(s) => s,
+        }
+    }
+}
+
+impl From<String> for PointType {
+    /// Converts a GLIF `type` attribute value, keeping unknown values as
+    /// [`PointType::Unrecognized`].
+    fn from(s: String) -> Self {
+        match s.as_str() {
+            val::MOVE => Self::Move,
+            val::LINE => Self::Line,
+            val::CURVE => Self::Curve,
+            val::QCURVE => Self::QCurve,
+            val::OFFCURVE => Self::OffCurve,
+            _ => Self::Unrecognized(s),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_version_supported() {
+        assert!(GlifFormatVersion::new(1, 0).is_supported());
+        assert!(GlifFormatVersion::new(2, 0).is_supported());
+        assert!(!GlifFormatVersion::new(2, 1).is_supported());
+        assert!(GlifFormatVersion::new(2, 1).is_major_supported());
+        assert!(!GlifFormatVersion::new(3, 0).is_major_supported());
+    }
+
+    #[test]
+    fn test_format_version_validate() {
+        assert!(GlifFormatVersion::V2.validate().is_ok());
+        assert!(GlifFormatVersion::new(2, 5).validate().is_ok());
+
+        let err = GlifFormatVersion::new(3, 0).validate().unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::UnsupportedFormat("3.0".into()));
+    }
+
+    #[test]
+    fn test_format_version_ordering_and_display() {
+        assert!(GlifFormatVersion::new(2, 1) > GlifFormatVersion::V2);
+        assert!(GlifFormatVersion::V1 < GlifFormatVersion::V2);
+        assert_eq!(GlifFormatVersion::new(2, 1).to_string(), "2.1");
+    }
+
+    #[test]
+    fn test_resolved_accessors() {
+        let component = Component {
+            x_scale: Some(2.0),
+            ..Default::default()
+        };
+        assert_eq!(component.x_scale_or_default(), 2.0);
+        assert_eq!(component.y_scale_or_default(), 1.0);
+        assert_eq!(component.xy_scale_or_default(), 0.0);
+        assert_eq!(component.x_offset_or_default(), 0.0);
+
+        let point = Point::default();
+        assert_eq!(point.point_type_or_default(), PointType::OffCurve);
+        assert!(!point.smooth_or_default());
+    }
+
+    #[test]
+    fn test_apply_defaults() {
+        let mut data = GlifData {
+            image: Some(Image::default()),
+            outline: Some(Outline {
+                components: vec![Component::default()],
+                contours: vec![Contour {
+                    identifier: None,
+                    points: vec![
+                        Point::default(),
+                        Point {
+                            point_type: Some(PointType::Unrecognized("spline".into())),
+                            ..Default::default()
+                        },
+                    ],
+                }],
+            }),
+            ..Default::default()
+        };
+        data.apply_defaults();
+
+        let advance = data.advance.unwrap();
+        assert_eq!((advance.width, advance.height), (Some(0.0), Some(0.0)));
+        assert_eq!(data.image.unwrap().x_scale, Some(1.0));
+
+        let outline = data.outline.unwrap();
+        assert_eq!(outline.components[0].y_scale, Some(1.0));
+        assert_eq!(outline.components[0].y_offset, Some(0.0));
+
+        let points = &outline.contours[0].points;
+        assert_eq!(points[0].point_type, Some(PointType::OffCurve));
+        assert_eq!(points[0].smooth, Some(false));
+        assert_eq!(
+            points[1].point_type,
+            Some(PointType::Unrecognized("spline".into()))
+        );
+    }
+
+    #[test]
+    fn test_unrecognized_point_type_serializes_as_string() {
+        let json = serde_json::to_value(PointType::Unrecognized("spline".into())).unwrap();
+        assert_eq!(json, "spline");
+        let json = serde_json::to_value(PointType::Curve).unwrap();
+        assert_eq!(json, "curve");
+    }
+
+    #[test]
+    fn test_mark_color() {
+        let mut data = GlifData::default();
+        assert!(data.mark_color().is_none());
+        assert!(data.invalid_mark_color().is_none());
+
+        let mut lib = plist::Dictionary::new();
+        lib.insert(lib_key::PUBLIC_MARK_COLOR.into(), "1,0,0,1".into());
+        data.lib = Some(Value::Dictionary(lib.clone()));
+        let color = data.mark_color().unwrap();
+        assert_eq!(color.to_tuple(), (1.0, 0.0, 0.0, 1.0));
+        assert!(data.invalid_mark_color().is_none());
+
+        lib.insert(lib_key::PUBLIC_MARK_COLOR.into(), "1,0,0".into());
+        data.lib = Some(Value::Dictionary(lib));
+        assert!(data.mark_color().is_none());
+        assert_eq!(data.invalid_mark_color(), Some(&Value::from("1,0,0")));
+    }
+
+    fn object_libs_glyph() -> GlifData {
+        let lib = r#"<dict><key>public.objectLibs</key><dict>
+            <key>anchor1</key><dict><key>com.example.class</key><string>top</string></dict>
+            <key>guide1</key><dict><key>com.example.locked</key><true/></dict>
+            <key>point1</key><dict/>
+            <key>orphan</key><dict/>
+        </dict></dict>"#;
+        GlifData {
+            anchors: vec![
+                Anchor {
+                    identifier: Some("anchor1".into()),
+                    ..Default::default()
+                },
+                Anchor::default(),
+            ],
+            guidelines: vec![Guideline {
+                identifier: Some("guide1".into()),
+                ..Default::default()
+            }],
+            outline: Some(Outline {
+                components: vec![],
+                contours: vec![Contour {
+                    identifier: Some("contour1".into()),
+                    points: vec![Point {
+                        identifier: Some("point1".into()),
+                        ..Default::default()
+                    }],
+                }],
+            }),
+            lib: Some(Value::from_reader_xml(lib.as_bytes()).unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_object_libs() {
+        let data = object_libs_glyph();
+        assert_eq!(data.object_libs().unwrap().len(), 4);
+
+        let lib = data.anchors[0].lib(&data).unwrap().unwrap();
+        assert_eq!(lib["com.example.class"].as_string(), Some("top"));
+        assert!(data.anchors[1].lib(&data).unwrap().is_none());
+
+        let lib = data.guidelines[0].lib(&data).unwrap().unwrap();
+        assert_eq!(lib["com.example.locked"].as_boolean(), Some(true));
+    }
+
+    #[test]
+    fn test_identifiers_and_validate_object_libs() {
+        let data = object_libs_glyph();
+        let identifiers: Vec<_> = data.identifiers().collect();
+        assert_eq!(identifiers, ["guide1", "anchor1", "contour1", "point1"]);
+        assert_eq!(data.validate_object_libs().unwrap(), ["orphan"]);
+    }
+
+    #[test]
+    fn test_format_version_serializes_flat() {
+        let data = GlifData {
+            format: GlifFormatVersion::new(2, 1),
+            ..Default::default()
+        };
+        let json = serde_json::to_value(&data).unwrap();
+        assert_eq!(json["format"], 2);
+        assert_eq!(json["formatMinor"], 1);
+    }
 }
@@ -8,22 +8,14 @@ use crate::glif::{context::GlifParseContext, data::Contour, helpers};
 
 // ----- Glyph -----
 
-/// Handles a `<glyph>` start element and stores the glyph name.
+/// Handles a `<glyph>` start element and stores the glyph name and format version.
+///
+/// Fails if the glyph declares a major format version this crate cannot read.
 pub fn handle_glyph_start(ctx: &mut GlifParseContext, e: BytesStart) -> Result<()> {
-    for attr in e.attributes().with_checks(false).flatten() {
-        match attr.key.as_ref() {
-            attr::NAME => {
-                ctx.glyph_name = attr.unescape_value()?.into_owned();
-            }
-            attr::FORMAT => {
-                ctx.format = attr.unescape_value()?.into_owned();
-            }
-            attr::FORMAT_MINOR => {
-                ctx.format = attr.unescape_value()?.into_owned();
-            }
-            _ => {}
-        }
-    }
+    ctx.glyph_name = helpers::attr_str(&e, attr::NAME)?.unwrap_or_default();
+    ctx.format = helpers::parse_format_version(&e)?;
+    ctx.format.validate()?;
+
     Ok(())
 }
 
@@ -50,23 +42,25 @@ pub fn handle_unicode(ctx: &mut GlifParseContext, e: BytesStart) -> Result<()> {
 /// Marks that the parser has entered a `<note>` element.
 pub fn handle_note_start(ctx: &mut GlifParseContext) -> Result<()> {
     ctx.inside_note = true;
-    ctx.note.clear();
+    ctx.note = Some(String::new());
 
     Ok(())
 }
 
 // Parses the text inside a `<note>` element.
 pub fn handle_note_contents(ctx: &mut GlifParseContext, e: BytesText) -> Result<()> {
-    if ctx.inside_note {
+    if ctx.inside_note
+        && let Some(note) = &mut ctx.note
+    {
         let text = std::str::from_utf8(e.as_ref())?;
-        ctx.note.push_str(text);
+        note.push_str(text);
     }
     Ok(())
 }
 
 /// Marks that the parser has exited an `<note>` element.
 pub fn handle_note_end(ctx: &mut GlifParseContext) -> Result<()> {
-    helpers::normalize_note(&ctx.note);
+    helpers::normalize_note(ctx.note.as_deref().unwrap_or_default());
     ctx.inside_note = false;
 
     Ok(())
@@ -103,6 +97,7 @@ pub fn handle_anchor(ctx: &mut GlifParseContext, e: BytesStart) -> Result<()> {
 /// Marks that the parser has entered an `<outline>` element.
 pub fn handle_outline_start(ctx: &mut GlifParseContext) -> Result<()> {
     ctx.inside_outline = true;
+    ctx.has_outline = true;
 
     Ok(())
 }
@@ -155,23 +150,9 @@ pub fn handle_component(ctx: &mut GlifParseContext, e: BytesStart) -> Result<()>
 
 // ----- Lib -----
 
-/// Marks that the parser has entered a `<lib>` element.
-pub fn handle_lib_start(ctx: &mut GlifParseContext) -> Result<()> {
-    ctx.inside_lib = true;
-
-    Ok(())
-}
-
-// Extracts the elements inside a `<note>` element.
-pub fn handle_lib_contents(ctx: &mut GlifParseContext, e: &[u8]) -> Result<()> {
-    ctx.lib_buffer.extend_from_slice(e);
-
-    Ok(())
-}
-
-/// Marks that the parser has exited an `<lib>` element.
-pub fn handle_lib_end(ctx: &mut GlifParseContext) -> Result<()> {
-    ctx.inside_lib = false;
+/// Stores the raw content of a `<lib>` element for later parsing.
+pub fn handle_lib(ctx: &mut GlifParseContext, content: &[u8]) -> Result<()> {
+    ctx.lib_source = Some(content.to_vec());
 
     Ok(())
 }
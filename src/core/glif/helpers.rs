@@ -1,13 +1,11 @@
 //! Attribute & parsing helpers.
 
-use std::io::Cursor;
-
-use quick_xml::Writer;
-use quick_xml::events::{BytesStart, Event};
+use quick_xml::events::BytesStart;
 
+use crate::color::Color;
 use crate::constants::xml::{attr, val};
 use crate::error::{Error, ErrorKind, Result};
-use crate::glif::data::{Anchor, Component, Guideline, Image, Point, PointType};
+use crate::glif::data::{Anchor, Component, GlifFormatVersion, Guideline, Image, Point, PointType};
 use crate::glif::helpers;
 
 /// Retrieves the value of a specific attribute as a [`String`].
@@ -32,6 +30,39 @@ pub fn attr_f64(e: &BytesStart, key: &[u8]) -> Result<Option<f64>> {
     Ok(None)
 }
 
+/// Retrieves the value of a color attribute and parses it as a [`Color`].
+///
+/// A value that is not a valid color is returned as the second element instead, so that a
+/// malformed color from another editor doesn't make the whole glyph unreadable.
+pub fn attr_color(e: &BytesStart, key: &[u8]) -> Result<(Option<Color>, Option<String>)> {
+    Ok(match helpers::attr_str(e, key)? {
+        Some(s) => match s.parse() {
+            Ok(color) => (Some(color), None),
+            Err(_) => (None, Some(s)),
+        },
+        None => (None, None),
+    })
+}
+
+/// Parses the `format` and `formatMinor` attributes of a `<glyph>` element into a
+/// [`GlifFormatVersion`].
+///
+/// `formatMinor` defaults to `0` when omitted.
+pub fn parse_format_version(e: &BytesStart) -> Result<GlifFormatVersion> {
+    let attr = attr::FORMAT;
+    let major = helpers::attr_str(e, attr)?.ok_or_else(|| {
+        Error::new(ErrorKind::MissingAttribute(
+            std::str::from_utf8(attr).unwrap().to_string(),
+        ))
+    })?;
+    let minor = helpers::attr_str(e, attr::FORMAT_MINOR)?;
+
+    Ok(GlifFormatVersion {
+        major: major.trim().parse()?,
+        minor: minor.map(|m| m.trim().parse()).transpose()?.unwrap_or(0),
+    })
+}
+
 /// Parses a `<unicode>` element into a [`Unicode`] value.
 pub fn parse_unicode(e: &BytesStart) -> Result<u32> {
     let attr = attr::HEX;
@@ -46,6 +77,7 @@ pub fn parse_unicode(e: &BytesStart) -> Result<u32> {
 
 /// Parses an `<image>` element into a [`Image`] object.
 pub fn parse_image(e: &BytesStart) -> Result<Image> {
+    let (color, invalid_color) = helpers::attr_color(e, attr::COLOR)?;
     let image = Image {
         file_name: helpers::attr_str(e, attr::FILE_NAME)?,
         x_scale: helpers::attr_f64(e, attr::X_SCALE)?,
@@ -54,19 +86,22 @@ pub fn parse_image(e: &BytesStart) -> Result<Image> {
         y_scale: helpers::attr_f64(e, attr::Y_SCALE)?,
         x_offset: helpers::attr_f64(e, attr::X_OFFSET)?,
         y_offset: helpers::attr_f64(e, attr::Y_OFFSET)?,
-        color: helpers::attr_str(e, attr::COLOR)?,
+        color,
+        invalid_color,
     };
     Ok(image)
 }
 
 /// Parses a `<guideline>` element into a [`Guideline`] object.
 pub fn parse_guideline(e: &BytesStart) -> Result<Guideline> {
+    let (color, invalid_color) = helpers::attr_color(e, attr::COLOR)?;
     let guideline = Guideline {
         x: helpers::attr_f64(e, attr::X)?,
         y: helpers::attr_f64(e, attr::Y)?,
         angle: helpers::attr_f64(e, attr::ANGLE)?,
         name: helpers::attr_str(e, attr::NAME)?,
-        color: helpers::attr_str(e, attr::COLOR)?,
+        color,
+        invalid_color,
         identifier: helpers::attr_str(e, attr::IDENTIFIER)?,
     };
 
@@ -75,11 +110,13 @@ pub fn parse_guideline(e: &BytesStart) -> Result<Guideline> {
 
 /// Parses an `<anchor>` element into a [`Anchor`] object.
 pub fn parse_anchor(e: &BytesStart) -> Result<Anchor> {
+    let (color, invalid_color) = helpers::attr_color(e, attr::COLOR)?;
     let anchor = Anchor {
         x: helpers::attr_f64(e, attr::X)?,
         y: helpers::attr_f64(e, attr::Y)?,
         name: helpers::attr_str(e, attr::NAME)?,
-        color: helpers::attr_str(e, attr::COLOR)?,
+        color,
+        invalid_color,
         identifier: helpers::attr_str(e, attr::IDENTIFIER)?,
     };
 
@@ -88,33 +125,22 @@ pub fn parse_anchor(e: &BytesStart) -> Result<Anchor> {
 
 /// Parses a `<point>` element into a [`Point`] object.
 pub fn parse_point(e: &BytesStart) -> Result<Point> {
-    let mut point = Point::default();
-
-    point.x = helpers::attr_f64(e, attr::X)?;
-    point.y = helpers::attr_f64(e, attr::Y)?;
+    let point_type = helpers::attr_str(e, attr::TYPE)?.map(PointType::from);
 
-    let point_type = helpers::attr_str(e, attr::TYPE)?.and_then(|t| match t.as_str() {
-        val::MOVE => Some(PointType::Move),
-        val::LINE => Some(PointType::Line),
-        val::CURVE => Some(PointType::Curve),
-        val::QCURVE => Some(PointType::QCurve),
-        val::OFFCURVE => Some(PointType::OffCurve),
-        _ => None,
-    });
-    point.point_type = point_type;
-
-    let smooth = helpers::attr_str(&e, attr::SMOOTH)?.and_then(|v| match v.as_str() {
+    let smooth = helpers::attr_str(e, attr::SMOOTH)?.and_then(|v| match v.as_str() {
         val::YES => Some(true),
         val::NO => Some(false),
         _ => None,
     });
-    point.smooth = smooth;
 
-    let name = helpers::attr_str(&e, attr::NAME)?;
-    point.name = name;
-
-    let identifier = helpers::attr_str(&e, attr::IDENTIFIER)?;
-    point.identifier = identifier;
+    let point = Point {
+        x: helpers::attr_f64(e, attr::X)?,
+        y: helpers::attr_f64(e, attr::Y)?,
+        point_type,
+        smooth,
+        name: helpers::attr_str(e, attr::NAME)?,
+        identifier: helpers::attr_str(e, attr::IDENTIFIER)?,
+    };
 
     Ok(point)
 }
@@ -146,11 +172,3 @@ pub fn normalize_note(note: &str) -> String {
         .collect::<Vec<_>>()
         .join("\n")
 }
-
-/// Turns an [`Event`] into XML bytes.
-pub fn serialize_event(ev: &Event) -> Vec<u8> {
-    let buffer = Cursor::new(Vec::new());
-    let mut writer = Writer::new(buffer);
-    writer.write_event(ev.to_owned()).unwrap_or_default();
-    writer.into_inner().into_inner()
-}
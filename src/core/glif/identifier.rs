@@ -0,0 +1,198 @@
+//! Validation and generation of GLIF object identifiers.
+//!
+//! Identifiers must be unique within a glyph, at most 100 characters long and consist only of
+//! characters in the range `0x20..=0x7E`.
+
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::glif::data::GlifData;
+
+/// The maximum length of an identifier.
+pub const MAX_IDENTIFIER_LENGTH: usize = 100;
+
+/// The length of identifiers created by [`generate_identifier`].
+const GENERATED_IDENTIFIER_LENGTH: usize = 10;
+
+/// A problem with an identifier found by [`GlifData::validate_identifiers`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "identifier", rename_all = "camelCase")]
+pub enum IdentifierIssue {
+    /// The identifier is used by more than one object in the glyph.
+    Duplicate(String),
+    /// The identifier is empty, too long or contains characters outside `0x20..=0x7E`.
+    Invalid(String),
+}
+
+/// Returns `true` if `identifier` satisfies the GLIF identifier rules.
+pub fn is_valid_identifier(identifier: &str) -> bool {
+    !identifier.is_empty()
+        && identifier.len() <= MAX_IDENTIFIER_LENGTH
+        && identifier.bytes().all(|b| (0x20..=0x7E).contains(&b))
+}
+
+/// Generates a random alphanumeric identifier that is not contained in `existing`.
+pub fn generate_identifier(existing: &HashSet<String>) -> String {
+    loop {
+        let identifier: String = std::iter::repeat_with(fastrand::alphanumeric)
+            .take(GENERATED_IDENTIFIER_LENGTH)
+            .collect();
+        if !existing.contains(&identifier) {
+            return identifier;
+        }
+    }
+}
+
+impl GlifData {
+    /// Checks that all identifiers in the glyph are valid and unique.
+    ///
+    /// Returns one issue per invalid identifier and per duplicated identifier, in document
+    /// order; an empty list means the identifiers are valid.
+    pub fn validate_identifiers(&self) -> Vec<IdentifierIssue> {
+        let mut counts: HashMap<&str, usize> = HashMap::new();
+        for identifier in self.identifiers() {
+            *counts.entry(identifier).or_default() += 1;
+        }
+
+        let mut reported = HashSet::new();
+        let mut issues = Vec::new();
+        for identifier in self.identifiers() {
+            if !reported.insert(identifier) {
+                continue;
+            }
+            if !is_valid_identifier(identifier) {
+                issues.push(IdentifierIssue::Invalid(identifier.to_owned()));
+            }
+            if counts[identifier] > 1 {
+                issues.push(IdentifierIssue::Duplicate(identifier.to_owned()));
+            }
+        }
+        issues
+    }
+
+    /// Assigns a random, unique identifier to every guideline, anchor, component, contour and
+    /// point that lacks one.
+    ///
+    /// Identifiers are only defined for GLIF format 2 and later, so format 1 glyphs are left
+    /// unchanged. Returns the number of identifiers assigned.
+    pub fn assign_missing_identifiers(&mut self) -> usize {
+        if self.format.major == 1 {
+            return 0;
+        }
+        let mut existing: HashSet<String> = self.identifiers().map(str::to_owned).collect();
+        let mut assigned = 0;
+        let mut assign = |identifier: &mut Option<String>| {
+            if identifier.is_none() {
+                let new = generate_identifier(&existing);
+                existing.insert(new.clone());
+                *identifier = Some(new);
+                assigned += 1;
+            }
+        };
+
+        for guideline in &mut self.guidelines {
+            assign(&mut guideline.identifier);
+        }
+        for anchor in &mut self.anchors {
+            assign(&mut anchor.identifier);
+        }
+        if let Some(outline) = &mut self.outline {
+            for component in &mut outline.components {
+                assign(&mut component.identifier);
+            }
+            for contour in &mut outline.contours {
+                assign(&mut contour.identifier);
+                for point in &mut contour.points {
+                    assign(&mut point.identifier);
+                }
+            }
+        }
+        assigned
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::glif::data::{Anchor, Contour, GlifFormatVersion, Outline, Point};
+
+    fn glyph(anchor_ids: &[Option<&str>], point_ids: &[Option<&str>]) -> GlifData {
+        let id = |id: &Option<&str>| id.map(str::to_owned);
+        GlifData {
+            anchors: anchor_ids
+                .iter()
+                .map(|i| Anchor {
+                    identifier: id(i),
+                    ..Default::default()
+                })
+                .collect(),
+            outline: Some(Outline {
+                components: vec![],
+                contours: vec![Contour {
+                    identifier: None,
+                    points: point_ids
+                        .iter()
+                        .map(|i| Point {
+                            identifier: id(i),
+                            ..Default::default()
+                        })
+                        .collect(),
+                }],
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_is_valid_identifier() {
+        assert!(is_valid_identifier("abc DEF-123_~"));
+        assert!(!is_valid_identifier(""));
+        assert!(!is_valid_identifier("tab\tchar"));
+        assert!(!is_valid_identifier("caf\u{e9}"));
+        assert!(is_valid_identifier(&"a".repeat(100)));
+        assert!(!is_valid_identifier(&"a".repeat(101)));
+    }
+
+    #[test]
+    fn test_validate_identifiers() {
+        let data = glyph(
+            &[Some("a"), Some("dup")],
+            &[Some("dup"), None, Some("bad\n")],
+        );
+        assert_eq!(
+            data.validate_identifiers(),
+            [
+                IdentifierIssue::Duplicate("dup".into()),
+                IdentifierIssue::Invalid("bad\n".into()),
+            ]
+        );
+
+        let data = glyph(&[Some("a")], &[Some("b"), None]);
+        assert!(data.validate_identifiers().is_empty());
+    }
+
+    #[test]
+    fn test_assign_missing_identifiers() {
+        let mut data = glyph(&[None, Some("a")], &[None, Some("b"), None]);
+        assert_eq!(data.assign_missing_identifiers(), 4);
+        assert_eq!(data.anchors[1].identifier.as_deref(), Some("a"));
+        assert_eq!(data.identifiers().count(), 6);
+        assert!(data.validate_identifiers().is_empty());
+
+        assert_eq!(data.assign_missing_identifiers(), 0);
+
+        let mut data = glyph(&[None], &[None]);
+        data.format = GlifFormatVersion::V1;
+        assert_eq!(data.assign_missing_identifiers(), 0);
+        assert_eq!(data.identifiers().count(), 0);
+    }
+
+    #[test]
+    fn test_issue_serialization() {
+        let json = serde_json::to_value(IdentifierIssue::Duplicate("x".into())).unwrap();
+        assert_eq!(json["kind"], "duplicate");
+        assert_eq!(json["identifier"], "x");
+    }
+}
@@ -2,10 +2,16 @@ pub(crate) mod context;
 pub mod data;
 pub(crate) mod handlers;
 pub(crate) mod helpers;
+pub mod identifier;
+pub mod options;
 pub(crate) mod parser;
 pub(crate) mod reader;
+pub mod writer;
 
 pub use context::*;
 pub use data::*;
+pub use identifier::*;
+pub use options::*;
 pub use parser::*;
 pub use reader::*;
+pub use writer::*;
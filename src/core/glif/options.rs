@@ -0,0 +1,72 @@
+//! Options controlling how GLIF files are parsed.
+
+use std::str::FromStr;
+
+use crate::error::{Error, ErrorKind};
+
+/// Configures the behavior of [`crate::glif::GlifParser`].
+///
+/// # Example
+///
+/// ```
+/// use ufo_gleaner::glif::ParseOptions;
+///
+/// let options = ParseOptions::default().with_apply_defaults(true);
+/// assert!(options.apply_defaults);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParseOptions {
+    /// Fill omitted attributes with their GLIF specification defaults.
+    ///
+    /// See [`crate::glif::GlifData::apply_defaults`].
+    pub apply_defaults: bool,
+    /// How the `<lib>` element is handled.
+    pub lib: LibMode,
+}
+
+/// Controls when the property list inside a GLIF `<lib>` element is parsed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LibMode {
+    /// Parse the lib together with the rest of the glyph.
+    #[default]
+    Eager,
+    /// Keep the raw lib XML and parse it on first access.
+    ///
+    /// See [`crate::glif::GlifData::resolve_lib`].
+    Lazy,
+    /// Ignore the lib entirely.
+    Skip,
+}
+
+impl ParseOptions {
+    /// Sets whether omitted attributes are filled with their spec defaults.
+    pub fn with_apply_defaults(mut self, apply_defaults: bool) -> Self {
+        self.apply_defaults = apply_defaults;
+        self
+    }
+
+    /// Sets how the `<lib>` element is handled.
+    pub fn with_lib_mode(mut self, lib: LibMode) -> Self {
+        self.lib = lib;
+        self
+    }
+}
+
+impl FromStr for LibMode {
+    type Err = Error;
+
+    /// Parses `"eager"`, `"lazy"` or `"skip"` into a [`LibMode`].
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "eager" => Ok(Self::Eager),
+            "lazy" => Ok(Self::Lazy),
+            "skip" => Ok(Self::Skip),
+            _ => {
+                let s = s.to_owned();
+                Err(Error::new(ErrorKind::Parse).with_context(move || {
+                    format!("unknown lib mode '{}', expected eager, lazy or skip", s)
+                }))
+            }
+        }
+    }
+}
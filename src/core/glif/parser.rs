@@ -1,13 +1,10 @@
 //! Streaming XML parser to convert UFO GLIF files into [`GlifData`].
 
-use std::io::{BufReader, Cursor};
-use std::path::PathBuf;
-
 use quick_xml::events::Event;
 
 use crate::constants::xml::elem;
 use crate::error::Result;
-use crate::glif::{GlifData, GlifEventReader, GlifParseContext, handlers, helpers};
+use crate::glif::{GlifData, GlifEventReader, GlifParseContext, LibMode, ParseOptions, handlers};
 
 use crate::paths::UfoRelativePath;
 use crate::provider::{Provider, ProviderHandle};
@@ -15,106 +12,272 @@ use crate::provider::{Provider, ProviderHandle};
 /// A parser for UFO GLIF files that produces [`GlifData`] from `.glif` files.
 pub struct GlifParser {
     provider: ProviderHandle,
+    options: ParseOptions,
 }
 
 impl GlifParser {
     /// Creates a new parser from a [`Provider`] instance.
     pub fn new(provider: ProviderHandle) -> Result<Self> {
-        Ok(Self { provider })
+        Self::with_options(provider, ParseOptions::default())
+    }
+
+    /// Creates a new parser from a [`Provider`] instance with the given [`ParseOptions`].
+    pub fn with_options(provider: ProviderHandle, options: ParseOptions) -> Result<Self> {
+        Ok(Self { provider, options })
+    }
+
+    /// Returns the options used by this parser.
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
     }
 
     /// Parses a single GLIF file and returns the corresponding [`GlifData`].
     pub fn parse_glif(&self, file_name: &str) -> Result<GlifData> {
         let path = UfoRelativePath::GlifFile(file_name.to_string()).to_pathbuf();
-        let file = self.open_xml(&path)?;
+        let data = self.provider.read(&path)?;
+        self.parse_glif_bytes(&data)
+    }
+
+    /// Parses the contents of a GLIF file that was already read from the provider.
+    pub fn parse_glif_bytes(&self, data: &[u8]) -> Result<GlifData> {
+        let mut events = GlifEventReader::new(data);
         let mut ctx = GlifParseContext::default();
 
-        for ev in GlifEventReader::new(file) {
+        while let Some(ev) = events.next() {
             let ev = ev?;
-            if ctx.inside_lib {
-                match ev {
-                    Event::End(e) if e.name().as_ref() == elem::LIB => {
-                        handlers::handle_lib_end(&mut ctx)?
-                    }
-                    _ => {
-                        let bytes = helpers::serialize_event(&ev);
-                        handlers::handle_lib_contents(&mut ctx, &bytes)?
-                    }
+            match ev {
+                // ----- Glyph -----
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == elem::GLYPH => {
+                    handlers::handle_glyph_start(&mut ctx, e)?
                 }
-            } else {
-                match ev {
-                    // ----- Glyph -----
-                    Event::Start(e) if e.name().as_ref() == elem::GLYPH => {
-                        handlers::handle_glyph_start(&mut ctx, e)?
-                    }
 
-                    // ----- Advance & Unicode -----
-                    Event::Empty(e) if e.name().as_ref() == elem::ADVANCE => {
-                        handlers::handle_advance(&mut ctx, e)?
-                    }
-                    Event::Empty(e) if e.name().as_ref() == elem::UNICODE => {
-                        handlers::handle_unicode(&mut ctx, e)?
-                    }
+                // ----- Advance & Unicode -----
+                Event::Empty(e) if e.name().as_ref() == elem::ADVANCE => {
+                    handlers::handle_advance(&mut ctx, e)?
+                }
+                Event::Empty(e) if e.name().as_ref() == elem::UNICODE => {
+                    handlers::handle_unicode(&mut ctx, e)?
+                }
 
-                    // ----- Note & Text -----
-                    Event::Start(e) if e.name().as_ref() == elem::NOTE => {
-                        handlers::handle_note_start(&mut ctx)?
-                    }
-                    Event::Text(e) => handlers::handle_note_contents(&mut ctx, e)?,
-                    Event::End(e) if e.name().as_ref() == elem::NOTE => {
-                        handlers::handle_note_end(&mut ctx)?
-                    }
+                // ----- Note & Text -----
+                Event::Start(e) if e.name().as_ref() == elem::NOTE => {
+                    handlers::handle_note_start(&mut ctx)?
+                }
+                Event::Text(e) => handlers::handle_note_contents(&mut ctx, e)?,
+                Event::End(e) if e.name().as_ref() == elem::NOTE => {
+                    handlers::handle_note_end(&mut ctx)?
+                }
 
-                    // ----- Image, Guideline & Anchor -----
-                    Event::Empty(e) if e.name().as_ref() == elem::IMAGE => {
-                        handlers::handle_image(&mut ctx, e)?
-                    }
-                    Event::Empty(e) if e.name().as_ref() == elem::GUIDELINE => {
-                        handlers::handle_guideline(&mut ctx, e)?
-                    }
-                    Event::Empty(e) if e.name().as_ref() == elem::ANCHOR => {
-                        handlers::handle_anchor(&mut ctx, e)?
-                    }
+                // ----- Image, Guideline & Anchor -----
+                Event::Empty(e) if e.name().as_ref() == elem::IMAGE => {
+                    handlers::handle_image(&mut ctx, e)?
+                }
+                Event::Empty(e) if e.name().as_ref() == elem::GUIDELINE => {
+                    handlers::handle_guideline(&mut ctx, e)?
+                }
+                Event::Empty(e) if e.name().as_ref() == elem::ANCHOR => {
+                    handlers::handle_anchor(&mut ctx, e)?
+                }
 
-                    // ----- Outline, Contours & Componenets -----
-                    Event::Start(e) if e.name().as_ref() == elem::OUTLINE => {
-                        handlers::handle_outline_start(&mut ctx)?
-                    }
-                    Event::End(e) if e.name().as_ref() == elem::OUTLINE => {
-                        handlers::handle_outline_end(&mut ctx)?
-                    }
-                    Event::Empty(e) if e.name().as_ref() == elem::POINT => {
-                        handlers::handle_point(&mut ctx, e)?
-                    }
-                    Event::Start(e) if e.name().as_ref() == elem::CONTOUR => {
-                        handlers::handle_contour_start(&mut ctx, e)?
-                    }
-                    Event::End(e) if e.name().as_ref() == elem::CONTOUR => {
-                        handlers::handle_contour_end(&mut ctx)?
-                    }
-                    Event::Empty(e) if e.name().as_ref() == elem::COMPONENT => {
-                        handlers::handle_component(&mut ctx, e)?
-                    }
+                // ----- Outline, Contours & Componenets -----
+                Event::Start(e) if e.name().as_ref() == elem::OUTLINE => {
+                    handlers::handle_outline_start(&mut ctx)?
+                }
+                Event::End(e) if e.name().as_ref() == elem::OUTLINE => {
+                    handlers::handle_outline_end(&mut ctx)?
+                }
+                Event::Empty(e) if e.name().as_ref() == elem::POINT => {
+                    handlers::handle_point(&mut ctx, e)?
+                }
+                Event::Start(e) if e.name().as_ref() == elem::CONTOUR => {
+                    handlers::handle_contour_start(&mut ctx, e)?
+                }
+                Event::End(e) if e.name().as_ref() == elem::CONTOUR => {
+                    handlers::handle_contour_end(&mut ctx)?
+                }
+                Event::Empty(e) if e.name().as_ref() == elem::COMPONENT => {
+                    handlers::handle_component(&mut ctx, e)?
+                }
 
-                    // ----- Lib -----
-                    Event::Start(e) if e.name().as_ref() == elem::LIB => {
-                        handlers::handle_lib_start(&mut ctx)?
+                // ----- Lib -----
+                Event::Start(e) if e.name().as_ref() == elem::LIB => {
+                    let span = events.skip_to_end(elem::LIB)?;
+                    if self.options.lib != LibMode::Skip {
+                        handlers::handle_lib(&mut ctx, &data[span])?
                     }
-
-                    // ----- Unknown -----
-                    _ => {}
                 }
+
+                // ----- Unknown -----
+                _ => {}
             }
         }
-        Ok(ctx.into_glif_data()?)
+        let mut glif = ctx.into_glif_data()?;
+        if self.options.lib == LibMode::Eager {
+            glif.resolve_lib()?;
+        }
+        if self.options.apply_defaults {
+            glif.apply_defaults();
+        }
+        Ok(glif)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::ErrorKind;
+    use crate::glif::{GlifFormatVersion, PointType};
+    use crate::test_utils::MockProvider;
+
+    fn parse(glif: &str) -> Result<GlifData> {
+        parse_with_options(glif, ParseOptions::default())
     }
 
-    /// Opens a GLIF file as a buffered reader from the UFO file system.
-    fn open_xml(&self, path: &PathBuf) -> Result<BufReader<Cursor<Vec<u8>>>> {
-        let data = self.provider.read(path)?;
-        let cursor = Cursor::new(data);
-        let reader = BufReader::new(cursor);
+    fn parse_with_options(glif: &str, options: ParseOptions) -> Result<GlifData> {
+        let path = UfoRelativePath::GlifFile("a.glif".to_string()).to_pathbuf();
+        let provider = MockProvider::new();
+        provider.with_file(&path, glif.as_bytes());
+        GlifParser::with_options(provider, options)?.parse_glif("a.glif")
+    }
+
+    #[test]
+    fn test_parse_format_and_minor() {
+        let data = parse(r#"<glyph name="a" format="2" formatMinor="1"></glyph>"#).unwrap();
+        assert_eq!(data.name, "a");
+        assert_eq!(data.format, GlifFormatVersion::new(2, 1));
+
+        let data = parse(r#"<glyph name="a" format="1"></glyph>"#).unwrap();
+        assert_eq!(data.format, GlifFormatVersion::V1);
+    }
+
+    #[test]
+    fn test_parse_self_closing_glyph() {
+        let data = parse(r#"<glyph name="b" format="2" formatMinor="1"/>"#).unwrap();
+        assert_eq!(data.name, "b");
+        assert_eq!(data.format, GlifFormatVersion::new(2, 1));
+    }
+
+    #[test]
+    fn test_parse_unsupported_format() {
+        let err = parse(r#"<glyph name="a" format="3"></glyph>"#).unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::UnsupportedFormat("3.0".into()));
+    }
+
+    #[test]
+    fn test_parse_missing_format() {
+        let err = parse(r#"<glyph name="a"></glyph>"#).unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::MissingAttribute("format".into()));
+    }
+
+    #[test]
+    fn test_parse_point_types() {
+        let glif = r#"<glyph name="a" format="2"><outline><contour>
+            <point x="0" y="0"/>
+            <point x="1" y="0" type="line" smooth="yes"/>
+            <point x="2" y="0" type="spline"/>
+        </contour></outline></glyph>"#;
+
+        let data = parse(glif).unwrap();
+        let points = &data.outline.unwrap().contours[0].points;
+        assert_eq!(points[0].point_type, None);
+        assert_eq!(points[0].smooth, None);
+        assert_eq!(points[1].point_type, Some(PointType::Line));
+        assert_eq!(points[1].smooth, Some(true));
+        assert_eq!(
+            points[2].point_type,
+            Some(PointType::Unrecognized("spline".into()))
+        );
+    }
+
+    #[test]
+    fn test_parse_apply_defaults() {
+        let glif = r#"<glyph name="a" format="2">
+            <outline>
+                <contour><point x="0" y="0"/></contour>
+                <component base="b" xOffset="10"/>
+            </outline>
+        </glyph>"#;
+
+        let options = ParseOptions::default().with_apply_defaults(true);
+        let data = parse_with_options(glif, options).unwrap();
+        assert_eq!(data.advance.unwrap().width, Some(0.0));
+
+        let outline = data.outline.unwrap();
+        let point = &outline.contours[0].points[0];
+        assert_eq!(point.point_type, Some(PointType::OffCurve));
+        assert_eq!(point.smooth, Some(false));
+
+        let component = &outline.components[0];
+        assert_eq!(component.x_offset, Some(10.0));
+        assert_eq!(component.x_scale, Some(1.0));
+        assert_eq!(component.y_offset, Some(0.0));
+    }
+
+    #[test]
+    fn test_parse_colors() {
+        let glif = r#"<glyph name="a" format="2">
+            <anchor x="0" y="0" name="top" color="1,0,0,1.0"/>
+            <guideline x="0" angle="90" color="0,0,1,0.5"/>
+        </glyph>"#;
+
+        let data = parse(glif).unwrap();
+        assert_eq!(data.anchors[0].color.unwrap().to_string(), "1,0,0,1");
+        assert_eq!(data.guidelines[0].color.unwrap().to_tuple().3, 0.5);
+
+        // A malformed color is kept as written instead of failing the glyph.
+        let glif = r#"<glyph name="a" format="2"><anchor x="0" y="0" color="red"/></glyph>"#;
+        let data = parse(glif).unwrap();
+        assert!(data.anchors[0].color.is_none());
+        assert_eq!(data.anchors[0].invalid_color.as_deref(), Some("red"));
+    }
+
+    const LIB_GLIF: &str = r#"<glyph name="a" format="2">
+        <lib>
+            <dict>
+                <key>public.markColor</key>
+                <string>1,0,0,1</string>
+                <key>com.example.nested</key>
+                <dict><key>lib</key><array><integer>1</integer></array></dict>
+            </dict>
+        </lib>
+        <outline><contour><point x="0" y="0" type="move"/></contour></outline>
+    </glyph>"#;
+
+    #[test]
+    fn test_parse_lib_eager() {
+        let data = parse(LIB_GLIF).unwrap();
+        let lib = data.lib.unwrap().into_dictionary().unwrap();
+        assert_eq!(lib.len(), 2);
+        assert!(data.lib_source.is_none());
+        assert_eq!(data.outline.unwrap().contours.len(), 1);
+    }
+
+    #[test]
+    fn test_parse_lib_lazy() {
+        let options = ParseOptions::default().with_lib_mode(LibMode::Lazy);
+        let mut data = parse_with_options(LIB_GLIF, options).unwrap();
+        assert!(data.lib.is_none());
+        assert!(data.lib_source.is_some());
+        assert_eq!(data.outline.as_ref().unwrap().contours.len(), 1);
+
+        data.resolve_lib().unwrap();
+        assert!(data.lib_source.is_none());
+        assert_eq!(data.mark_color().unwrap().to_tuple().0, 1.0);
+    }
+
+    #[test]
+    fn test_parse_lib_skip() {
+        let options = ParseOptions::default().with_lib_mode(LibMode::Skip);
+        let data = parse_with_options(LIB_GLIF, options).unwrap();
+        assert!(data.lib.is_none());
+        assert!(data.lib_source.is_none());
+        assert_eq!(data.outline.unwrap().contours.len(), 1);
+    }
 
-        Ok(reader)
+    #[test]
+    fn test_parse_empty_lib() {
+        let data = parse(r#"<glyph name="a" format="2"><lib> </lib></glyph>"#).unwrap();
+        assert!(data.lib.is_none());
     }
 }
@@ -2,8 +2,10 @@
 
 use quick_xml::events::Event;
 use std::io::BufRead;
+use std::ops::Range;
 
 use quick_xml::Reader;
+use quick_xml::name::QName;
 
 use crate::error::Error;
 
@@ -21,22 +23,30 @@ impl<R: BufRead> GlifEventReader<R> {
             buf: Vec::new(),
         }
     }
+
+    /// Skips all events up to and including the end tag matching `name`.
+    ///
+    /// Must be called right after the corresponding start event. Returns the byte range of the
+    /// element's content, i.e. between the `>` of the start tag and the `<` of the end tag.
+    pub fn skip_to_end(&mut self, name: &[u8]) -> Result<Range<usize>, Error> {
+        let span = self.reader.read_to_end_into(QName(name), &mut self.buf)?;
+        self.buf.clear();
+        Ok(span.start as usize..span.end as usize)
+    }
 }
 
 impl<R: BufRead> Iterator for GlifEventReader<R> {
     type Item = Result<Event<'static>, Error>;
     /// Returns the next GLIF event from the XML reader.
     fn next(&mut self) -> Option<Self::Item> {
-        loop {
-            match self.reader.read_event_into(&mut self.buf) {
-                Ok(Event::Eof) => return None,
-                Ok(ev) => {
-                    let owned = ev.into_owned();
-                    self.buf.clear();
-                    return Some(Ok(owned));
-                }
-                Err(e) => return Some(Err(Error::from(e))),
+        match self.reader.read_event_into(&mut self.buf) {
+            Ok(Event::Eof) => None,
+            Ok(ev) => {
+                let owned = ev.into_owned();
+                self.buf.clear();
+                Some(Ok(owned))
             }
+            Err(e) => Some(Err(Error::from(e))),
         }
     }
 }
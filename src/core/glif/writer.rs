@@ -0,0 +1,397 @@
+//! Serialization of [`GlifData`] into GLIF XML.
+
+use std::io::Write;
+
+use plist::XmlWriteOptions;
+use quick_xml::Writer;
+use quick_xml::events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event};
+
+use crate::color::Color;
+use crate::constants::xml::{attr, elem, val};
+use crate::error::Result;
+use crate::glif::{Anchor, Component, Contour, GlifData, Guideline, Image, Point, PointType};
+
+/// The number of spaces used per indentation level.
+const INDENT: usize = 2;
+
+/// Serializes a [`GlifData`] into a GLIF document.
+///
+/// Glyphs are written in the format they were read in. Format 1 glyphs store their anchors as
+/// contours (see [`Contour::format_1_anchor`]) and leave out the image, guidelines and
+/// identifiers, which format 1 cannot represent. Omitted (`None`) attributes are not written,
+/// and a lib pending [`GlifData::resolve_lib`] is parsed and written out.
+///
+/// # Errors
+///
+/// Returns an [`ErrorKind::UnsupportedFormat`] error if the glyph's major format version is
+/// unknown, and an error if the pending lib cannot be parsed or the lib cannot be serialized.
+///
+/// [`ErrorKind::UnsupportedFormat`]: crate::error::ErrorKind::UnsupportedFormat
+pub fn write_glif(glif: &GlifData) -> Result<Vec<u8>> {
+    glif.format.validate()?;
+    let converted;
+    let glif = if glif.lib_source.is_some() || glif.format.major == 1 {
+        let mut data = glif.clone();
+        data.resolve_lib()?;
+        if data.format.major == 1 {
+            to_format_1(&mut data);
+        }
+        converted = data;
+        &converted
+    } else {
+        glif
+    };
+
+    let mut writer = Writer::new_with_indent(Vec::new(), b' ', INDENT);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("UTF-8"), None)))?;
+
+    let format = glif.format;
+    let mut glyph = BytesStart::from_content("glyph", 5);
+    push(&mut glyph, attr::NAME, Some(&glif.name));
+    push(&mut glyph, attr::FORMAT, Some(format.major));
+    if format.minor != 0 {
+        push(&mut glyph, attr::FORMAT_MINOR, Some(format.minor));
+    }
+    writer.write_event(Event::Start(glyph))?;
+
+    if let Some(advance) = &glif.advance {
+        let mut e = start(elem::ADVANCE);
+        push(&mut e, attr::HEIGHT, advance.height.map(number));
+        push(&mut e, attr::WIDTH, advance.width.map(number));
+        writer.write_event(Event::Empty(e))?;
+    }
+    for unicode in &glif.unicodes {
+        let mut e = start(elem::UNICODE);
+        push(&mut e, attr::HEX, Some(format!("{unicode:04X}")));
+        writer.write_event(Event::Empty(e))?;
+    }
+    if let Some(note) = &glif.note {
+        writer.write_event(Event::Start(start(elem::NOTE)))?;
+        writer.write_event(Event::Text(BytesText::new(note)))?;
+        writer.write_event(Event::End(end(elem::NOTE)))?;
+    }
+    if let Some(image) = &glif.image {
+        writer.write_event(Event::Empty(image_element(image)))?;
+    }
+    for guideline in &glif.guidelines {
+        writer.write_event(Event::Empty(guideline_element(guideline)))?;
+    }
+    for anchor in &glif.anchors {
+        writer.write_event(Event::Empty(anchor_element(anchor)))?;
+    }
+    if let Some(outline) = &glif.outline {
+        writer.write_event(Event::Start(start(elem::OUTLINE)))?;
+        for contour in &outline.contours {
+            write_contour(&mut writer, contour)?;
+        }
+        for component in &outline.components {
+            writer.write_event(Event::Empty(component_element(component)))?;
+        }
+        writer.write_event(Event::End(end(elem::OUTLINE)))?;
+    }
+    if let Some(lib) = &glif.lib {
+        write_lib(&mut writer, lib)?;
+    }
+
+    writer.write_event(Event::End(end(elem::GLYPH)))?;
+    let mut buffer = writer.into_inner();
+    buffer.push(b'\n');
+    Ok(buffer)
+}
+
+/// Drops the data format 1 cannot represent, and stores anchors as single-point contours.
+fn to_format_1(data: &mut GlifData) {
+    data.image = None;
+    data.guidelines.clear();
+    let anchors = std::mem::take(&mut data.anchors);
+    if anchors.is_empty() && data.outline.is_none() {
+        return;
+    }
+    let outline = data.outline.get_or_insert_with(Default::default);
+    outline
+        .contours
+        .extend(anchors.into_iter().map(|anchor| Contour {
+            points: vec![Point {
+                x: anchor.x,
+                y: anchor.y,
+                point_type: Some(PointType::Move),
+                name: anchor.name,
+                ..Default::default()
+            }],
+            ..Default::default()
+        }));
+    for contour in &mut outline.contours {
+        contour.identifier = None;
+        for point in &mut contour.points {
+            point.identifier = None;
+        }
+    }
+    for component in &mut outline.components {
+        component.identifier = None;
+    }
+}
+
+/// Writes the `<lib>` element, indenting the plist content to the element's depth.
+fn write_lib(writer: &mut Writer<Vec<u8>>, lib: &plist::Value) -> Result<()> {
+    let mut content = Vec::new();
+    let options = XmlWriteOptions::default()
+        .root_element(false)
+        .indent(b' ', INDENT);
+    lib.to_writer_xml_with_options(&mut content, &options)?;
+
+    writer.write_event(Event::Start(start(elem::LIB)))?;
+    let indent = " ".repeat(INDENT * 2);
+    let buffer = writer.get_mut();
+    for line in content.split(|&b| b == b'\n') {
+        buffer.write_all(b"\n")?;
+        buffer.write_all(indent.as_bytes())?;
+        buffer.write_all(line)?;
+    }
+    writer.write_event(Event::End(end(elem::LIB)))?;
+    Ok(())
+}
+
+fn write_contour(writer: &mut Writer<Vec<u8>>, contour: &Contour) -> Result<()> {
+    let mut e = start(elem::CONTOUR);
+    push(&mut e, attr::IDENTIFIER, contour.identifier.as_deref());
+    if contour.points.is_empty() {
+        writer.write_event(Event::Empty(e))?;
+        return Ok(());
+    }
+    writer.write_event(Event::Start(e))?;
+    for point in &contour.points {
+        writer.write_event(Event::Empty(point_element(point)))?;
+    }
+    writer.write_event(Event::End(end(elem::CONTOUR)))?;
+    Ok(())
+}
+
+fn point_element(point: &Point) -> BytesStart<'static> {
+    let mut e = start(elem::POINT);
+    push(&mut e, attr::X, point.x.map(number));
+    push(&mut e, attr::Y, point.y.map(number));
+    push(
+        &mut e,
+        attr::TYPE,
+        point.point_type.as_ref().and_then(point_type),
+    );
+    if point.smooth == Some(true) {
+        push(&mut e, attr::SMOOTH, Some(val::YES));
+    }
+    push(&mut e, attr::NAME, point.name.as_deref());
+    push(&mut e, attr::IDENTIFIER, point.identifier.as_deref());
+    e
+}
+
+fn component_element(component: &Component) -> BytesStart<'static> {
+    let mut e = start(elem::COMPONENT);
+    push(&mut e, attr::BASE, component.base.as_deref());
+    push(&mut e, attr::X_SCALE, component.x_scale.map(number));
+    push(&mut e, attr::XY_SCALE, component.xy_scale.map(number));
+    push(&mut e, attr::YX_SCALE, component.yx_scale.map(number));
+    push(&mut e, attr::Y_SCALE, component.y_scale.map(number));
+    push(&mut e, attr::X_OFFSET, component.x_offset.map(number));
+    push(&mut e, attr::Y_OFFSET, component.y_offset.map(number));
+    push(&mut e, attr::IDENTIFIER, component.identifier.as_deref());
+    e
+}
+
+fn image_element(image: &Image) -> BytesStart<'static> {
+    let mut e = start(elem::IMAGE);
+    push(&mut e, attr::FILE_NAME, image.file_name.as_deref());
+    push(&mut e, attr::X_SCALE, image.x_scale.map(number));
+    push(&mut e, attr::XY_SCALE, image.xy_scale.map(number));
+    push(&mut e, attr::YX_SCALE, image.yx_scale.map(number));
+    push(&mut e, attr::Y_SCALE, image.y_scale.map(number));
+    push(&mut e, attr::X_OFFSET, image.x_offset.map(number));
+    push(&mut e, attr::Y_OFFSET, image.y_offset.map(number));
+    push(
+        &mut e,
+        attr::COLOR,
+        color(image.color, &image.invalid_color),
+    );
+    e
+}
+
+fn guideline_element(guideline: &Guideline) -> BytesStart<'static> {
+    let mut e = start(elem::GUIDELINE);
+    push(&mut e, attr::X, guideline.x.map(number));
+    push(&mut e, attr::Y, guideline.y.map(number));
+    push(&mut e, attr::ANGLE, guideline.angle.map(number));
+    push(&mut e, attr::NAME, guideline.name.as_deref());
+    push(
+        &mut e,
+        attr::COLOR,
+        color(guideline.color, &guideline.invalid_color),
+    );
+    push(&mut e, attr::IDENTIFIER, guideline.identifier.as_deref());
+    e
+}
+
+fn anchor_element(anchor: &Anchor) -> BytesStart<'static> {
+    let mut e = start(elem::ANCHOR);
+    push(&mut e, attr::X, anchor.x.map(number));
+    push(&mut e, attr::Y, anchor.y.map(number));
+    push(&mut e, attr::NAME, anchor.name.as_deref());
+    push(
+        &mut e,
+        attr::COLOR,
+        color(anchor.color, &anchor.invalid_color),
+    );
+    push(&mut e, attr::IDENTIFIER, anchor.identifier.as_deref());
+    e
+}
+
+/// Returns the `type` attribute value for a point type, or `None` for the default `offcurve`.
+fn point_type(point_type: &PointType) -> Option<&str> {
+    match point_type {
+        PointType::OffCurve => None,
+        other => Some(other.as_str()),
+    }
+}
+
+/// Formats a coordinate or scale value, writing integral values without a fraction.
+fn number(value: f64) -> String {
+    if value == 0.0 {
+        return "0".to_string();
+    }
+    value.to_string()
+}
+
+fn start(name: &[u8]) -> BytesStart<'static> {
+    BytesStart::new(String::from_utf8_lossy(name).into_owned())
+}
+
+fn end(name: &[u8]) -> BytesEnd<'static> {
+    BytesEnd::new(String::from_utf8_lossy(name).into_owned())
+}
+
+/// Returns the value of a `color` attribute, keeping an unparsable value as it was read.
+fn color(color: Option<Color>, invalid: &Option<String>) -> Option<String> {
+    color.map(|c| c.to_string()).or_else(|| invalid.clone())
+}
+
+/// Adds an attribute to `e` if `value` is present.
+fn push(e: &mut BytesStart, name: &[u8], value: Option<impl ToString>) {
+    if let Some(value) = value {
+        e.push_attribute((name, value.to_string().as_bytes()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::error::ErrorKind;
+    use crate::glif::{GlifFormatVersion, GlifParser, ParseOptions};
+    use crate::paths::UfoRelativePath;
+    use crate::test_utils::MockProvider;
+
+    fn parse(glif: &[u8]) -> GlifData {
+        let path = UfoRelativePath::GlifFile("a.glif".to_string()).to_pathbuf();
+        let provider = MockProvider::new();
+        provider.with_file(&path, glif);
+        GlifParser::with_options(provider, ParseOptions::default())
+            .unwrap()
+            .parse_glif("a.glif")
+            .unwrap()
+    }
+
+    const GLIF: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="A" format="2">
+  <advance width="500.5"/>
+  <unicode hex="0041"/>
+  <note>Some note</note>
+  <guideline x="10" angle="90" identifier="guide1"/>
+  <anchor x="250" y="700" name="top" color="1,0,0,1"/>
+  <outline>
+    <contour identifier="c1">
+      <point x="0" y="0" type="line"/>
+      <point x="100" y="-50"/>
+      <point x="200" y="0" type="curve" smooth="yes" name="join"/>
+    </contour>
+    <component base="B" xOffset="10"/>
+  </outline>
+  <lib>
+    <dict>
+      <key>public.markColor</key>
+      <string>1,0,0,1</string>
+    </dict>
+  </lib>
+</glyph>
+"#;
+
+    #[test]
+    fn test_write_glif_round_trip() {
+        let data = parse(GLIF.as_bytes());
+        let written = write_glif(&data).unwrap();
+        assert_eq!(String::from_utf8(written).unwrap(), GLIF);
+    }
+
+    #[test]
+    fn test_write_glif_keeps_invalid_color() {
+        let data =
+            parse(br#"<glyph name="a" format="2"><anchor x="0" y="0" color="red"/></glyph>"#);
+        let written = String::from_utf8(write_glif(&data).unwrap()).unwrap();
+        assert!(written.contains(r#"<anchor x="0" y="0" color="red"/>"#));
+    }
+
+    #[test]
+    fn test_write_glif_without_note_and_outline() {
+        let glif = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="a" format="2">
+  <advance width="500"/>
+</glyph>
+"#;
+        let data = parse(glif.as_bytes());
+        assert!(data.note.is_none());
+        assert!(data.outline.is_none());
+        assert_eq!(String::from_utf8(write_glif(&data).unwrap()).unwrap(), glif);
+    }
+
+    #[test]
+    fn test_write_glif_format_1_round_trip() {
+        let glif = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="a" format="1">
+  <advance width="500"/>
+  <outline>
+    <contour>
+      <point x="250" y="700" type="move" name="top"/>
+    </contour>
+    <contour>
+      <point x="0" y="0" type="line"/>
+      <point x="10" y="0" type="line"/>
+    </contour>
+  </outline>
+</glyph>
+"#;
+        let data = parse(glif.as_bytes());
+        assert_eq!(String::from_utf8(write_glif(&data).unwrap()).unwrap(), glif);
+    }
+
+    #[test]
+    fn test_write_glif_format_1_stores_anchors_as_contours() {
+        let mut data = parse(br#"<glyph name="a" format="1"/>"#);
+        data.anchors.push(Anchor {
+            x: Some(250.0),
+            y: Some(700.0),
+            name: Some("top".into()),
+            identifier: Some("anchor1".into()),
+            ..Default::default()
+        });
+        data.guidelines.push(Guideline::default());
+        let written = String::from_utf8(write_glif(&data).unwrap()).unwrap();
+        assert!(written.contains(r#"<point x="250" y="700" type="move" name="top"/>"#));
+        assert!(!written.contains("<anchor"));
+        assert!(!written.contains("<guideline"));
+        assert!(!written.contains("identifier"));
+    }
+
+    #[test]
+    fn test_write_glif_rejects_unknown_format() {
+        let mut data = parse(br#"<glyph name="a" format="2"/>"#);
+        data.format = GlifFormatVersion::new(3, 0);
+        let err = write_glif(&data).unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::UnsupportedFormat("3.0".into()));
+    }
+}
@@ -0,0 +1,189 @@
+//! Canonical content hashes of glyph outlines.
+//!
+//! A hash covers the geometry of the outline only: point types and coordinates, in contour
+//! order. Identifiers, point names, smooth flags and formatting are ignored, and coordinates are
+//! rounded to three decimal places so that floating-point noise from component transforms does
+//! not change the hash. Hashes are stable across runs and platforms.
+
+use std::fmt::Write;
+
+use sha2::{Digest, Sha256};
+
+use crate::glif::{Contour, GlifData};
+use crate::path::format_number;
+
+/// Selects the glyph data included in an outline hash besides the outline itself.
+///
+/// # Example
+///
+/// ```
+/// use ufo_gleaner::hash::HashOptions;
+///
+/// let options = HashOptions::default().with_advance(true);
+/// assert!(options.advance && !options.anchors);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HashOptions {
+    /// Include the advance width and height.
+    pub advance: bool,
+    /// Include the anchors, by name and position, regardless of their order.
+    pub anchors: bool,
+}
+
+impl HashOptions {
+    /// Sets whether the advance is included.
+    pub fn with_advance(mut self, advance: bool) -> Self {
+        self.advance = advance;
+        self
+    }
+
+    /// Sets whether the anchors are included.
+    pub fn with_anchors(mut self, anchors: bool) -> Self {
+        self.anchors = anchors;
+        self
+    }
+}
+
+/// Returns the SHA-256 hash, as a lowercase hexadecimal string, of the canonical form of
+/// `contours`, with the advance and anchors of `glyph` as selected by `options`.
+///
+/// `contours` are normally the decomposed contours of `glyph` (see
+/// [`crate::font::Glyph::decomposed_contours`]), so that components are hashed as the outlines
+/// they draw.
+pub fn outline_hash(contours: &[Contour], glyph: &GlifData, options: HashOptions) -> String {
+    let mut canonical = String::new();
+    for contour in contours {
+        canonical.push_str("contour\n");
+        for point in &contour.points {
+            let _ = writeln!(
+                canonical,
+                "{} {} {}",
+                point.point_type_or_default().as_str(),
+                format_number(point.x.unwrap_or_default()),
+                format_number(point.y.unwrap_or_default())
+            );
+        }
+    }
+    if options.advance {
+        let advance = glyph.advance.clone().unwrap_or_default();
+        let _ = writeln!(
+            canonical,
+            "advance {} {}",
+            format_number(advance.width_or_default()),
+            format_number(advance.height_or_default())
+        );
+    }
+    if options.anchors {
+        let mut anchors = glyph
+            .anchors
+            .iter()
+            .map(|anchor| {
+                format!(
+                    "anchor {} {} {}\n",
+                    anchor.name.as_deref().unwrap_or_default(),
+                    format_number(anchor.x.unwrap_or_default()),
+                    format_number(anchor.y.unwrap_or_default())
+                )
+            })
+            .collect::<Vec<_>>();
+        anchors.sort_unstable();
+        anchors.iter().for_each(|anchor| canonical.push_str(anchor));
+    }
+
+    Sha256::digest(canonical.as_bytes())
+        .iter()
+        .fold(String::with_capacity(64), |mut hex, byte| {
+            let _ = write!(hex, "{byte:02x}");
+            hex
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::glif::{Anchor, Point, PointType};
+    use crate::transform::Transform;
+
+    fn contour(points: &[(f64, f64)]) -> Contour {
+        Contour {
+            points: points
+                .iter()
+                .map(|&(x, y)| Point {
+                    x: Some(x),
+                    y: Some(y),
+                    point_type: Some(PointType::Line),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_outline_hash() {
+        let glyph = GlifData::default();
+        let square = contour(&[(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 0.0)]);
+        let hash = outline_hash(
+            std::slice::from_ref(&square),
+            &glyph,
+            HashOptions::default(),
+        );
+        assert_eq!(hash.len(), 64);
+
+        // Rotating by 360 degrees leaves floating-point noise that must not change the hash.
+        let rotated = square.transformed(&Transform::rotate(360.0));
+        assert_ne!(rotated.points[2].x, Some(100.0));
+        assert_eq!(
+            outline_hash(&[rotated], &glyph, HashOptions::default()),
+            hash
+        );
+
+        let mut smooth = square.clone();
+        smooth.points[0].smooth = Some(true);
+        smooth.points[0].identifier = Some("p0".into());
+        assert_eq!(
+            outline_hash(&[smooth], &glyph, HashOptions::default()),
+            hash
+        );
+
+        let moved = contour(&[(0.0, 0.0), (0.0, 100.0), (100.0, 100.0), (100.0, 1.0)]);
+        assert_ne!(outline_hash(&[moved], &glyph, HashOptions::default()), hash);
+        assert_ne!(outline_hash(&[], &glyph, HashOptions::default()), hash);
+    }
+
+    #[test]
+    fn test_outline_hash_options() {
+        let anchor = |name: &str, x| Anchor {
+            name: Some(name.into()),
+            x: Some(x),
+            y: Some(0.0),
+            ..Default::default()
+        };
+        let a = GlifData {
+            anchors: vec![anchor("top", 10.0), anchor("bottom", 20.0)],
+            ..Default::default()
+        };
+        let b = GlifData {
+            anchors: vec![anchor("bottom", 20.0), anchor("top", 10.0)],
+            ..Default::default()
+        };
+        let c = GlifData {
+            anchors: vec![anchor("top", 10.0)],
+            ..Default::default()
+        };
+        let options = HashOptions::default().with_anchors(true);
+        assert_eq!(
+            outline_hash(&[], &a, options),
+            outline_hash(&[], &b, options)
+        );
+        assert_ne!(
+            outline_hash(&[], &a, options),
+            outline_hash(&[], &c, options)
+        );
+        assert_eq!(
+            outline_hash(&[], &a, HashOptions::default()),
+            outline_hash(&[], &c, HashOptions::default())
+        );
+    }
+}
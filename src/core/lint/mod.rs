@@ -0,0 +1,496 @@
+//! A pluggable framework for font quality checks.
+//!
+//! A [`Rule`] inspects glyph data and the font, and reports problems through a [`Context`]. A
+//! [`Linter`] runs a set of rules over a [`Font`], applying per-rule [`RuleConfig`]s and
+//! suppressions, and collects the resulting [`Diagnostic`]s.
+//!
+//! Rules can be suppressed by listing their ids, or `"*"` for all rules, in an array under the
+//! [`lib_key::LINT_IGNORE`] key: in `lib.plist` for the whole font, or in a glyph's lib for that
+//! glyph.
+
+pub mod rules;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+use std::str::FromStr;
+
+use plist::{Dictionary, Value};
+use serde::Serialize;
+
+use crate::constants::ufo::lib_key;
+use crate::error::{Error, ErrorKind, Result};
+use crate::font::Font;
+use crate::glif::GlifData;
+
+pub use rules::*;
+
+/// The id of the diagnostic reported for glyphs that cannot be parsed.
+pub const UNREADABLE_GLYPH: &str = "unreadable-glyph";
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Info => write!(f, "info"),
+            Self::Warning => write!(f, "warning"),
+            Self::Error => write!(f, "error"),
+        }
+    }
+}
+
+impl FromStr for Severity {
+    type Err = Error;
+
+    /// Parses `"info"`, `"warning"` or `"error"` into a [`Severity`].
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "info" => Ok(Self::Info),
+            "warning" => Ok(Self::Warning),
+            "error" => Ok(Self::Error),
+            _ => {
+                let s = s.to_owned();
+                Err(Error::new(ErrorKind::Parse).with_context(move || {
+                    format!("unknown severity '{}', expected info, warning or error", s)
+                }))
+            }
+        }
+    }
+}
+
+/// A problem reported by a [`Rule`].
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    /// The id of the rule that reported the problem.
+    pub rule: String,
+    pub severity: Severity,
+    /// The glyph the problem was found in, or `None` for font-level problems.
+    pub glyph: Option<String>,
+    pub message: String,
+    /// The index of the contour the problem was found in, if any.
+    pub contour: Option<usize>,
+    /// The index of the point within `contour` the problem was found at, if any.
+    pub point: Option<usize>,
+}
+
+/// Serializes diagnostics as a JSON array.
+pub fn to_json(diagnostics: &[Diagnostic]) -> Result<String> {
+    Ok(serde_json::to_string(diagnostics)?)
+}
+
+/// Per-rule settings for a [`Linter`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct RuleConfig {
+    /// Whether the rule runs at all.
+    pub enabled: bool,
+    /// Overrides the rule's [`Rule::default_severity`].
+    pub severity: Option<Severity>,
+    /// Rule-specific options, read through [`Context::option`].
+    pub options: Dictionary,
+}
+
+impl Default for RuleConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            severity: None,
+            options: Dictionary::new(),
+        }
+    }
+}
+
+impl RuleConfig {
+    /// Sets whether the rule runs.
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Overrides the severity of the rule's diagnostics.
+    pub fn with_severity(mut self, severity: Severity) -> Self {
+        self.severity = Some(severity);
+        self
+    }
+
+    /// Sets a rule-specific option.
+    pub fn with_option(mut self, key: &str, value: impl Into<Value>) -> Self {
+        self.options.insert(key.to_owned(), value.into());
+        self
+    }
+}
+
+/// A font quality check.
+///
+/// Both check methods default to doing nothing, so a rule only implements the scope it needs.
+pub trait Rule {
+    /// Returns the rule's unique id, e.g. `"open-contour"`.
+    fn id(&self) -> &str;
+
+    /// Returns the severity of the rule's diagnostics unless configured otherwise.
+    fn default_severity(&self) -> Severity {
+        Severity::Warning
+    }
+
+    /// Checks a single glyph, whose name is [`Context::glyph`].
+    fn check_glyph(&self, _glyph: &GlifData, _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+
+    /// Checks the font as a whole, e.g. its plists or relations between glyphs.
+    fn check_font(&self, _font: &Rc<Font>, _ctx: &mut Context) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Collects the diagnostics of a single rule run.
+pub struct Context<'a> {
+    rule: &'a str,
+    severity: Severity,
+    config: &'a RuleConfig,
+    glyph: Option<&'a str>,
+    diagnostics: &'a mut Vec<Diagnostic>,
+}
+
+impl Context<'_> {
+    /// Returns the name of the glyph being checked, or `None` in [`Rule::check_font`].
+    pub fn glyph(&self) -> Option<&str> {
+        self.glyph
+    }
+
+    /// Returns all rule-specific options from the rule's [`RuleConfig`].
+    pub fn options(&self) -> &Dictionary {
+        &self.config.options
+    }
+
+    /// Returns a rule-specific option from the rule's [`RuleConfig`].
+    pub fn option(&self, key: &str) -> Option<&Value> {
+        self.config.options.get(key)
+    }
+
+    /// Returns a numeric option, or `default` if it is not set or not a number.
+    pub fn option_f64(&self, key: &str, default: f64) -> f64 {
+        match self.option(key) {
+            Some(Value::Real(value)) => *value,
+            Some(Value::Integer(value)) => value.as_signed().map_or(default, |v| v as f64),
+            _ => default,
+        }
+    }
+
+    /// Reports a problem with the current glyph or the font.
+    pub fn report(&mut self, message: impl Into<String>) {
+        self.push(message.into(), None, None);
+    }
+
+    /// Reports a problem at a contour, or at a point within it.
+    pub fn report_at(&mut self, message: impl Into<String>, contour: usize, point: Option<usize>) {
+        self.push(message.into(), Some(contour), point);
+    }
+
+    fn push(&mut self, message: String, contour: Option<usize>, point: Option<usize>) {
+        self.diagnostics.push(Diagnostic {
+            rule: self.rule.to_owned(),
+            severity: self.severity,
+            glyph: self.glyph.map(str::to_owned),
+            message,
+            contour,
+            point,
+        });
+    }
+}
+
+/// Runs a set of [`Rule`]s over a font.
+///
+/// # Example
+///
+/// ```no_run
+/// use ufo_gleaner::font::Font;
+/// use ufo_gleaner::lint::{Linter, RuleConfig, Severity};
+/// use ufo_gleaner::provider::FileProvider;
+///
+/// let font = Font::new(FileProvider::new("MyFont.ufo")).unwrap();
+/// let linter = Linter::with_default_rules()
+///     .with_config("non-integer-coordinate", RuleConfig::default().with_enabled(false))
+///     .with_config("open-contour", RuleConfig::default().with_severity(Severity::Error));
+/// for diagnostic in linter.lint(&font).unwrap() {
+///     println!("{:?}", diagnostic);
+/// }
+/// ```
+#[derive(Default)]
+pub struct Linter {
+    rules: Vec<Box<dyn Rule>>,
+    configs: HashMap<String, RuleConfig>,
+}
+
+impl Linter {
+    /// Creates a linter without rules.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a linter with the starter rules from [`rules::default_rules`].
+    pub fn with_default_rules() -> Self {
+        let mut linter = Self::new();
+        for rule in rules::default_rules() {
+            linter.add_rule(rule);
+        }
+        linter
+    }
+
+    /// Adds a rule, replacing any rule with the same id.
+    pub fn with_rule(mut self, rule: impl Rule + 'static) -> Self {
+        self.add_rule(Box::new(rule));
+        self
+    }
+
+    /// Sets the configuration of the rule with the given id.
+    pub fn with_config(mut self, id: &str, config: RuleConfig) -> Self {
+        self.set_config(id, config);
+        self
+    }
+
+    /// Adds a rule, replacing any rule with the same id.
+    pub fn add_rule(&mut self, rule: Box<dyn Rule>) {
+        match self.rules.iter().position(|r| r.id() == rule.id()) {
+            Some(index) => self.rules[index] = rule,
+            None => self.rules.push(rule),
+        }
+    }
+
+    /// Sets the configuration of the rule with the given id.
+    pub fn set_config(&mut self, id: &str, config: RuleConfig) {
+        self.configs.insert(id.to_owned(), config);
+    }
+
+    /// Returns the configuration of the rule with the given id.
+    pub fn config(&self, id: &str) -> RuleConfig {
+        self.configs.get(id).cloned().unwrap_or_default()
+    }
+
+    /// Returns the ids of all rules, in the order they run.
+    pub fn rule_ids(&self) -> Vec<&str> {
+        self.rules.iter().map(|rule| rule.id()).collect()
+    }
+
+    /// Runs all enabled rules over the font and returns their diagnostics.
+    ///
+    /// Font-level checks run first, then every glyph is checked in glyph order. Glyphs that
+    /// cannot be parsed are reported as [`UNREADABLE_GLYPH`] errors and skipped.
+    pub fn lint(&self, font: &Rc<Font>) -> Result<Vec<Diagnostic>> {
+        let font_ignored = match font.lib() {
+            Ok(lib) => ignored_rules(lib.get(lib_key::LINT_IGNORE)),
+            Err(_) => Vec::new(),
+        };
+        let default_config = RuleConfig::default();
+        let mut diagnostics = Vec::new();
+
+        for rule in &self.rules {
+            let config = self.configs.get(rule.id()).unwrap_or(&default_config);
+            if !config.enabled || is_ignored(&font_ignored, rule.id()) {
+                continue;
+            }
+            let mut ctx = Context {
+                rule: rule.id(),
+                severity: config.severity.unwrap_or(rule.default_severity()),
+                config,
+                glyph: None,
+                diagnostics: &mut diagnostics,
+            };
+            rule.check_font(font, &mut ctx)?;
+        }
+
+        for glyph in font.iter() {
+            match glyph.data() {
+                Ok(data) => {
+                    diagnostics.extend(self.lint_glyph(&data, glyph.name(), &font_ignored)?)
+                }
+                Err(err) => diagnostics.push(Diagnostic {
+                    rule: UNREADABLE_GLYPH.to_owned(),
+                    severity: Severity::Error,
+                    glyph: Some(glyph.name().to_owned()),
+                    message: err.to_string(),
+                    contour: None,
+                    point: None,
+                }),
+            }
+        }
+        Ok(diagnostics)
+    }
+
+    /// Runs the glyph checks of all enabled rules over a single glyph.
+    ///
+    /// `ignored` lists rule ids suppressed in addition to those in the glyph's lib.
+    pub fn lint_glyph(
+        &self,
+        glyph: &GlifData,
+        name: &str,
+        ignored: &[String],
+    ) -> Result<Vec<Diagnostic>> {
+        let glyph_ignored = ignored_rules(
+            glyph
+                .lib
+                .as_ref()
+                .and_then(Value::as_dictionary)
+                .and_then(|lib| lib.get(lib_key::LINT_IGNORE)),
+        );
+        let default_config = RuleConfig::default();
+        let mut diagnostics = Vec::new();
+
+        for rule in &self.rules {
+            let config = self.configs.get(rule.id()).unwrap_or(&default_config);
+            if !config.enabled
+                || is_ignored(ignored, rule.id())
+                || is_ignored(&glyph_ignored, rule.id())
+            {
+                continue;
+            }
+            let mut ctx = Context {
+                rule: rule.id(),
+                severity: config.severity.unwrap_or(rule.default_severity()),
+                config,
+                glyph: Some(name),
+                diagnostics: &mut diagnostics,
+            };
+            rule.check_glyph(glyph, &mut ctx)?;
+        }
+        Ok(diagnostics)
+    }
+}
+
+/// Reads the rule ids listed in a lint ignore lib value, skipping non-string entries.
+fn ignored_rules(value: Option<&Value>) -> Vec<String> {
+    value
+        .and_then(Value::as_array)
+        .map(|ids| {
+            ids.iter()
+                .filter_map(Value::as_string)
+                .map(str::to_owned)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn is_ignored(ignored: &[String], id: &str) -> bool {
+    ignored
+        .iter()
+        .any(|ignored| ignored == id || ignored == "*")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::paths::UfoRelativePath;
+    use crate::test_utils::MockProvider;
+
+    struct NameLength;
+
+    impl Rule for NameLength {
+        fn id(&self) -> &str {
+            "name-length"
+        }
+
+        fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+            let max = ctx.option_f64("max", 1.0) as usize;
+            if glyph.name.len() > max {
+                ctx.report(format!("name is longer than {max} characters"));
+            }
+            Ok(())
+        }
+
+        fn check_font(&self, font: &Rc<Font>, ctx: &mut Context) -> Result<()> {
+            if font.is_empty() {
+                ctx.report("font has no glyphs");
+            }
+            Ok(())
+        }
+    }
+
+    fn font() -> Rc<Font> {
+        let provider = MockProvider::new();
+        provider
+            .with_file(
+                &UfoRelativePath::Contents.to_pathbuf(),
+                br#"<plist version='1.0'><dict>
+                    <key>A</key><string>A_.glif</string>
+                    <key>Aacute</key><string>A_acute.glif</string>
+                    <key>Abreve</key><string>A_breve.glif</string>
+                    <key>broken</key><string>broken.glif</string>
+                </dict></plist>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("A_.glif".into()).to_pathbuf(),
+                br#"<glyph name="A" format="2"/>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("A_acute.glif".into()).to_pathbuf(),
+                br#"<glyph name="Aacute" format="2"/>"#,
+            )
+            .with_file(
+                &UfoRelativePath::GlifFile("A_breve.glif".into()).to_pathbuf(),
+                br#"<glyph name="Abreve" format="2"><lib><dict>
+                    <key>com.github.knutnergaard.ufoGleaner.lintIgnore</key>
+                    <array><string>name-length</string></array>
+                </dict></lib></glyph>"#,
+            );
+        Font::new(provider).unwrap()
+    }
+
+    #[test]
+    fn test_linter_runs_rules() {
+        let linter = Linter::new().with_rule(NameLength);
+        let diagnostics = linter.lint(&font()).unwrap();
+
+        assert_eq!(diagnostics.len(), 2);
+        assert_eq!(diagnostics[0].rule, "name-length");
+        assert_eq!(diagnostics[0].glyph.as_deref(), Some("Aacute"));
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+        assert_eq!(diagnostics[1].rule, UNREADABLE_GLYPH);
+        assert_eq!(diagnostics[1].glyph.as_deref(), Some("broken"));
+
+        let json = to_json(&diagnostics[..1]).unwrap();
+        assert!(
+            json.starts_with(r#"[{"rule":"name-length","severity":"warning","glyph":"Aacute""#)
+        );
+    }
+
+    #[test]
+    fn test_linter_config() {
+        let linter = Linter::new().with_rule(NameLength).with_config(
+            "name-length",
+            RuleConfig::default()
+                .with_severity(Severity::Error)
+                .with_option("max", 10),
+        );
+        assert_eq!(linter.rule_ids(), ["name-length"]);
+        let diagnostics = linter.lint(&font()).unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].rule, UNREADABLE_GLYPH);
+
+        let linter = linter.with_config(
+            "name-length",
+            RuleConfig::default()
+                .with_severity(Severity::Error)
+                .with_enabled(true),
+        );
+        let diagnostics = linter.lint(&font()).unwrap();
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+
+        let linter = linter.with_config("name-length", RuleConfig::default().with_enabled(false));
+        assert_eq!(linter.lint(&font()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_severity_from_str() {
+        assert_eq!("error".parse::<Severity>().unwrap(), Severity::Error);
+        assert!("fatal".parse::<Severity>().is_err());
+    }
+}
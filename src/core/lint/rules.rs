@@ -0,0 +1,477 @@
+//! The starter set of lint rules.
+
+use std::rc::Rc;
+
+use plist::Value;
+
+use crate::error::Result;
+use crate::font::Font;
+use crate::glif::{Contour, GlifData, PointType};
+use crate::lint::{Context, Rule, Severity};
+use crate::plist::LayerInfo;
+
+/// Returns one instance of every starter rule, in the order they run by default.
+pub fn default_rules() -> Vec<Box<dyn Rule>> {
+    vec![
+        Box::new(OpenContour),
+        Box::new(SinglePointContour),
+        Box::new(DuplicatePoint),
+        Box::new(NonIntegerCoordinate),
+        Box::new(EmptyGlyphWithUnicode),
+        Box::new(OverlappingAnchors),
+        Box::new(UnsupportedFormatVersion),
+        Box::new(InvalidColor),
+    ]
+}
+
+/// Reports contours that start with a `move` point and are therefore open.
+///
+/// The single named `move` points that format 1 glyphs use as anchors are not reported.
+pub struct OpenContour;
+
+impl Rule for OpenContour {
+    fn id(&self) -> &str {
+        "open-contour"
+    }
+
+    fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+        for (index, contour) in glyph.contours().iter().enumerate() {
+            if is_open(contour) && !glyph.is_anchor_contour(contour) {
+                ctx.report_at("contour is open", index, None);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports contours with a single point, which draw nothing.
+///
+/// The single named `move` points that format 1 glyphs use as anchors are not reported.
+pub struct SinglePointContour;
+
+impl Rule for SinglePointContour {
+    fn id(&self) -> &str {
+        "single-point-contour"
+    }
+
+    fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+        for (index, contour) in glyph.contours().iter().enumerate() {
+            if contour.points.len() == 1 && !glyph.is_anchor_contour(contour) {
+                ctx.report_at("contour has a single point", index, Some(0));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports points at the same position as the previous point in their contour.
+///
+/// The first point of a closed contour is compared with the last point.
+pub struct DuplicatePoint;
+
+impl Rule for DuplicatePoint {
+    fn id(&self) -> &str {
+        "duplicate-point"
+    }
+
+    fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+        for (index, contour) in glyph.contours().iter().enumerate() {
+            let points = &contour.points;
+            if points.len() < 2 {
+                continue;
+            }
+            let first = if is_open(contour) { 1 } else { 0 };
+            for current in first..points.len() {
+                let previous = (current + points.len() - 1) % points.len();
+                let (a, b) = (&points[previous], &points[current]);
+                if a.x.is_some() && a.y.is_some() && a.x == b.x && a.y == b.y {
+                    ctx.report_at(
+                        format!(
+                            "point at ({}, {}) duplicates the previous point",
+                            b.x.unwrap_or_default(),
+                            b.y.unwrap_or_default()
+                        ),
+                        index,
+                        Some(current),
+                    );
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports points, anchors and component offsets with fractional coordinates.
+pub struct NonIntegerCoordinate;
+
+impl Rule for NonIntegerCoordinate {
+    fn id(&self) -> &str {
+        "non-integer-coordinate"
+    }
+
+    fn default_severity(&self) -> Severity {
+        Severity::Info
+    }
+
+    fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+        for (index, contour) in glyph.contours().iter().enumerate() {
+            for (point_index, point) in contour.points.iter().enumerate() {
+                if !is_integral(point.x) || !is_integral(point.y) {
+                    ctx.report_at(
+                        format!(
+                            "point at ({}, {}) has non-integer coordinates",
+                            point.x.unwrap_or_default(),
+                            point.y.unwrap_or_default()
+                        ),
+                        index,
+                        Some(point_index),
+                    );
+                }
+            }
+        }
+        for anchor in &glyph.anchors {
+            if !is_integral(anchor.x) || !is_integral(anchor.y) {
+                ctx.report(format!(
+                    "anchor {} has non-integer coordinates",
+                    anchor.name.as_deref().unwrap_or("<unnamed>")
+                ));
+            }
+        }
+        for component in glyph.components() {
+            if !is_integral(component.x_offset) || !is_integral(component.y_offset) {
+                ctx.report(format!(
+                    "component {} has a non-integer offset",
+                    component.base.as_deref().unwrap_or("<no base>")
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports glyphs with Unicode code points but no contours or components.
+///
+/// Glyphs whose code points are all whitespace or invisible format characters are expected to
+/// be empty and are not reported.
+pub struct EmptyGlyphWithUnicode;
+
+impl Rule for EmptyGlyphWithUnicode {
+    fn id(&self) -> &str {
+        "empty-glyph-with-unicode"
+    }
+
+    fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+        let empty = glyph.outline.as_ref().is_none_or(|outline| {
+            outline.components.is_empty()
+                && outline
+                    .contours
+                    .iter()
+                    .all(|contour| contour.points.is_empty())
+        });
+        if empty && !glyph.unicodes.iter().all(|&unicode| is_blank(unicode)) {
+            let unicodes = glyph
+                .unicodes
+                .iter()
+                .map(|unicode| format!("U+{unicode:04X}"))
+                .collect::<Vec<_>>();
+            ctx.report(format!(
+                "glyph is empty but mapped to {}",
+                unicodes.join(", ")
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Reports pairs of anchors at the same position.
+///
+/// The `tolerance` option sets the maximum distance along each axis at which anchors are
+/// considered overlapping. It defaults to `0`.
+pub struct OverlappingAnchors;
+
+impl Rule for OverlappingAnchors {
+    fn id(&self) -> &str {
+        "overlapping-anchors"
+    }
+
+    fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+        let tolerance = ctx.option_f64("tolerance", 0.0);
+        let anchors = &glyph.anchors;
+        for (i, a) in anchors.iter().enumerate() {
+            for b in &anchors[i + 1..] {
+                let (Some(ax), Some(ay), Some(bx), Some(by)) = (a.x, a.y, b.x, b.y) else {
+                    continue;
+                };
+                if (ax - bx).abs() <= tolerance && (ay - by).abs() <= tolerance {
+                    ctx.report(format!(
+                        "anchors {} and {} overlap at ({}, {})",
+                        a.name.as_deref().unwrap_or("<unnamed>"),
+                        b.name.as_deref().unwrap_or("<unnamed>"),
+                        ax,
+                        ay
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reports glyphs whose GLIF format version is not one this crate fully supports.
+///
+/// Glyphs with a known major version and a newer minor version are still read, as minor
+/// revisions may only add data, but anything that data adds is ignored.
+pub struct UnsupportedFormatVersion;
+
+impl Rule for UnsupportedFormatVersion {
+    fn id(&self) -> &str {
+        "unsupported-format-version"
+    }
+
+    fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+        if !glyph.format.is_supported() {
+            ctx.report(format!(
+                "GLIF format version {} is not supported; data it adds is ignored",
+                glyph.format
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Reports anchors, guidelines and images whose `color` attribute is not a valid color, as well
+/// as invalid glyph mark colors and layer colors.
+pub struct InvalidColor;
+
+impl Rule for InvalidColor {
+    fn id(&self) -> &str {
+        "invalid-color"
+    }
+
+    fn check_glyph(&self, glyph: &GlifData, ctx: &mut Context) -> Result<()> {
+        let anchors = glyph
+            .anchors
+            .iter()
+            .map(|a| ("anchor", &a.name, &a.invalid_color));
+        let guidelines = glyph
+            .guidelines
+            .iter()
+            .map(|g| ("guideline", &g.name, &g.invalid_color));
+        let images = glyph
+            .image
+            .iter()
+            .map(|i| ("image", &None, &i.invalid_color));
+        for (kind, name, color) in anchors.chain(guidelines).chain(images) {
+            if let Some(color) = color {
+                let name = name.as_deref().map(|n| format!(" {n}")).unwrap_or_default();
+                ctx.report(format!("{kind}{name} has an invalid color '{color}'"));
+            }
+        }
+        if let Some(color) = glyph.invalid_mark_color() {
+            ctx.report(invalid_color_message("glyph", "mark color", color));
+        }
+        Ok(())
+    }
+
+    fn check_font(&self, font: &Rc<Font>, ctx: &mut Context) -> Result<()> {
+        // An unreadable layerinfo.plist is not a color problem, so it is left to other checks.
+        if let Ok(LayerInfo {
+            invalid_color: Some(color),
+            ..
+        }) = font.layer_info()
+        {
+            ctx.report(invalid_color_message("layer", "color", &color));
+        }
+        Ok(())
+    }
+}
+
+fn invalid_color_message(owner: &str, kind: &str, color: &Value) -> String {
+    match color {
+        Value::String(color) => format!("{owner} has an invalid {kind} '{color}'"),
+        _ => format!("{owner} has a {kind} that is not a string"),
+    }
+}
+
+fn is_open(contour: &Contour) -> bool {
+    contour
+        .points
+        .first()
+        .is_some_and(|point| point.point_type == Some(PointType::Move))
+}
+
+fn is_integral(value: Option<f64>) -> bool {
+    value.is_none_or(|value| value.fract() == 0.0)
+}
+
+/// Returns `true` for whitespace and invisible format characters, which have no outline.
+fn is_blank(unicode: u32) -> bool {
+    char::from_u32(unicode).is_some_and(char::is_whitespace)
+        || matches!(
+            unicode,
+            0x00AD
+                | 0x034F
+                | 0x061C
+                | 0x180B..=0x180F
+                | 0x200B..=0x200F
+                | 0x202A..=0x202E
+                | 0x2060..=0x2064
+                | 0x2066..=0x206F
+                | 0xFE00..=0xFE0F
+                | 0xFEFF
+                | 0xE0100..=0xE01EF
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::glif::{GlifParser, ParseOptions};
+    use crate::lint::{Diagnostic, Linter, RuleConfig};
+    use crate::paths::UfoRelativePath;
+    use crate::test_utils::MockProvider;
+
+    fn lint(linter: &Linter, glif: &str) -> Vec<Diagnostic> {
+        let path = UfoRelativePath::GlifFile("a.glif".to_string()).to_pathbuf();
+        let provider = MockProvider::new();
+        provider.with_file(&path, glif.as_bytes());
+        let data = GlifParser::with_options(provider, ParseOptions::default())
+            .unwrap()
+            .parse_glif("a.glif")
+            .unwrap();
+        linter.lint_glyph(&data, "a", &[]).unwrap()
+    }
+
+    fn rules(diagnostics: &[Diagnostic]) -> Vec<(&str, Option<usize>, Option<usize>)> {
+        diagnostics
+            .iter()
+            .map(|d| (d.rule.as_str(), d.contour, d.point))
+            .collect()
+    }
+
+    #[test]
+    fn test_contour_rules() {
+        let glif = r#"<glyph name="a" format="2"><outline>
+            <contour><point x="0" y="0" type="move"/><point x="10" y="0" type="line"/></contour>
+            <contour><point x="0" y="0" type="line"/></contour>
+            <contour>
+                <point x="0" y="0" type="line"/>
+                <point x="0" y="0" type="line"/>
+                <point x="10" y="10.5" type="line"/>
+                <point x="0" y="0" type="line"/>
+            </contour>
+        </outline></glyph>"#;
+        let diagnostics = lint(&Linter::with_default_rules(), glif);
+        assert_eq!(
+            rules(&diagnostics),
+            [
+                ("open-contour", Some(0), None),
+                ("single-point-contour", Some(1), Some(0)),
+                ("duplicate-point", Some(2), Some(0)),
+                ("duplicate-point", Some(2), Some(1)),
+                ("non-integer-coordinate", Some(2), Some(2)),
+            ]
+        );
+        assert_eq!(diagnostics[4].severity, Severity::Info);
+    }
+
+    #[test]
+    fn test_contour_rules_skip_format_1_anchors() {
+        let glif = r#"<glyph name="a" format="1"><outline>
+            <contour><point x="100" y="700" type="move" name="top"/></contour>
+            <contour><point x="0" y="0" type="move"/></contour>
+        </outline></glyph>"#;
+        assert_eq!(
+            rules(&lint(&Linter::with_default_rules(), glif)),
+            [
+                ("open-contour", Some(1), None),
+                ("single-point-contour", Some(1), Some(0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_glyph_rules() {
+        let linter = Linter::with_default_rules();
+        let glif = r#"<glyph name="a" format="2">
+            <unicode hex="0061"/>
+            <anchor x="100" y="0" name="bottom"/>
+            <anchor x="100" y="0" name="ogonek"/>
+            <anchor x="101" y="700" name="top"/>
+            <anchor x="100" y="700" name="top_1"/>
+        </glyph>"#;
+        let diagnostics = lint(&linter, glif);
+        assert_eq!(
+            rules(&diagnostics),
+            [
+                ("empty-glyph-with-unicode", None, None),
+                ("overlapping-anchors", None, None)
+            ]
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "glyph is empty but mapped to U+0061"
+        );
+
+        let linter = linter.with_config(
+            "overlapping-anchors",
+            RuleConfig::default().with_option("tolerance", 1.0),
+        );
+        assert_eq!(lint(&linter, glif).len(), 3);
+
+        let colors = r#"<glyph name="a" format="2">
+            <anchor x="0" y="0" name="top" color="red"/>
+            <guideline y="0" color="1,0,0"/>
+            <lib><dict><key>public.markColor</key><string>1,0</string></dict></lib>
+        </glyph>"#;
+        let messages = lint(&linter, colors)
+            .into_iter()
+            .map(|d| d.message)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            messages,
+            [
+                "anchor top has an invalid color 'red'",
+                "guideline has an invalid color '1,0,0'",
+                "glyph has an invalid mark color '1,0'"
+            ]
+        );
+
+        let future = r#"<glyph name="a" format="2" formatMinor="5"/>"#;
+        let diagnostics = lint(&linter, future);
+        assert_eq!(
+            rules(&diagnostics),
+            [("unsupported-format-version", None, None)]
+        );
+        assert_eq!(
+            diagnostics[0].message,
+            "GLIF format version 2.5 is not supported; data it adds is ignored"
+        );
+
+        let space =
+            r#"<glyph name="a" format="2"><unicode hex="0020"/><unicode hex="00A0"/></glyph>"#;
+        assert!(lint(&linter, space).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_layer_color() {
+        let provider = MockProvider::new();
+        provider
+            .with_file(
+                &UfoRelativePath::Contents.to_pathbuf(),
+                b"<plist version='1.0'><dict/></plist>",
+            )
+            .with_file(
+                &UfoRelativePath::LayerInfo.to_pathbuf(),
+                b"<plist version='1.0'><dict><key>color</key><integer>1</integer></dict></plist>",
+            );
+        let font = Font::new(provider).unwrap();
+        let diagnostics = Linter::with_default_rules().lint(&font).unwrap();
+        assert_eq!(rules(&diagnostics), [("invalid-color", None, None)]);
+        assert_eq!(
+            diagnostics[0].message,
+            "layer has a color that is not a string"
+        );
+    }
+}
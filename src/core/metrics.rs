@@ -0,0 +1,88 @@
+//! Sidebearing computation from glyph bounds and advances.
+
+use serde::Serialize;
+
+use crate::glif::Advance;
+use crate::path::{Bounds, Position, horizontal_crossings};
+
+/// The distances between a glyph's outline and the edges of its advance box.
+///
+/// Horizontal sidebearings are measured from `x = 0` and from the advance width. Vertical
+/// sidebearings are measured from the vertical origin and from the vertical origin minus the
+/// advance height; without a `public.verticalOrigin` the origin is taken to be the advance
+/// height, as in defcon.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sidebearings {
+    pub left: f64,
+    pub right: f64,
+    pub top: f64,
+    pub bottom: f64,
+}
+
+impl Sidebearings {
+    /// Computes the sidebearings of an outline with the given bounds.
+    pub fn new(bounds: &Bounds, advance: &Advance, vertical_origin: Option<f64>) -> Self {
+        let height = advance.height_or_default();
+        let origin = vertical_origin.unwrap_or(height);
+        Self {
+            left: bounds.x_min,
+            right: advance.width_or_default() - bounds.x_max,
+            top: origin - bounds.y_max,
+            bottom: bounds.y_min - (origin - height),
+        }
+    }
+}
+
+/// Returns the left and right sidebearings of the outline along the horizontal line at `y`,
+/// or `None` if the line does not touch the outline.
+///
+/// `polylines` is the flattened outline, see [`crate::path::flatten`]. This is the "beam"
+/// measurement used by spacing tools: the distance from `x = 0` to the first outline crossing,
+/// and from the last crossing to the advance width.
+pub fn sidebearings_at(
+    polylines: &[Vec<Position>],
+    advance_width: f64,
+    y: f64,
+) -> Option<(f64, f64)> {
+    let crossings = horizontal_crossings(polylines, y);
+    let min = crossings.iter().copied().reduce(f64::min)?;
+    let max = crossings.iter().copied().reduce(f64::max)?;
+    Some((min, advance_width - max))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sidebearings() {
+        let bounds = Bounds {
+            x_min: 50.0,
+            y_min: -10.0,
+            x_max: 450.0,
+            y_max: 700.0,
+        };
+        let advance = Advance {
+            width: Some(500.0),
+            height: Some(1000.0),
+        };
+        let sidebearings = Sidebearings::new(&bounds, &advance, Some(880.0));
+        assert_eq!(
+            sidebearings,
+            Sidebearings {
+                left: 50.0,
+                right: 50.0,
+                top: 180.0,
+                bottom: 110.0
+            }
+        );
+        assert_eq!(Sidebearings::new(&bounds, &advance, None).top, 300.0);
+
+        let triangle = vec![(0.0, 0.0), (100.0, 0.0), (50.0, 100.0), (0.0, 0.0)];
+        let polylines = [triangle];
+        assert_eq!(sidebearings_at(&polylines, 120.0, 50.0), Some((25.0, 45.0)));
+        assert_eq!(sidebearings_at(&polylines, 120.0, 0.0), Some((0.0, 20.0)));
+        assert_eq!(sidebearings_at(&polylines, 120.0, 101.0), None);
+    }
+}
@@ -1,8 +1,23 @@
+pub mod cache;
+pub mod cmap;
+pub mod color;
+pub mod components;
 pub mod constants;
+pub mod diff;
 pub mod error;
 pub mod font;
+pub mod fontlib;
 pub mod gleaner;
 pub mod glif;
+pub mod hash;
+pub mod lint;
+pub mod metrics;
+pub mod path;
 pub mod paths;
 pub mod plist;
 pub mod provider;
+pub mod raster;
+pub mod svg;
+pub mod transform;
+#[cfg(feature = "watch")]
+pub mod watch;
@@ -0,0 +1,485 @@
+//! Conversion of GLIF contours into drawable path segments, and geometry on those paths.
+//!
+//! GLIF contours are lists of points whose types describe the segments between them; a
+//! [`PathElement`] list spells those segments out, resolving closed contours, implied on-curve
+//! points of quadratic curves and cubic curves with more than two off-curve points.
+
+use serde::Serialize;
+
+use crate::glif::{Contour, Point, PointType};
+
+/// A point in glyph coordinates.
+pub type Position = (f64, f64);
+
+/// A drawing command, in the style of a segment pen.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PathElement {
+    /// Starts a new subpath at the given point.
+    Move(Position),
+    /// A straight line to the given point.
+    Line(Position),
+    /// A quadratic curve through a control point to the end point.
+    Quad(Position, Position),
+    /// A cubic curve through two control points to the end point.
+    Cubic(Position, Position, Position),
+    /// Closes the current subpath with a line back to its start.
+    Close,
+}
+
+/// An axis-aligned bounding box.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bounds {
+    pub x_min: f64,
+    pub y_min: f64,
+    pub x_max: f64,
+    pub y_max: f64,
+}
+
+impl Bounds {
+    /// Returns the bounds of a single point.
+    pub fn at(point: Position) -> Self {
+        Self {
+            x_min: point.0,
+            y_min: point.1,
+            x_max: point.0,
+            y_max: point.1,
+        }
+    }
+
+    /// Extends the bounds to include `point`.
+    pub fn include(&mut self, point: Position) {
+        self.x_min = self.x_min.min(point.0);
+        self.y_min = self.y_min.min(point.1);
+        self.x_max = self.x_max.max(point.0);
+        self.y_max = self.y_max.max(point.1);
+    }
+
+    /// Returns the bounds covering both `self` and `other`.
+    pub fn union(&self, other: &Bounds) -> Bounds {
+        let mut bounds = *self;
+        bounds.include((other.x_min, other.y_min));
+        bounds.include((other.x_max, other.y_max));
+        bounds
+    }
+
+    /// Returns the width of the bounds.
+    pub fn width(&self) -> f64 {
+        self.x_max - self.x_min
+    }
+
+    /// Returns the height of the bounds.
+    pub fn height(&self) -> f64 {
+        self.y_max - self.y_min
+    }
+}
+
+/// Converts contours into path elements, one subpath per contour.
+///
+/// Open contours (starting with a `move` point) are left open; all others end with
+/// [`PathElement::Close`]. Contours without points are skipped.
+pub fn to_path(contours: &[Contour]) -> Vec<PathElement> {
+    let mut path = Vec::new();
+    for contour in contours {
+        contour_to_path(contour, &mut path);
+    }
+    path
+}
+
+/// Returns the exact bounds of the path, including the extrema of its curves, or `None` if the
+/// path is empty.
+pub fn bounds(path: &[PathElement]) -> Option<Bounds> {
+    let mut bounds: Option<Bounds> = None;
+    let mut include = |point| match &mut bounds {
+        Some(bounds) => bounds.include(point),
+        None => bounds = Some(Bounds::at(point)),
+    };
+    let mut current = (0.0, 0.0);
+    for element in path {
+        match *element {
+            PathElement::Move(end) | PathElement::Line(end) => {
+                include(end);
+                current = end;
+            }
+            PathElement::Quad(control, end) => {
+                include(end);
+                for t in quad_extrema(current, control, end) {
+                    include(quad_at(current, control, end, t));
+                }
+                current = end;
+            }
+            PathElement::Cubic(c1, c2, end) => {
+                include(end);
+                for t in cubic_extrema(current, c1, c2, end) {
+                    include(cubic_at(current, c1, c2, end, t));
+                }
+                current = end;
+            }
+            PathElement::Close => {}
+        }
+    }
+    bounds
+}
+
+/// Approximates the path with straight lines, returning one polyline per subpath.
+///
+/// Curves are split into enough lines that no line strays more than about `tolerance` units
+/// from the curve. Closed subpaths end with their start point.
+pub fn flatten(path: &[PathElement], tolerance: f64) -> Vec<Vec<Position>> {
+    let mut polylines = Vec::new();
+    let mut current = Vec::<Position>::new();
+    for element in path {
+        let start = current.last().copied().unwrap_or_default();
+        match *element {
+            PathElement::Move(point) => {
+                if !current.is_empty() {
+                    polylines.push(std::mem::take(&mut current));
+                }
+                current.push(point);
+            }
+            PathElement::Line(point) => current.push(point),
+            PathElement::Quad(control, end) => {
+                let n = subdivisions(0.25 * deviation(start, control, control, end), tolerance);
+                current.extend((1..=n).map(|i| quad_at(start, control, end, i as f64 / n as f64)));
+            }
+            PathElement::Cubic(c1, c2, end) => {
+                let n = subdivisions(0.75 * deviation(start, c1, c2, end), tolerance);
+                current.extend((1..=n).map(|i| cubic_at(start, c1, c2, end, i as f64 / n as f64)));
+            }
+            PathElement::Close => {
+                if let Some(&first) = current.first() {
+                    current.push(first);
+                    polylines.push(std::mem::take(&mut current));
+                }
+            }
+        }
+    }
+    if !current.is_empty() {
+        polylines.push(current);
+    }
+    polylines
+}
+
+/// Returns the x coordinates at which the polylines cross or touch the horizontal line at `y`.
+///
+/// Each polyline is treated as closed. The coordinates are not sorted and may repeat where the
+/// line passes through a vertex.
+pub fn horizontal_crossings(polylines: &[Vec<Position>], y: f64) -> Vec<f64> {
+    let mut crossings = Vec::new();
+    for polyline in polylines {
+        let closing = polyline.last().zip(polyline.first());
+        let edges = polyline.windows(2).map(|edge| (edge[0], edge[1]));
+        for (a, b) in edges.chain(closing.map(|(&a, &b)| (a, b))) {
+            if (a.1 < y && b.1 < y) || (a.1 > y && b.1 > y) {
+                continue;
+            }
+            if a.1 == b.1 {
+                crossings.extend([a.0, b.0]);
+            } else {
+                crossings.push(a.0 + (y - a.1) / (b.1 - a.1) * (b.0 - a.0));
+            }
+        }
+    }
+    crossings
+}
+
+/// Formats a coordinate rounded to three decimal places, without a trailing `.0` or a negative
+/// zero.
+pub(crate) fn format_number(value: f64) -> String {
+    let rounded = (value * 1000.0).round() / 1000.0;
+    if rounded == 0.0 {
+        return "0".to_owned();
+    }
+    rounded.to_string()
+}
+
+fn contour_to_path(contour: &Contour, path: &mut Vec<PathElement>) {
+    let points = &contour.points;
+    let Some(first) = points.first() else {
+        return;
+    };
+    let mut off_curves = Vec::new();
+    if first.point_type == Some(PointType::Move) {
+        path.push(PathElement::Move(position(first)));
+        for point in &points[1..] {
+            segment_to(point, &mut off_curves, path);
+        }
+        return;
+    }
+
+    // A closed contour starts at its first on-curve point and wraps around to it.
+    let Some(start) = points.iter().position(|p| !is_off_curve(p)) else {
+        // A quadratic contour without on-curve points starts at the implied point between its
+        // last and first off-curve points.
+        let off_curves = points.iter().map(position).collect::<Vec<_>>();
+        let start = midpoint(off_curves[off_curves.len() - 1], off_curves[0]);
+        path.push(PathElement::Move(start));
+        quad_spline(&off_curves, start, path);
+        path.push(PathElement::Close);
+        return;
+    };
+    path.push(PathElement::Move(position(&points[start])));
+    for point in points[start + 1..].iter().chain(&points[..=start]) {
+        segment_to(point, &mut off_curves, path);
+    }
+    path.push(PathElement::Close);
+}
+
+/// Appends the segment ending at `point`, or buffers `point` if it is an off-curve point.
+fn segment_to(point: &Point, off_curves: &mut Vec<Position>, path: &mut Vec<PathElement>) {
+    let end = position(point);
+    match point.point_type_or_default() {
+        PointType::OffCurve => {
+            off_curves.push(end);
+            return;
+        }
+        PointType::QCurve => quad_spline(off_curves, end, path),
+        PointType::Curve => match off_curves.len() {
+            0 => path.push(PathElement::Line(end)),
+            1 => path.push(PathElement::Quad(off_curves[0], end)),
+            2 => path.push(PathElement::Cubic(off_curves[0], off_curves[1], end)),
+            _ => super_bezier(off_curves, end, path),
+        },
+        PointType::Move | PointType::Line | PointType::Unrecognized(_) => {
+            path.push(PathElement::Line(end))
+        }
+    }
+    off_curves.clear();
+}
+
+/// Appends a TrueType-style quadratic spline, with implied on-curve points halfway between
+/// consecutive off-curve points.
+fn quad_spline(off_curves: &[Position], end: Position, path: &mut Vec<PathElement>) {
+    let Some((&last, rest)) = off_curves.split_last() else {
+        path.push(PathElement::Line(end));
+        return;
+    };
+    for (i, &control) in rest.iter().enumerate() {
+        path.push(PathElement::Quad(
+            control,
+            midpoint(control, off_curves[i + 1]),
+        ));
+    }
+    path.push(PathElement::Quad(last, end));
+}
+
+/// Splits a cubic curve with more than two off-curve points into cubic segments, using the
+/// same construction as `decomposeSuperBezierSegment` in fontTools.
+fn super_bezier(off_curves: &[Position], end: Position, path: &mut Vec<PathElement>) {
+    let n = off_curves.len();
+    let mut first = off_curves[0];
+    let mut second = None;
+    for i in 2..=n {
+        let divisions = i.min(3).min(n + 2 - i);
+        for j in 1..divisions {
+            let factor = j as f64 / divisions as f64;
+            let (a, b) = (off_curves[i - 2], off_curves[i - 1]);
+            let temp = (a.0 + factor * (b.0 - a.0), a.1 + factor * (b.1 - a.1));
+            match second.take() {
+                None => second = Some(temp),
+                Some(control) => {
+                    path.push(PathElement::Cubic(first, control, midpoint(control, temp)));
+                    first = temp;
+                }
+            }
+        }
+    }
+    path.push(PathElement::Cubic(first, off_curves[n - 1], end));
+}
+
+fn position(point: &Point) -> Position {
+    (point.x.unwrap_or_default(), point.y.unwrap_or_default())
+}
+
+fn is_off_curve(point: &Point) -> bool {
+    point.point_type_or_default() == PointType::OffCurve
+}
+
+fn midpoint(a: Position, b: Position) -> Position {
+    ((a.0 + b.0) / 2.0, (a.1 + b.1) / 2.0)
+}
+
+fn quad_at(p0: Position, p1: Position, p2: Position, t: f64) -> Position {
+    let mt = 1.0 - t;
+    let at = |a: f64, b: f64, c: f64| mt * mt * a + 2.0 * mt * t * b + t * t * c;
+    (at(p0.0, p1.0, p2.0), at(p0.1, p1.1, p2.1))
+}
+
+fn cubic_at(p0: Position, p1: Position, p2: Position, p3: Position, t: f64) -> Position {
+    let mt = 1.0 - t;
+    let at = |a: f64, b: f64, c: f64, d: f64| {
+        mt * mt * mt * a + 3.0 * mt * mt * t * b + 3.0 * mt * t * t * c + t * t * t * d
+    };
+    (at(p0.0, p1.0, p2.0, p3.0), at(p0.1, p1.1, p2.1, p3.1))
+}
+
+/// Returns the parameters in `(0, 1)` at which a quadratic curve has a horizontal or vertical
+/// tangent.
+fn quad_extrema(p0: Position, p1: Position, p2: Position) -> Vec<f64> {
+    [(p0.0, p1.0, p2.0), (p0.1, p1.1, p2.1)]
+        .into_iter()
+        .filter_map(|(a, b, c)| {
+            let denominator = a - 2.0 * b + c;
+            (denominator != 0.0).then(|| (a - b) / denominator)
+        })
+        .filter(|t| *t > 0.0 && *t < 1.0)
+        .collect()
+}
+
+/// Returns the parameters in `(0, 1)` at which a cubic curve has a horizontal or vertical
+/// tangent.
+fn cubic_extrema(p0: Position, p1: Position, p2: Position, p3: Position) -> Vec<f64> {
+    let mut extrema = Vec::new();
+    for (a, b, c, d) in [(p0.0, p1.0, p2.0, p3.0), (p0.1, p1.1, p2.1, p3.1)] {
+        // The derivative, divided by 3, is `qa t^2 + qb t + qc`.
+        let qa = -a + 3.0 * b - 3.0 * c + d;
+        let qb = 2.0 * (a - 2.0 * b + c);
+        let qc = b - a;
+        if qa.abs() < 1e-12 {
+            if qb != 0.0 {
+                extrema.push(-qc / qb);
+            }
+            continue;
+        }
+        let discriminant = qb * qb - 4.0 * qa * qc;
+        if discriminant < 0.0 {
+            continue;
+        }
+        let root = discriminant.sqrt();
+        extrema.extend([(-qb + root) / (2.0 * qa), (-qb - root) / (2.0 * qa)]);
+    }
+    extrema.retain(|t| *t > 0.0 && *t < 1.0);
+    extrema
+}
+
+/// Returns the largest second difference of the control polygon, which bounds how far the
+/// curve bends away from its chord.
+fn deviation(p0: Position, p1: Position, p2: Position, p3: Position) -> f64 {
+    let second = |a: Position, b: Position, c: Position| {
+        (a.0 - 2.0 * b.0 + c.0).hypot(a.1 - 2.0 * b.1 + c.1)
+    };
+    second(p0, p1, p2).max(second(p1, p2, p3))
+}
+
+/// Returns the number of lines needed to keep the flattening error of a curve with the given
+/// deviation within `tolerance`.
+fn subdivisions(deviation: f64, tolerance: f64) -> usize {
+    ((deviation / tolerance).sqrt().ceil() as usize).clamp(1, 1000)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn contour(points: &[(f64, f64, Option<PointType>)]) -> Contour {
+        Contour {
+            points: points
+                .iter()
+                .map(|(x, y, point_type)| Point {
+                    x: Some(*x),
+                    y: Some(*y),
+                    point_type: point_type.clone(),
+                    ..Default::default()
+                })
+                .collect(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_to_path() {
+        use PathElement::*;
+        use PointType::{Curve, Line as L, Move as M, QCurve};
+
+        // A closed cubic contour starting with off-curve points wraps around.
+        let closed = contour(&[
+            (0.0, 50.0, None),
+            (0.0, 100.0, Some(Curve)),
+            (100.0, 100.0, Some(L)),
+            (100.0, 0.0, Some(L)),
+            (0.0, 0.0, None),
+        ]);
+        assert_eq!(
+            to_path(&[closed]),
+            [
+                Move((0.0, 100.0)),
+                Line((100.0, 100.0)),
+                Line((100.0, 0.0)),
+                Cubic((0.0, 0.0), (0.0, 50.0), (0.0, 100.0)),
+                Close
+            ]
+        );
+
+        let open = contour(&[(0.0, 0.0, Some(M)), (10.0, 0.0, Some(L))]);
+        assert_eq!(to_path(&[open]), [Move((0.0, 0.0)), Line((10.0, 0.0))]);
+
+        let quadratic = contour(&[
+            (0.0, 0.0, Some(QCurve)),
+            (10.0, 0.0, None),
+            (20.0, 10.0, None),
+        ]);
+        assert_eq!(
+            to_path(&[quadratic]),
+            [
+                Move((0.0, 0.0)),
+                Quad((10.0, 0.0), (15.0, 5.0)),
+                Quad((20.0, 10.0), (0.0, 0.0)),
+                Close
+            ]
+        );
+
+        let off_curves_only = contour(&[(0.0, 0.0, None), (10.0, 0.0, None), (10.0, 10.0, None)]);
+        assert_eq!(to_path(&[off_curves_only])[0], Move((5.0, 5.0)));
+
+        let super_bezier = contour(&[
+            (0.0, 0.0, Some(L)),
+            (0.0, 30.0, None),
+            (30.0, 60.0, None),
+            (60.0, 60.0, None),
+            (90.0, 30.0, Some(Curve)),
+        ]);
+        let path = to_path(&[super_bezier]);
+        assert_eq!(
+            path[1..3],
+            [
+                Cubic((0.0, 30.0), (15.0, 45.0), (30.0, 52.5)),
+                Cubic((45.0, 60.0), (60.0, 60.0), (90.0, 30.0)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bounds_and_crossings() {
+        // A circle-like cubic contour whose extrema lie between its on-curve points.
+        let path = [
+            PathElement::Move((0.0, 0.0)),
+            PathElement::Cubic((0.0, 100.0), (100.0, 100.0), (100.0, 0.0)),
+            PathElement::Close,
+        ];
+        let bounds = bounds(&path).unwrap();
+        assert_eq!(
+            bounds,
+            Bounds {
+                x_min: 0.0,
+                y_min: 0.0,
+                x_max: 100.0,
+                y_max: 75.0
+            }
+        );
+        assert!(super::bounds(&[]).is_none());
+
+        let polylines = flatten(&path, 0.01);
+        assert_eq!(polylines.len(), 1);
+        assert_eq!(polylines[0].first(), polylines[0].last());
+        let crossings = horizontal_crossings(&polylines, 75.0 * 0.75);
+        let (min, max) = crossings
+            .iter()
+            .fold((f64::MAX, f64::MIN), |(min, max), &x| {
+                (min.min(x), max.max(x))
+            });
+        // y(t) = 225 t (1 - t) = 56.25 at t = 0.25 and t = 0.75.
+        assert!((min - 15.625).abs() < 0.05, "{min}");
+        assert!((max - 84.375).abs() < 0.05, "{max}");
+        assert!(horizontal_crossings(&polylines, 80.0).is_empty());
+    }
+}
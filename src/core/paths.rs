@@ -3,12 +3,22 @@
 //! Each variant corresponds to a well-known file or folder in the UFO structure.
 //! The `to_pathbuf` method converts the variant into a [`PathBuf`] relative to the
 //! root of the UFO package.
+//!
+//! It also implements the UFO 3 "user name to file name" algorithm used to derive
+//! `.glif` file names from glyph names, and checkers for existing `contents.plist` mappings.
+use std::collections::HashSet;
 use std::path::PathBuf;
 
+use indexmap::IndexMap;
+use serde::Serialize;
+
+use crate::error::{Error, ErrorKind, Result};
+
 /// Represents a file or directory path relative to the root of a UFO font package.
 pub enum UfoRelativePath {
     Contents,
     FontInfo,
+    Glyphs,
     Groups,
     Kerning,
     LayerContents,
@@ -24,15 +34,272 @@ impl UfoRelativePath {
         match self {
             UfoRelativePath::Contents => PathBuf::from("glyphs").join("contents.plist"),
             UfoRelativePath::FontInfo => PathBuf::from("fontinfo.plist"),
+            UfoRelativePath::Glyphs => PathBuf::from("glyphs"),
             UfoRelativePath::Groups => PathBuf::from("groups.plist"),
             UfoRelativePath::Kerning => PathBuf::from("kerning.plist"),
             UfoRelativePath::LayerContents => PathBuf::from("layercontents.plist"),
             UfoRelativePath::LayerInfo => PathBuf::from("glyphs").join("layerinfo.plist"),
             UfoRelativePath::Lib => PathBuf::from("lib.plist"),
             UfoRelativePath::MetaInfo => PathBuf::from("metainfo.plist"),
-            UfoRelativePath::GlifFile(name) => PathBuf::from("glyphs").join(format!("{}", name)),
+            UfoRelativePath::GlifFile(name) => PathBuf::from("glyphs").join(name),
+        }
+    }
+}
+
+/// The maximum length of a file name in bytes.
+pub const MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// The file extension appended to glyph file names.
+pub const GLIF_SUFFIX: &str = ".glif";
+
+/// The number of digits in the counter used to resolve file name clashes.
+const CLASH_COUNTER_WIDTH: usize = 15;
+
+/// Characters that are replaced by an underscore.
+///
+/// Control characters (`0x00..=0x1F` and `0x7F`) are illegal as well.
+const ILLEGAL_CHARACTERS: &[char] = &['"', '*', '+', '/', ':', '<', '>', '?', '[', '\\', ']', '|'];
+
+/// Dot-separated name parts that the UFO specification reserves, compared case-insensitively.
+///
+/// Drive names such as `a:` cannot occur because `:` is always escaped.
+const RESERVED_FILE_NAMES: &[&str] = &[
+    "con", "prn", "aux", "clock$", "nul", "com1", "com2", "com3", "com4", "lpt1", "lpt2", "lpt3",
+];
+
+/// Converts `user_name` to a file name following the UFO 3 "user name to file name" algorithm.
+///
+/// `existing` must contain the lowercased file names already in use; a name that clashes
+/// case-insensitively with one of them gets a 15-digit counter appended before the `suffix`.
+/// The result, including `prefix` and `suffix`, is at most [`MAX_FILE_NAME_LENGTH`] bytes long.
+pub fn user_name_to_file_name(
+    user_name: &str,
+    existing: &HashSet<String>,
+    prefix: &str,
+    suffix: &str,
+) -> Result<String> {
+    if user_name.is_empty() {
+        return Err(Error::new(ErrorKind::InvalidName(
+            "names must be at least one character long".to_string(),
+        )));
+    }
+
+    let mut filtered = String::with_capacity(user_name.len() * 2);
+    for (i, c) in user_name.chars().enumerate() {
+        let leading_dot = i == 0 && c == '.' && prefix.is_empty();
+        if leading_dot || matches!(c, '\0'..='\x1f' | '\x7f') || ILLEGAL_CHARACTERS.contains(&c) {
+            filtered.push('_');
+        } else if !c.to_lowercase().eq([c]) {
+            filtered.push(c);
+            filtered.push('_');
+        } else {
+            filtered.push(c);
+        }
+    }
+
+    let max_length = MAX_FILE_NAME_LENGTH.saturating_sub(prefix.len() + suffix.len());
+    let truncated = truncate(&filtered, max_length);
+    let name = truncated
+        .split('.')
+        .map(|part| {
+            if RESERVED_FILE_NAMES.contains(&part.to_lowercase().as_str()) {
+                format!("_{part}")
+            } else {
+                part.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(".");
+
+    let full_name = format!("{prefix}{name}{suffix}");
+    if !existing.contains(&full_name.to_lowercase()) {
+        return Ok(full_name);
+    }
+
+    let max_length = max_length.saturating_sub(CLASH_COUNTER_WIDTH);
+    let name = truncate(&name, max_length);
+    (1u64..)
+        .map(|counter| {
+            format!(
+                "{prefix}{name}{counter:0width$}{suffix}",
+                width = CLASH_COUNTER_WIDTH
+            )
+        })
+        .find(|full_name| !existing.contains(&full_name.to_lowercase()))
+        .ok_or_else(|| Error::new(ErrorKind::InvalidName(user_name.to_string())))
+}
+
+/// Converts `glyph_name` to a `.glif` file name, see [`user_name_to_file_name`].
+pub fn glyph_name_to_file_name(glyph_name: &str, existing: &HashSet<String>) -> Result<String> {
+    user_name_to_file_name(glyph_name, existing, "", GLIF_SUFFIX)
+}
+
+/// Truncates `s` to at most `max_length` bytes without splitting a character.
+fn truncate(s: &str, max_length: usize) -> &str {
+    if s.len() <= max_length {
+        return s;
+    }
+    let mut end = max_length;
+    while !s.is_char_boundary(end) {
+        end -= 1;
+    }
+    &s[..end]
+}
+
+/// A problem with a `contents.plist` mapping found by [`check_contents`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum FileNameIssue {
+    /// The file name is not one the UFO 3 algorithm produces for the glyph name.
+    ///
+    /// `expected` is the unclashed file name, or `None` if the glyph name cannot be converted.
+    Nonconforming {
+        glyph: String,
+        file_name: String,
+        expected: Option<String>,
+    },
+    /// Several glyphs use file names that differ only in case, so they collide on
+    /// case-insensitive file systems.
+    Clash {
+        file_name: String,
+        glyphs: Vec<String>,
+    },
+}
+
+/// Checks that the file names in a `contents.plist` mapping follow the UFO 3 algorithm.
+///
+/// A file name conforms if it equals the algorithm's output for its glyph name, either as is or
+/// with a clash counter appended. Issues are reported in `contents` order, nonconforming names
+/// first; an empty list means the mapping is valid.
+pub fn check_contents(contents: &IndexMap<String, String>) -> Vec<FileNameIssue> {
+    let mut issues = Vec::new();
+    for (glyph, file_name) in contents {
+        let expected = glyph_name_to_file_name(glyph, &HashSet::new()).ok();
+        let conforms = expected
+            .as_deref()
+            .is_some_and(|expected| is_clash_variant(file_name, expected));
+        if !conforms {
+            issues.push(FileNameIssue::Nonconforming {
+                glyph: glyph.clone(),
+                file_name: file_name.clone(),
+                expected,
+            });
         }
     }
+
+    let mut groups: IndexMap<String, Vec<(&String, &String)>> = IndexMap::new();
+    for (glyph, file_name) in contents {
+        groups
+            .entry(file_name.to_lowercase())
+            .or_default()
+            .push((glyph, file_name));
+    }
+    for group in groups.into_values().filter(|group| group.len() > 1) {
+        issues.push(FileNameIssue::Clash {
+            file_name: group[0].1.clone(),
+            glyphs: group.into_iter().map(|(glyph, _)| glyph.clone()).collect(),
+        });
+    }
+    issues
+}
+
+/// A mismatch between `contents.plist` and the glyph files of a layer.
+///
+/// Found by [`check_glyphs_dir`] and [`crate::font::Font::check_consistency`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(
+    tag = "kind",
+    rename_all = "camelCase",
+    rename_all_fields = "camelCase"
+)]
+pub enum ConsistencyIssue {
+    /// A `.glif` file is not listed in `contents.plist`.
+    OrphanFile { file_name: String },
+    /// A glyph is listed in `contents.plist`, but its file does not exist.
+    MissingFile { glyph: String, file_name: String },
+    /// The `name` attribute of a GLIF file differs from its `contents.plist` key.
+    NameMismatch {
+        glyph: String,
+        file_name: String,
+        name: String,
+    },
+    /// A GLIF file cannot be parsed.
+    Unreadable {
+        glyph: String,
+        file_name: String,
+        error: String,
+    },
+    /// Several files, listed or on disk, have names that differ only in case.
+    CaseClash { file_names: Vec<String> },
+}
+
+/// Cross-checks a `contents.plist` mapping against the file names found in the layer directory.
+///
+/// Reports orphan `.glif` files in `files` order, then missing files in `contents` order, then
+/// groups of file names that differ only in case.
+pub fn check_glyphs_dir(
+    contents: &IndexMap<String, String>,
+    files: &[String],
+) -> Vec<ConsistencyIssue> {
+    let listed: HashSet<&str> = contents.values().map(String::as_str).collect();
+    let present: HashSet<&str> = files.iter().map(String::as_str).collect();
+
+    let mut issues = Vec::new();
+    for file_name in files {
+        if file_name.ends_with(GLIF_SUFFIX) && !listed.contains(file_name.as_str()) {
+            issues.push(ConsistencyIssue::OrphanFile {
+                file_name: file_name.clone(),
+            });
+        }
+    }
+    for (glyph, file_name) in contents {
+        if !present.contains(file_name.as_str()) {
+            issues.push(ConsistencyIssue::MissingFile {
+                glyph: glyph.clone(),
+                file_name: file_name.clone(),
+            });
+        }
+    }
+
+    let mut groups: IndexMap<String, Vec<&str>> = IndexMap::new();
+    for file_name in contents.values().chain(files) {
+        let group = groups.entry(file_name.to_lowercase()).or_default();
+        if !group.contains(&file_name.as_str()) {
+            group.push(file_name);
+        }
+    }
+    for group in groups.into_values().filter(|group| group.len() > 1) {
+        issues.push(ConsistencyIssue::CaseClash {
+            file_names: group.into_iter().map(str::to_owned).collect(),
+        });
+    }
+    issues
+}
+
+/// Returns `true` if `file_name` is `expected`, or `expected` with a clash counter appended.
+fn is_clash_variant(file_name: &str, expected: &str) -> bool {
+    if file_name == expected {
+        return true;
+    }
+    let (Some(stem), Some(expected_stem)) = (
+        file_name.strip_suffix(GLIF_SUFFIX),
+        expected.strip_suffix(GLIF_SUFFIX),
+    ) else {
+        return false;
+    };
+    let Some(split) = stem.len().checked_sub(CLASH_COUNTER_WIDTH) else {
+        return false;
+    };
+    if !stem.is_char_boundary(split) {
+        return false;
+    }
+    let (name, counter) = stem.split_at(split);
+    let max_length = MAX_FILE_NAME_LENGTH - GLIF_SUFFIX.len() - CLASH_COUNTER_WIDTH;
+    counter.bytes().all(|b| b.is_ascii_digit()) && name == truncate(expected_stem, max_length)
 }
 
 #[cfg(test)]
@@ -78,4 +345,122 @@ mod tests {
         let path = UfoRelativePath::GlifFile("A.glif".to_string()).to_pathbuf();
         assert_eq!(path, Path::new("glyphs/A.glif"));
     }
+
+    #[test]
+    fn test_glyph_name_to_file_name() {
+        let cases = [
+            ("a", "a.glif"),
+            ("A", "A_.glif"),
+            ("AE", "A_E_.glif"),
+            ("Ae", "A_e.glif"),
+            ("aE", "aE_.glif"),
+            ("A.alt", "A_.alt.glif"),
+            ("A.aLt", "A_.aL_t.glif"),
+            ("T_H", "T__H_.glif"),
+            ("t_h", "t_h.glif"),
+            ("Aacute_V.swash", "A_acute_V_.swash.glif"),
+            (".notdef", "_notdef.glif"),
+            ("con", "_con.glif"),
+            ("CON", "C_O_N_.glif"),
+            ("alt.con", "alt._con.glif"),
+            ("com4", "_com4.glif"),
+            ("com5", "com5.glif"),
+            ("lpt4", "lpt4.glif"),
+            ("a*b/c|d", "a_b_c_d.glif"),
+            ("a\tb", "a_b.glif"),
+        ];
+        for (glyph_name, file_name) in cases {
+            let result = glyph_name_to_file_name(glyph_name, &HashSet::new()).unwrap();
+            assert_eq!(result, file_name, "glyph name {glyph_name:?}");
+        }
+    }
+
+    #[test]
+    fn test_glyph_name_to_file_name_clashes() {
+        let mut existing = HashSet::from(["a_.glif".to_string()]);
+        let name = glyph_name_to_file_name("A", &existing).unwrap();
+        assert_eq!(name, "A_000000000000001.glif");
+
+        existing.insert(name.to_lowercase());
+        let name = glyph_name_to_file_name("A", &existing).unwrap();
+        assert_eq!(name, "A_000000000000002.glif");
+    }
+
+    #[test]
+    fn test_glyph_name_to_file_name_length() {
+        let long = "a".repeat(300);
+        let name = glyph_name_to_file_name(&long, &HashSet::new()).unwrap();
+        assert_eq!(name.len(), MAX_FILE_NAME_LENGTH);
+
+        let existing = HashSet::from([name]);
+        let name = glyph_name_to_file_name(&long, &existing).unwrap();
+        assert_eq!(name.len(), MAX_FILE_NAME_LENGTH);
+        assert!(name.ends_with("000000000000001.glif"));
+
+        let err = glyph_name_to_file_name("", &HashSet::new()).unwrap_err();
+        assert!(matches!(err.kind(), ErrorKind::InvalidName(_)));
+    }
+
+    #[test]
+    fn test_check_contents() {
+        let contents: IndexMap<String, String> = [
+            ("a", "a.glif"),
+            ("A", "A_.glif"),
+            ("B", "b.glif"),
+            ("b", "B.glif"),
+            ("C", "C_000000000000001.glif"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let issues = check_contents(&contents);
+        assert_eq!(
+            issues,
+            vec![
+                FileNameIssue::Nonconforming {
+                    glyph: "B".into(),
+                    file_name: "b.glif".into(),
+                    expected: Some("B_.glif".into()),
+                },
+                FileNameIssue::Nonconforming {
+                    glyph: "b".into(),
+                    file_name: "B.glif".into(),
+                    expected: Some("b.glif".into()),
+                },
+                FileNameIssue::Clash {
+                    file_name: "b.glif".into(),
+                    glyphs: vec!["B".into(), "b".into()],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_check_glyphs_dir() {
+        let contents: IndexMap<String, String> = [("A", "A_.glif"), ("B", "B_.glif")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let files = ["A_.glif", "a_.glif", "contents.plist", "orphan.glif"].map(String::from);
+
+        assert_eq!(
+            check_glyphs_dir(&contents, &files),
+            vec![
+                ConsistencyIssue::OrphanFile {
+                    file_name: "a_.glif".into()
+                },
+                ConsistencyIssue::OrphanFile {
+                    file_name: "orphan.glif".into()
+                },
+                ConsistencyIssue::MissingFile {
+                    glyph: "B".into(),
+                    file_name: "B_.glif".into()
+                },
+                ConsistencyIssue::CaseClash {
+                    file_names: vec!["A_.glif".into(), "a_.glif".into()]
+                },
+            ]
+        );
+    }
 }
@@ -1,12 +1,15 @@
 //! Property list (`.plist`) file parser.
-use std::collections::HashMap;
 use std::io::BufReader;
 use std::io::Cursor;
 use std::path::Path;
 
+use indexmap::IndexMap;
 use plist::Value;
 
+use crate::color::Color;
+use crate::constants::ufo::layerinfo_key;
 use crate::error::{Error, ErrorKind, Result};
+use crate::fontlib::FontLib;
 use crate::paths::UfoRelativePath;
 use crate::provider::{Provider, ProviderHandle};
 
@@ -40,17 +43,18 @@ impl PlistParser {
 /// Reads `contents.plist` from the UFO package and converts it into a mapping
 /// from glyph names to `.glif` file names.
 ///
-/// Only entries where the value is a string are included; other types are ignored.
+/// Entries keep the order of the file. Only entries where the value is a string are included;
+/// other types are ignored.
 ///
 /// # Errors
 ///
 /// Returns an [`Error`] if `contents.plist` cannot be read, is not a [`plist::Dictionary`],
 /// or if parsing fails for other reasons.
-pub fn parse_contents(provider: ProviderHandle) -> Result<HashMap<String, String>> {
+pub fn parse_contents(provider: ProviderHandle) -> Result<IndexMap<String, String>> {
     let plist_parser = PlistParser::new(provider)?;
     let contents_path = UfoRelativePath::Contents.to_pathbuf();
     let plist_value = plist_parser.parse_plist(contents_path.as_ref())?;
-    let contents: HashMap<String, String> = match plist_value {
+    let contents: IndexMap<String, String> = match plist_value {
         Value::Dictionary(dict) => dict
             .into_iter()
             .filter_map(|(k, v)| {
@@ -70,6 +74,147 @@ pub fn parse_contents(provider: ProviderHandle) -> Result<HashMap<String, String
     Ok(contents)
 }
 
+/// Reads `contents.plist` like [`parse_contents`] and orders it by the `public.glyphOrder` key of
+/// `lib.plist`.
+///
+/// See [`order_contents`] for the ordering rules. A malformed `lib.plist` or `public.glyphOrder`
+/// is ignored.
+pub fn parse_ordered_contents(provider: ProviderHandle) -> Result<IndexMap<String, String>> {
+    let contents = parse_contents(provider.clone())?;
+    let glyph_order = parse_lib(provider)
+        .and_then(|lib| lib.glyph_order())
+        .unwrap_or_default();
+    Ok(order_contents(contents, &glyph_order))
+}
+
+/// Reads the font's `lib.plist` into a [`FontLib`].
+///
+/// As `lib.plist` is optional, a missing file yields an empty lib.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if `lib.plist` cannot be parsed or is not a [`plist::Dictionary`].
+pub fn parse_lib(provider: ProviderHandle) -> Result<FontLib> {
+    let lib_path = UfoRelativePath::Lib.to_pathbuf();
+    if !provider.exists(&lib_path) {
+        return Ok(FontLib::default());
+    }
+    let plist_parser = PlistParser::new(provider)?;
+    match plist_parser.parse_plist(lib_path.as_ref())? {
+        Value::Dictionary(dict) => Ok(FontLib::new(dict)),
+        _ => Err(Error::new(ErrorKind::Plist)
+            .with_context(|| "lib.plist is not a dictionary")
+            .with_path(lib_path.to_string_lossy())),
+    }
+}
+
+/// Reads the font's `fontinfo.plist` into a [`plist::Dictionary`].
+///
+/// As `fontinfo.plist` is optional, a missing file yields an empty dictionary.
+///
+/// # Errors
+///
+/// Returns an [`Error`] if `fontinfo.plist` cannot be parsed or is not a [`plist::Dictionary`].
+pub fn parse_fontinfo(provider: ProviderHandle) -> Result<plist::Dictionary> {
+    let fontinfo_path = UfoRelativePath::FontInfo.to_pathbuf();
+    if !provider.exists(&fontinfo_path) {
+        return Ok(plist::Dictionary::new());
+    }
+    let plist_parser = PlistParser::new(provider)?;
+    match plist_parser.parse_plist(fontinfo_path.as_ref())? {
+        Value::Dictionary(dict) => Ok(dict),
+        _ => Err(Error::new(ErrorKind::Plist)
+            .with_context(|| "fontinfo.plist is not a dictionary")
+            .with_path(fontinfo_path.to_string_lossy())),
+    }
+}
+
+/// Orders a contents map by `glyph_order`.
+///
+/// Glyphs listed in `glyph_order` come first, in that order. Names in `glyph_order` that are
+/// not in `contents` are ignored, as are repeated names. The remaining glyphs are appended in
+/// sorted order.
+pub fn order_contents(
+    mut contents: IndexMap<String, String>,
+    glyph_order: &[String],
+) -> IndexMap<String, String> {
+    let mut ordered = IndexMap::with_capacity(contents.len());
+    for name in glyph_order {
+        if let Some((name, file_name)) = contents.swap_remove_entry(name) {
+            ordered.insert(name, file_name);
+        }
+    }
+    contents.sort_unstable_keys();
+    ordered.extend(contents);
+    ordered
+}
+
+/// Serializes a glyph name to file name mapping as a `contents.plist` document.
+///
+/// Entries are written sorted by glyph name, so the output does not depend on glyph order.
+pub fn write_contents(contents: &IndexMap<String, String>) -> Result<Vec<u8>> {
+    let mut entries: Vec<_> = contents.iter().collect();
+    entries.sort_unstable();
+    let dict: plist::Dictionary = entries
+        .into_iter()
+        .map(|(name, file_name)| (name.clone(), Value::String(file_name.clone())))
+        .collect();
+
+    let mut buffer = Vec::new();
+    Value::Dictionary(dict).to_writer_xml(&mut buffer)?;
+    buffer.push(b'\n');
+    Ok(buffer)
+}
+
+/// Layer-level data stored in a glyph layer's `layerinfo.plist`.
+#[derive(Clone, Debug, Default)]
+pub struct LayerInfo {
+    pub color: Option<Color>,
+    /// The `color` value as it was read, if it is not a valid color string.
+    pub invalid_color: Option<Value>,
+    pub lib: Option<Value>,
+}
+
+/// Reads `layerinfo.plist` from the UFO package into a [`LayerInfo`].
+///
+/// As `layerinfo.plist` is optional, a missing file yields an empty [`LayerInfo`].
+///
+/// # Errors
+///
+/// Returns an [`Error`] if `layerinfo.plist` cannot be parsed or is not a [`plist::Dictionary`].
+/// A `color` that is not a valid color string is kept in [`LayerInfo::invalid_color`].
+pub fn parse_layerinfo(provider: ProviderHandle) -> Result<LayerInfo> {
+    let layerinfo_path = UfoRelativePath::LayerInfo.to_pathbuf();
+    if !provider.exists(&layerinfo_path) {
+        return Ok(LayerInfo::default());
+    }
+    let plist_parser = PlistParser::new(provider)?;
+    let mut dict = match plist_parser.parse_plist(layerinfo_path.as_ref())? {
+        Value::Dictionary(dict) => dict,
+        _ => {
+            return Err(Error::new(ErrorKind::Plist)
+                .with_context(|| "layerinfo.plist is not a dictionary")
+                .with_path(layerinfo_path.to_string_lossy()));
+        }
+    };
+
+    let (color, invalid_color) = match dict.remove(layerinfo_key::COLOR) {
+        Some(Value::String(s)) => match s.parse::<Color>() {
+            Ok(color) => (Some(color), None),
+            Err(_) => (None, Some(Value::String(s))),
+        },
+        Some(value) => (None, Some(value)),
+        None => (None, None),
+    };
+    let lib = dict.remove(layerinfo_key::LIB);
+
+    Ok(LayerInfo {
+        color,
+        invalid_color,
+        lib,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -169,4 +314,106 @@ mod tests {
         let err = parse_contents(provider).unwrap_err();
         assert_eq!(err.kind(), &crate::error::ErrorKind::Plist);
     }
+
+    #[test]
+    fn test_parse_layerinfo() {
+        let plist_bytes = br#"<?xml version='1.0'?><plist version='1.0'><dict>
+            <key>color</key><string>0,0.5,1,1</string>
+            <key>lib</key><dict><key>x</key><integer>1</integer></dict>
+        </dict></plist>"#;
+
+        let path = UfoRelativePath::LayerInfo.to_pathbuf();
+        let provider = MockProvider::new();
+        provider.with_file(&path, plist_bytes);
+        let info = parse_layerinfo(provider).unwrap();
+
+        assert_eq!(info.color.unwrap().to_tuple(), (0.0, 0.5, 1.0, 1.0));
+        assert!(info.lib.unwrap().as_dictionary().unwrap().contains_key("x"));
+    }
+
+    #[test]
+    fn test_parse_layerinfo_invalid_color() {
+        let plist_bytes = br#"<?xml version='1.0'?><plist version='1.0'><dict>
+            <key>color</key><string>0,0.5,1</string>
+        </dict></plist>"#;
+
+        let path = UfoRelativePath::LayerInfo.to_pathbuf();
+        let provider = MockProvider::new();
+        provider.with_file(&path, plist_bytes);
+        let info = parse_layerinfo(provider).unwrap();
+        assert!(info.color.is_none());
+        assert_eq!(info.invalid_color, Some(Value::from("0,0.5,1")));
+    }
+
+    #[test]
+    fn test_parse_fontinfo() {
+        let plist_bytes = br#"<?xml version='1.0'?><plist version='1.0'><dict>
+            <key>unitsPerEm</key><integer>2048</integer>
+        </dict></plist>"#;
+
+        let path = UfoRelativePath::FontInfo.to_pathbuf();
+        let provider = MockProvider::new();
+        provider.with_file(&path, plist_bytes);
+        let info = parse_fontinfo(provider).unwrap();
+        assert_eq!(info["unitsPerEm"].as_signed_integer(), Some(2048));
+
+        assert!(parse_fontinfo(MockProvider::new()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_parse_layerinfo_missing() {
+        let info = parse_layerinfo(MockProvider::new()).unwrap();
+        assert!(info.color.is_none());
+        assert!(info.lib.is_none());
+    }
+
+    #[test]
+    fn test_write_contents() {
+        let contents: IndexMap<String, String> = [("b", "b.glif"), ("a", "a.glif")]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        let data = write_contents(&contents).unwrap();
+
+        let provider = MockProvider::new();
+        provider.with_file(&UfoRelativePath::Contents.to_pathbuf(), &data);
+        let parsed = parse_contents(provider).unwrap();
+        assert_eq!(parsed.keys().collect::<Vec<_>>(), ["a", "b"]);
+        assert_eq!(parsed["b"], "b.glif");
+    }
+
+    #[test]
+    fn test_order_contents() {
+        let contents: IndexMap<String, String> = ["d", "b", "a", "c", "e"]
+            .iter()
+            .map(|n| (n.to_string(), format!("{}.glif", n)))
+            .collect();
+        let order = ["c", "missing", "a", "c"].map(String::from);
+
+        let ordered = order_contents(contents, &order);
+        let names: Vec<_> = ordered.keys().map(String::as_str).collect();
+        assert_eq!(names, ["c", "a", "b", "d", "e"]);
+        assert_eq!(ordered["a"], "a.glif");
+    }
+
+    #[test]
+    fn test_parse_ordered_contents() {
+        let contents = br#"<?xml version='1.0'?><plist version='1.0'><dict>
+            <key>b</key><string>b.glif</string>
+            <key>a</key><string>a.glif</string>
+            <key>z</key><string>z.glif</string>
+        </dict></plist>"#;
+        let lib = br#"<?xml version='1.0'?><plist version='1.0'><dict>
+            <key>public.glyphOrder</key><array><string>z</string></array>
+        </dict></plist>"#;
+
+        let provider = MockProvider::new();
+        provider.with_file(&UfoRelativePath::Contents.to_pathbuf(), contents);
+        let unordered = parse_ordered_contents(provider.clone()).unwrap();
+        assert_eq!(unordered.keys().collect::<Vec<_>>(), ["a", "b", "z"]);
+
+        provider.with_file(&UfoRelativePath::Lib.to_pathbuf(), lib);
+        let ordered = parse_ordered_contents(provider).unwrap();
+        assert_eq!(ordered.keys().collect::<Vec<_>>(), ["z", "a", "b"]);
+    }
 }
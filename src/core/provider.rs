@@ -1,10 +1,16 @@
-//! A file system abstraction and default implementation for reading files in a UFO.
+//! A file system abstraction and default implementations for reading and writing files in a UFO.
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::io::Read;
+use std::hash::{DefaultHasher, Hasher};
+use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::time::SystemTime;
 
-use crate::error::Result;
+use tempfile::NamedTempFile;
+
+use crate::error::{Error, ErrorKind, Result};
 
 /// Abstracts a read-only file system interface for accessing files relative to
 /// a font package root.
@@ -24,6 +30,127 @@ pub trait Provider: 'static {
     ///
     /// Returns an [`Error`] if the file cannot be opened or read.
     fn read(&self, rel_path: &Path) -> Result<Vec<u8>>;
+
+    /// Returns `true` if a file exists at `rel_path` relative to the provider's root.
+    ///
+    /// Used to detect optional UFO files. The default implementation attempts to [`read`] the
+    /// file, so implementors should override it with a cheaper check where possible.
+    ///
+    /// [`read`]: Provider::read
+    fn exists(&self, rel_path: &Path) -> bool {
+        self.read(rel_path).is_ok()
+    }
+
+    /// Returns the sorted names of the files directly inside the directory at `rel_path`.
+    ///
+    /// Used to find files that are not referenced by the UFO's plists. The default
+    /// implementation returns an error.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`] if the directory cannot be listed.
+    fn list_dir(&self, rel_path: &Path) -> Result<Vec<String>> {
+        Err(Error::new(ErrorKind::Io)
+            .with_context(|| "provider does not support directory listing")
+            .with_path(rel_path.to_string_lossy()))
+    }
+
+    /// Returns the modification time of the file at `rel_path`, if the provider can report it.
+    ///
+    /// Used to detect files changed outside a [`crate::font::Font`]. If this returns `None`,
+    /// changes are detected by hashing the file contents instead. The default implementation
+    /// returns `None`.
+    fn modified(&self, _rel_path: &Path) -> Option<SystemTime> {
+        None
+    }
+
+    /// Returns this provider as a [`WritableProvider`] if it supports writing.
+    ///
+    /// The default implementation returns `None`.
+    fn as_writable(&self) -> Option<&dyn WritableProvider> {
+        None
+    }
+}
+
+/// Identifies a version of a file, so that changes made outside the font can be detected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FileStamp {
+    /// The modification time reported by [`Provider::modified`].
+    Modified(SystemTime),
+    /// A hash of the file contents, used if the provider does not report modification times.
+    Hash(u64),
+}
+
+impl FileStamp {
+    /// Creates a stamp for the file at `rel_path`, whose contents are `data`.
+    ///
+    /// Use this after writing a file; when reading one, use [`FileStamp::read`].
+    pub fn new(provider: &dyn Provider, rel_path: &Path, data: &[u8]) -> Self {
+        match provider.modified(rel_path) {
+            Some(time) => Self::Modified(time),
+            None => Self::Hash(hash(data)),
+        }
+    }
+
+    /// Reads the file at `rel_path` and returns a stamp for the version read with its contents.
+    ///
+    /// The modification time is taken before reading, so a change made while the file is read
+    /// leaves the stamp stale instead of going unnoticed.
+    pub fn read(provider: &dyn Provider, rel_path: &Path) -> Result<(Self, Vec<u8>)> {
+        let modified = provider.modified(rel_path);
+        let data = provider.read(rel_path)?;
+        let stamp = match modified {
+            Some(time) => Self::Modified(time),
+            None => Self::Hash(hash(&data)),
+        };
+        Ok((stamp, data))
+    }
+
+    /// Creates a stamp for the current version of the file at `rel_path`, or returns `None` if
+    /// the file does not exist.
+    pub fn current(provider: &dyn Provider, rel_path: &Path) -> Result<Option<Self>> {
+        if !provider.exists(rel_path) {
+            return Ok(None);
+        }
+        if let Some(time) = provider.modified(rel_path) {
+            return Ok(Some(Self::Modified(time)));
+        }
+        Ok(Some(Self::Hash(hash(&provider.read(rel_path)?))))
+    }
+}
+
+fn hash(data: &[u8]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    hasher.write(data);
+    hasher.finish()
+}
+
+/// Extends [`Provider`] with the operations needed to save a UFO.
+///
+/// Implementors should also override [`Provider::as_writable`] to return `Some(self)`, so that a
+/// [`crate::font::Font`] holding a [`ProviderHandle`] can save through it.
+pub trait WritableProvider: Provider {
+    /// Writes `data` to the file at `rel_path`, replacing any existing file.
+    ///
+    /// Implementations should make the write atomic where possible, so that readers never see a
+    /// partially written file.
+    fn write(&self, rel_path: &Path, data: &[u8]) -> Result<()>;
+
+    /// Removes the file at `rel_path`.
+    fn remove(&self, rel_path: &Path) -> Result<()>;
+
+    /// Creates the directory at `rel_path` and any missing parents. Existing directories are
+    /// left as they are.
+    fn create_dir(&self, rel_path: &Path) -> Result<()>;
+
+    /// Moves the file at `from` to `to`, replacing any existing file at `to`.
+    ///
+    /// The default implementation reads, writes and removes the file, which is not atomic.
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let data = self.read(from)?;
+        self.write(to, &data)?;
+        self.remove(from)
+    }
 }
 
 pub type ProviderHandle = Rc<dyn Provider>;
@@ -36,6 +163,22 @@ impl Provider for ProviderHandle {
     fn read(&self, path: &Path) -> Result<Vec<u8>> {
         (**self).read(path) // deref the Box and delegate
     }
+
+    fn exists(&self, path: &Path) -> bool {
+        (**self).exists(path)
+    }
+
+    fn list_dir(&self, path: &Path) -> Result<Vec<String>> {
+        (**self).list_dir(path)
+    }
+
+    fn modified(&self, path: &Path) -> Option<SystemTime> {
+        (**self).modified(path)
+    }
+
+    fn as_writable(&self) -> Option<&dyn WritableProvider> {
+        (**self).as_writable()
+    }
 }
 
 /// A simple file-based [`Provider`] that reads files from a root directory.
@@ -72,6 +215,184 @@ impl Provider for FileProvider {
 
         Ok(buffer)
     }
+
+    /// Returns `true` if a file exists relative to the root directory.
+    fn exists(&self, rel_path: &Path) -> bool {
+        self.root.join(rel_path).is_file()
+    }
+
+    /// Lists the regular files in a directory relative to the root directory.
+    fn list_dir(&self, rel_path: &Path) -> Result<Vec<String>> {
+        let mut names = Vec::new();
+        for entry in fs::read_dir(self.root.join(rel_path))? {
+            let entry = entry?;
+            if entry.file_type()?.is_file() {
+                names.push(entry.file_name().to_string_lossy().into_owned());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    /// Returns the file's modification time from the file system.
+    fn modified(&self, rel_path: &Path) -> Option<SystemTime> {
+        fs::metadata(self.root.join(rel_path))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+    }
+
+    fn as_writable(&self) -> Option<&dyn WritableProvider> {
+        Some(self)
+    }
+}
+
+impl WritableProvider for FileProvider {
+    /// Writes to a temporary file in the target directory and renames it into place.
+    ///
+    /// An existing file keeps its permissions; new files get the permissions
+    /// [`fs::File::create`] would give them.
+    fn write(&self, rel_path: &Path, data: &[u8]) -> Result<()> {
+        let full_path = self.root.join(rel_path);
+        let dir = full_path.parent().unwrap_or(&self.root);
+        let permissions = fs::metadata(&full_path).map(|metadata| metadata.permissions());
+        let mut file = temp_file_in(dir)?;
+        file.write_all(data)?;
+        if let Ok(permissions) = permissions {
+            file.as_file().set_permissions(permissions)?;
+        }
+        file.persist(&full_path)
+            .map_err(|err| Error::from(err.error).with_path(full_path.to_string_lossy()))?;
+
+        Ok(())
+    }
+
+    fn remove(&self, rel_path: &Path) -> Result<()> {
+        Ok(fs::remove_file(self.root.join(rel_path))?)
+    }
+
+    fn create_dir(&self, rel_path: &Path) -> Result<()> {
+        Ok(fs::create_dir_all(self.root.join(rel_path))?)
+    }
+
+    /// Renames the file on disk, which is atomic on most file systems.
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        Ok(fs::rename(self.root.join(from), self.root.join(to))?)
+    }
+}
+
+/// Creates a temporary file in `dir` for [`FileProvider`] to write to.
+///
+/// Temporary files are only accessible by their owner by default, so on Unix the file is
+/// created with the mode [`fs::File::create`] uses, which the process umask then restricts.
+fn temp_file_in(dir: &Path) -> std::io::Result<NamedTempFile> {
+    let mut builder = tempfile::Builder::new();
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        builder.permissions(fs::Permissions::from_mode(0o666));
+    }
+    builder.tempfile_in(dir)
+}
+
+/// A [`WritableProvider`] that keeps all files in memory.
+///
+/// Useful for building UFOs in tests or saving to a buffer before writing them elsewhere.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryProvider {
+    root: PathBuf,
+    files: RefCell<BTreeMap<PathBuf, Vec<u8>>>,
+    dirs: RefCell<BTreeSet<PathBuf>>,
+}
+
+impl MemoryProvider {
+    /// Creates a new, empty provider.
+    pub fn new() -> Rc<Self> {
+        Rc::new(Self::default())
+    }
+
+    /// Returns the paths of all files in the provider, in sorted order.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.files.borrow().keys().cloned().collect()
+    }
+
+    /// Returns `true` if a directory was created at `rel_path` or contains a file.
+    pub fn is_dir(&self, rel_path: &Path) -> bool {
+        self.dirs.borrow().contains(rel_path)
+            || self
+                .files
+                .borrow()
+                .keys()
+                .any(|path| path.starts_with(rel_path))
+    }
+
+    fn not_found(rel_path: &Path) -> Error {
+        Error::new(ErrorKind::FileNotFound).with_path(rel_path.to_string_lossy())
+    }
+}
+
+impl Provider for MemoryProvider {
+    fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn read(&self, rel_path: &Path) -> Result<Vec<u8>> {
+        self.files
+            .borrow()
+            .get(rel_path)
+            .cloned()
+            .ok_or_else(|| Self::not_found(rel_path))
+    }
+
+    fn exists(&self, rel_path: &Path) -> bool {
+        self.files.borrow().contains_key(rel_path)
+    }
+
+    fn list_dir(&self, rel_path: &Path) -> Result<Vec<String>> {
+        if !self.is_dir(rel_path) {
+            return Err(Self::not_found(rel_path));
+        }
+        Ok(self
+            .files
+            .borrow()
+            .keys()
+            .filter(|path| path.parent() == Some(rel_path))
+            .filter_map(|path| path.file_name())
+            .map(|name| name.to_string_lossy().into_owned())
+            .collect())
+    }
+
+    fn as_writable(&self) -> Option<&dyn WritableProvider> {
+        Some(self)
+    }
+}
+
+impl WritableProvider for MemoryProvider {
+    fn write(&self, rel_path: &Path, data: &[u8]) -> Result<()> {
+        self.files
+            .borrow_mut()
+            .insert(rel_path.to_path_buf(), data.to_vec());
+        Ok(())
+    }
+
+    fn remove(&self, rel_path: &Path) -> Result<()> {
+        self.files
+            .borrow_mut()
+            .remove(rel_path)
+            .map(|_| ())
+            .ok_or_else(|| Self::not_found(rel_path))
+    }
+
+    fn create_dir(&self, rel_path: &Path) -> Result<()> {
+        self.dirs.borrow_mut().insert(rel_path.to_path_buf());
+        Ok(())
+    }
+
+    fn rename(&self, from: &Path, to: &Path) -> Result<()> {
+        let mut files = self.files.borrow_mut();
+        let data = files.remove(from).ok_or_else(|| Self::not_found(from))?;
+        files.insert(to.to_path_buf(), data);
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -95,6 +416,105 @@ mod tests {
         assert_eq!(bytes, b"Hello, world!");
     }
 
+    #[test]
+    fn test_fileprovider_exists() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("test.txt")).unwrap();
+
+        let provider = FileProvider::new(dir.path());
+        assert!(provider.exists(Path::new("test.txt")));
+        assert!(!provider.exists(Path::new("missing.txt")));
+    }
+
+    #[test]
+    fn test_fileprovider_write() {
+        let dir = tempdir().unwrap();
+        let provider = FileProvider::new(dir.path());
+        let writer = provider.as_writable().unwrap();
+
+        writer.create_dir(Path::new("glyphs")).unwrap();
+        writer.write(Path::new("glyphs/a.glif"), b"one").unwrap();
+        writer.write(Path::new("glyphs/a.glif"), b"two").unwrap();
+        assert_eq!(provider.read(Path::new("glyphs/a.glif")).unwrap(), b"two");
+
+        writer
+            .rename(Path::new("glyphs/a.glif"), Path::new("glyphs/b.glif"))
+            .unwrap();
+        assert!(!provider.exists(Path::new("glyphs/a.glif")));
+        writer.create_dir(Path::new("glyphs/sub")).unwrap();
+        assert_eq!(provider.list_dir(Path::new("glyphs")).unwrap(), ["b.glif"]);
+
+        writer.remove(Path::new("glyphs/b.glif")).unwrap();
+        assert!(!provider.exists(Path::new("glyphs/b.glif")));
+        assert!(provider.list_dir(Path::new("glyphs")).unwrap().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_fileprovider_write_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempdir().unwrap();
+        let provider = FileProvider::new(dir.path());
+        let writer = provider.as_writable().unwrap();
+        let mode = |name: &str| {
+            let metadata = fs::metadata(dir.path().join(name)).unwrap();
+            metadata.permissions().mode() & 0o777
+        };
+
+        writer.write(Path::new("new.plist"), b"one").unwrap();
+        fs::File::create(dir.path().join("created.plist")).unwrap();
+        assert_eq!(mode("new.plist"), mode("created.plist"));
+
+        let existing = dir.path().join("existing.plist");
+        fs::write(&existing, b"one").unwrap();
+        fs::set_permissions(&existing, fs::Permissions::from_mode(0o664)).unwrap();
+        writer.write(Path::new("existing.plist"), b"two").unwrap();
+        assert_eq!(mode("existing.plist"), 0o664);
+    }
+
+    #[test]
+    fn test_file_stamp() {
+        let provider = MemoryProvider::new();
+        let path = Path::new("a.glif");
+        assert_eq!(FileStamp::current(&*provider, path).unwrap(), None);
+
+        provider.write(path, b"one").unwrap();
+        let stamp = FileStamp::new(&*provider, path, b"one");
+        assert_eq!(FileStamp::current(&*provider, path).unwrap(), Some(stamp));
+
+        provider.write(path, b"two").unwrap();
+        assert_ne!(FileStamp::current(&*provider, path).unwrap(), Some(stamp));
+        let (stamp, data) = FileStamp::read(&*provider, path).unwrap();
+        assert_eq!(data, b"two");
+        assert_eq!(FileStamp::current(&*provider, path).unwrap(), Some(stamp));
+
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("a.glif")).unwrap();
+        let provider = FileProvider::new(dir.path());
+        let stamp = FileStamp::current(&*provider, path).unwrap();
+        assert!(matches!(stamp, Some(FileStamp::Modified(_))));
+    }
+
+    #[test]
+    fn test_memoryprovider() {
+        let provider = MemoryProvider::new();
+        provider.write(Path::new("glyphs/a.glif"), b"a").unwrap();
+        assert_eq!(provider.read(Path::new("glyphs/a.glif")).unwrap(), b"a");
+        assert!(provider.is_dir(Path::new("glyphs")));
+
+        provider
+            .rename(Path::new("glyphs/a.glif"), Path::new("glyphs/b.glif"))
+            .unwrap();
+        assert_eq!(provider.paths(), [Path::new("glyphs/b.glif")]);
+        assert_eq!(provider.list_dir(Path::new("glyphs")).unwrap(), ["b.glif"]);
+        assert!(provider.list_dir(Path::new("images")).is_err());
+
+        let err = provider.read(Path::new("glyphs/a.glif")).unwrap_err();
+        assert_eq!(err.kind(), &ErrorKind::FileNotFound);
+        assert!(provider.remove(Path::new("glyphs/a.glif")).is_err());
+    }
+
     #[test]
     fn test_fileprovider_file_not_found() {
         let dir = tempdir().unwrap();
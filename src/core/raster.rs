@@ -0,0 +1,246 @@
+//! Anti-aliased rasterization of glyph outlines.
+//!
+//! Outlines are filled with the nonzero winding rule by scanline sampling: each pixel row is
+//! crossed by 16 evenly spaced scanlines, and the filled spans along each scanline are
+//! measured exactly, so coverage is anti-aliased both horizontally and vertically.
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::path::Position;
+
+/// The number of scanlines sampled per pixel row.
+const SAMPLES: usize = 16;
+
+/// The largest em size in pixels accepted by [`crate::font::Glyph::rasterize`].
+pub const MAX_SIZE: f64 = 4096.0;
+
+/// The largest number of pixels in a bitmap made by [`crate::font::Glyph::rasterize`], enough
+/// for a glyph four ems wide at [`MAX_SIZE`].
+pub const MAX_PIXELS: u64 = 1 << 26;
+
+/// A grayscale image of pixel coverage, from 0 (blank) to 255 (fully covered).
+///
+/// Pixels are stored row by row, from the top left, so `data` can be viewed as a
+/// `height` × `width` array of unsigned bytes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Bitmap {
+    pub width: u32,
+    pub height: u32,
+    pub data: Vec<u8>,
+}
+
+impl Bitmap {
+    /// Returns the coverage of the pixel at column `x` and row `y`, or `None` if it is outside
+    /// the bitmap.
+    pub fn get(&self, x: u32, y: u32) -> Option<u8> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        self.data
+            .get(y as usize * self.width as usize + x as usize)
+            .copied()
+    }
+
+    /// Encodes the bitmap as an 8-bit grayscale PNG, drawn in black on white.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`ErrorKind::Io`] error if the bitmap is empty, as PNG images need at least
+    /// one pixel, or cannot be encoded.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let encode_error = |err: png::EncodingError| {
+            Error::new(ErrorKind::Io)
+                .with_context(|| "cannot encode PNG")
+                .with_cause(err)
+        };
+        let mut png = Vec::new();
+        let mut encoder = png::Encoder::new(&mut png, self.width, self.height);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let inverted = self.data.iter().map(|c| 255 - c).collect::<Vec<_>>();
+        encoder
+            .write_header()
+            .and_then(|mut writer| writer.write_image_data(&inverted))
+            .map_err(encode_error)?;
+        Ok(png)
+    }
+}
+
+/// Which part of the glyph a rasterized bitmap shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RasterFrame {
+    /// The advance width by one em, from the ascender down to the descender, so that bitmaps
+    /// of different glyphs and font revisions line up.
+    #[default]
+    Advance,
+    /// The outline's bounds, rounded out to whole pixels.
+    Bounds,
+}
+
+/// Configures glyph rasterization.
+///
+/// # Example
+///
+/// ```
+/// use ufo_gleaner::raster::{RasterFrame, RasterOptions};
+///
+/// let options = RasterOptions::default().with_size(128.0);
+/// assert_eq!(options.frame, RasterFrame::Advance);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RasterOptions {
+    /// The size of one em in pixels, at most [`MAX_SIZE`].
+    pub size: f64,
+    pub frame: RasterFrame,
+}
+
+impl Default for RasterOptions {
+    fn default() -> Self {
+        Self {
+            size: 64.0,
+            frame: RasterFrame::default(),
+        }
+    }
+}
+
+impl RasterOptions {
+    /// Sets the size of one em in pixels.
+    pub fn with_size(mut self, size: f64) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Sets which part of the glyph is rendered.
+    pub fn with_frame(mut self, frame: RasterFrame) -> Self {
+        self.frame = frame;
+        self
+    }
+}
+
+/// Fills `polylines`, given in pixel coordinates with y pointing down, into a bitmap of
+/// `width` × `height` pixels using the nonzero winding rule.
+///
+/// Each polyline is treated as closed, as with [`crate::path::flatten`] output.
+pub fn rasterize(polylines: &[Vec<Position>], width: u32, height: u32) -> Bitmap {
+    let edges = polylines
+        .iter()
+        .flat_map(|polyline| {
+            let next = polyline.iter().cycle().skip(1);
+            polyline.iter().zip(next).map(|(&a, &b)| (a, b))
+        })
+        .filter(|(a, b)| a.1 != b.1)
+        .collect::<Vec<_>>();
+
+    let (columns, weight) = (width as usize, 1.0 / SAMPLES as f32);
+    let mut coverage = vec![0.0_f32; columns * height as usize];
+    let mut crossings = Vec::<(f64, i32)>::new();
+    for (row, pixels) in coverage.chunks_exact_mut(columns.max(1)).enumerate() {
+        for sample in 0..SAMPLES {
+            let y = row as f64 + (sample as f64 + 0.5) / SAMPLES as f64;
+            crossings.clear();
+            for &(a, b) in &edges {
+                let (top, bottom, winding) = if a.1 < b.1 { (a, b, 1) } else { (b, a, -1) };
+                if y < top.1 || y >= bottom.1 {
+                    continue;
+                }
+                let x = top.0 + (y - top.1) / (bottom.1 - top.1) * (bottom.0 - top.0);
+                crossings.push((x, winding));
+            }
+            crossings.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+            let mut winding = 0;
+            for pair in crossings.windows(2) {
+                winding += pair[0].1;
+                if winding != 0 {
+                    add_span(pixels, pair[0].0, pair[1].0, weight);
+                }
+            }
+        }
+    }
+
+    Bitmap {
+        width,
+        height,
+        data: coverage
+            .into_iter()
+            .map(|c| (c.min(1.0) * 255.0).round() as u8)
+            .collect(),
+    }
+}
+
+/// Adds `weight` times the covered fraction of each pixel between `start` and `end`.
+fn add_span(pixels: &mut [f32], start: f64, end: f64, weight: f32) {
+    let (start, end) = (start.max(0.0), end.min(pixels.len() as f64));
+    if start >= end {
+        return;
+    }
+    let (first, last) = (start.floor() as usize, end.ceil() as usize - 1);
+    if first == last {
+        pixels[first] += (end - start) as f32 * weight;
+        return;
+    }
+    pixels[first] += (first as f64 + 1.0 - start) as f32 * weight;
+    for pixel in &mut pixels[first + 1..last] {
+        *pixel += weight;
+    }
+    pixels[last] += (end - last as f64) as f32 * weight;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x0: f64, y0: f64, x1: f64, y1: f64) -> Vec<Position> {
+        vec![(x0, y0), (x1, y0), (x1, y1), (x0, y1)]
+    }
+
+    #[test]
+    fn test_rasterize() {
+        let bitmap = rasterize(&[square(1.0, 1.0, 3.0, 3.0)], 4, 4);
+        assert_eq!(
+            bitmap.data,
+            [
+                0, 0, 0, 0, //
+                0, 255, 255, 0, //
+                0, 255, 255, 0, //
+                0, 0, 0, 0,
+            ]
+        );
+
+        // Half pixels are half covered, horizontally and vertically.
+        let bitmap = rasterize(&[square(0.5, 0.5, 2.0, 2.0)], 2, 2);
+        assert_eq!(bitmap.data, [64, 128, 128, 255]);
+        assert_eq!(bitmap.get(1, 1), Some(255));
+        assert_eq!(bitmap.get(2, 0), None);
+    }
+
+    #[test]
+    fn test_rasterize_winding() {
+        let outer = square(0.0, 0.0, 3.0, 3.0);
+        let same = square(1.0, 1.0, 2.0, 2.0);
+        let mut reversed = same.clone();
+        reversed.reverse();
+
+        // An inner contour in the same direction overlaps; a reversed one cuts a hole.
+        let filled = rasterize(&[outer.clone(), same], 3, 3);
+        assert!(filled.data.iter().all(|&c| c == 255));
+        let hole = rasterize(&[outer, reversed], 3, 3);
+        assert_eq!(hole.get(1, 1), Some(0));
+        assert_eq!(hole.get(0, 1), Some(255));
+    }
+
+    #[test]
+    fn test_to_png() {
+        let bitmap = rasterize(&[square(0.0, 0.0, 1.0, 1.0)], 2, 1);
+        let png = bitmap.to_png().unwrap();
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+
+        let mut reader = png::Decoder::new(std::io::Cursor::new(png))
+            .read_info()
+            .unwrap();
+        let mut pixels = vec![0; reader.output_buffer_size().unwrap()];
+        reader.next_frame(&mut pixels).unwrap();
+        assert_eq!(pixels, [0, 255]);
+
+        assert!(Bitmap::default().to_png().is_err());
+    }
+}
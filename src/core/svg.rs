@@ -0,0 +1,354 @@
+//! SVG export of glyph outlines.
+
+use std::fmt::Write;
+
+use quick_xml::escape::escape;
+
+use crate::glif::{Anchor, Guideline};
+use crate::path::{PathElement, Position, format_number};
+
+/// Converts path elements into the value of an SVG path's `d` attribute.
+///
+/// Coordinates are written as given, so the path is upside down in SVG's y-down coordinate
+/// system unless it is flipped, as [`document`] does. A line back to the start of a subpath just
+/// before it is closed is left to the `Z` command.
+pub fn path_data(path: &[PathElement]) -> String {
+    let mut d = String::new();
+    let mut start = (0.0, 0.0);
+    for (i, element) in path.iter().enumerate() {
+        let closes_next = matches!(path.get(i + 1), Some(PathElement::Close));
+        let _ = match *element {
+            PathElement::Move(p) => {
+                start = p;
+                write!(d, "M{} {}", format_number(p.0), format_number(p.1))
+            }
+            PathElement::Line(p) if closes_next && p == start => Ok(()),
+            PathElement::Line(p) => write!(d, "L{} {}", format_number(p.0), format_number(p.1)),
+            PathElement::Quad(c, p) => write!(
+                d,
+                "Q{} {} {} {}",
+                format_number(c.0),
+                format_number(c.1),
+                format_number(p.0),
+                format_number(p.1)
+            ),
+            PathElement::Cubic(c1, c2, p) => write!(
+                d,
+                "C{} {} {} {} {} {}",
+                format_number(c1.0),
+                format_number(c1.1),
+                format_number(c2.0),
+                format_number(c2.1),
+                format_number(p.0),
+                format_number(p.1)
+            ),
+            PathElement::Close => write!(d, "Z"),
+        };
+    }
+    d
+}
+
+/// How [`document`] arranges glyphs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SvgLayout {
+    /// Square cells of one em, `columns` per row, with each glyph centered in its cell.
+    Grid { columns: usize },
+    /// A single line of text, with each glyph placed at the advance of the previous one.
+    Line,
+}
+
+impl Default for SvgLayout {
+    fn default() -> Self {
+        Self::Grid { columns: 10 }
+    }
+}
+
+/// Configures [`document`].
+///
+/// # Example
+///
+/// ```
+/// use ufo_gleaner::svg::{SvgLayout, SvgOptions};
+///
+/// let options = SvgOptions::default()
+///     .with_layout(SvgLayout::Line)
+///     .with_anchors(true);
+/// assert!(options.anchors && !options.guidelines);
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct SvgOptions {
+    pub layout: SvgLayout,
+    /// Mark anchors with small circles.
+    pub anchors: bool,
+    /// Draw glyph guidelines across each glyph's cell.
+    pub guidelines: bool,
+}
+
+impl SvgOptions {
+    /// Sets how glyphs are arranged.
+    pub fn with_layout(mut self, layout: SvgLayout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Sets whether anchors are drawn.
+    pub fn with_anchors(mut self, anchors: bool) -> Self {
+        self.anchors = anchors;
+        self
+    }
+
+    /// Sets whether guidelines are drawn.
+    pub fn with_guidelines(mut self, guidelines: bool) -> Self {
+        self.guidelines = guidelines;
+        self
+    }
+}
+
+/// A glyph to be drawn by [`document`].
+#[derive(Clone, Debug, Default)]
+pub struct ProofGlyph {
+    pub name: String,
+    pub advance: f64,
+    pub path: Vec<PathElement>,
+    pub anchors: Vec<Anchor>,
+    pub guidelines: Vec<Guideline>,
+}
+
+/// Vertical metrics used to lay out a [`document`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ProofMetrics {
+    pub units_per_em: f64,
+    /// The descender, which is negative for glyphs that extend below the baseline.
+    pub descender: f64,
+}
+
+/// Creates an SVG document showing `glyphs`.
+///
+/// Each glyph is drawn in a cell one em high, with the descender at the bottom, and the y-axis
+/// flipped so glyphs appear upright. The `viewBox` is measured in font units. Outlines,
+/// anchors and guidelines have the classes `glyph`, `anchor` and `guideline`, styled by an
+/// embedded stylesheet that can be overridden.
+pub fn document(glyphs: &[ProofGlyph], metrics: ProofMetrics, options: SvgOptions) -> String {
+    let upm = metrics.units_per_em;
+    let ascender = upm + metrics.descender;
+
+    // The position of each cell's top left corner, its width, and the glyph offset within it.
+    let mut cells = Vec::with_capacity(glyphs.len());
+    let (mut width, mut height) = (0.0_f64, 0.0_f64);
+    let mut x = 0.0;
+    for (i, glyph) in glyphs.iter().enumerate() {
+        let cell = match options.layout {
+            SvgLayout::Grid { columns } => {
+                let columns = columns.max(1);
+                let (row, column) = (i / columns, i % columns);
+                let offset = (upm - glyph.advance) / 2.0;
+                ((column as f64 * upm, row as f64 * upm), upm, offset)
+            }
+            SvgLayout::Line => {
+                let cell = ((x, 0.0), glyph.advance, 0.0);
+                x += glyph.advance;
+                cell
+            }
+        };
+        width = width.max(cell.0.0 + cell.1);
+        height = height.max(cell.0.1 + upm);
+        cells.push(cell);
+    }
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {} {}">"#,
+        format_number(width),
+        format_number(height)
+    );
+    let _ = writeln!(
+        svg,
+        "<style>.glyph{{fill:#000}}.anchor{{fill:#e00}}\
+         .guideline{{stroke:#09f;stroke-width:1;vector-effect:non-scaling-stroke}}</style>"
+    );
+    for (glyph, ((left, top), cell_width, offset)) in glyphs.iter().zip(cells) {
+        let _ = writeln!(
+            svg,
+            r#"<g transform="translate({} {}) scale(1 -1)"><title>{}</title>"#,
+            format_number(left + offset),
+            format_number(top + ascender),
+            escape(&glyph.name)
+        );
+        let _ = writeln!(
+            svg,
+            r#"<path class="glyph" d="{}"/>"#,
+            path_data(&glyph.path)
+        );
+        if options.guidelines {
+            let cell = (
+                (-offset, metrics.descender),
+                (cell_width - offset, ascender),
+            );
+            for guideline in &glyph.guidelines {
+                if let Some((a, b)) = guideline_segment(guideline, cell) {
+                    let _ = writeln!(
+                        svg,
+                        r#"<line class="guideline" x1="{}" y1="{}" x2="{}" y2="{}"/>"#,
+                        format_number(a.0),
+                        format_number(a.1),
+                        format_number(b.0),
+                        format_number(b.1)
+                    );
+                }
+            }
+        }
+        if options.anchors {
+            for anchor in &glyph.anchors {
+                let _ = writeln!(
+                    svg,
+                    r#"<circle class="anchor" cx="{}" cy="{}" r="{}"><title>{}</title></circle>"#,
+                    format_number(anchor.x.unwrap_or_default()),
+                    format_number(anchor.y.unwrap_or_default()),
+                    format_number((upm / 100.0).max(1.0)),
+                    escape(anchor.name.as_deref().unwrap_or_default())
+                );
+            }
+        }
+        svg.push_str("</g>\n");
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Returns the part of a guideline inside the box with corners `min` and `max`.
+///
+/// A guideline with only `x` is vertical and one with only `y` is horizontal; otherwise it
+/// passes through `(x, y)` at `angle` degrees counterclockwise from the x-axis.
+fn guideline_segment(
+    guideline: &Guideline,
+    (min, max): (Position, Position),
+) -> Option<(Position, Position)> {
+    let (origin, angle) = match (guideline.x, guideline.y) {
+        (Some(x), None) => ((x, 0.0), 90.0),
+        (None, Some(y)) => ((0.0, y), 0.0),
+        (Some(x), Some(y)) => ((x, y), guideline.angle.unwrap_or_default()),
+        (None, None) => return None,
+    };
+    let direction = (angle.to_radians().cos(), angle.to_radians().sin());
+
+    // Clip the line `origin + t * direction` against each pair of box edges.
+    let (mut t_min, mut t_max) = (f64::NEG_INFINITY, f64::INFINITY);
+    for (o, d, low, high) in [
+        (origin.0, direction.0, min.0, max.0),
+        (origin.1, direction.1, min.1, max.1),
+    ] {
+        if d.abs() < 1e-12 {
+            if o < low || o > high {
+                return None;
+            }
+            continue;
+        }
+        let (t0, t1) = ((low - o) / d, (high - o) / d);
+        t_min = t_min.max(t0.min(t1));
+        t_max = t_max.min(t0.max(t1));
+    }
+    let at = |t: f64| (origin.0 + t * direction.0, origin.1 + t * direction.1);
+    (t_min < t_max).then(|| (at(t_min), at(t_max)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square() -> Vec<PathElement> {
+        vec![
+            PathElement::Move((0.0, 0.0)),
+            PathElement::Line((0.0, 100.0)),
+            PathElement::Quad((50.0, 150.0), (100.0, 100.0)),
+            PathElement::Cubic((100.0, 66.6666), (100.0, 33.3333), (100.0, 0.0)),
+            PathElement::Line((0.0, 0.0)),
+            PathElement::Close,
+        ]
+    }
+
+    #[test]
+    fn test_path_data() {
+        assert_eq!(
+            path_data(&square()),
+            "M0 0L0 100Q50 150 100 100C100 66.667 100 33.333 100 0Z"
+        );
+        let open = [
+            PathElement::Move((0.0, -0.0001)),
+            PathElement::Line((1.5, 0.0)),
+        ];
+        assert_eq!(path_data(&open), "M0 0L1.5 0");
+    }
+
+    #[test]
+    fn test_document() {
+        let glyphs = [
+            ProofGlyph {
+                name: "a&b".into(),
+                advance: 600.0,
+                path: square(),
+                anchors: vec![Anchor {
+                    x: Some(50.0),
+                    y: Some(100.0),
+                    name: Some("top".into()),
+                    ..Default::default()
+                }],
+                guidelines: vec![
+                    Guideline {
+                        y: Some(500.0),
+                        ..Default::default()
+                    },
+                    Guideline {
+                        x: Some(0.0),
+                        y: Some(0.0),
+                        angle: Some(45.0),
+                        ..Default::default()
+                    },
+                ],
+            },
+            ProofGlyph {
+                name: "b".into(),
+                advance: 400.0,
+                ..Default::default()
+            },
+        ];
+        let metrics = ProofMetrics {
+            units_per_em: 1000.0,
+            descender: -200.0,
+        };
+
+        let grid = document(&glyphs, metrics, SvgOptions::default());
+        assert!(
+            grid.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 2000 1000">"#)
+        );
+        assert!(
+            grid.contains(
+                r#"<g transform="translate(200 800) scale(1 -1)"><title>a&amp;b</title>"#
+            )
+        );
+        assert!(
+            grid.contains(r#"<g transform="translate(1300 800) scale(1 -1)"><title>b</title>"#)
+        );
+        assert!(!grid.contains("<circle"));
+
+        let options = SvgOptions::default()
+            .with_layout(SvgLayout::Line)
+            .with_anchors(true)
+            .with_guidelines(true);
+        let line = document(&glyphs, metrics, options);
+        assert!(line.contains(r#"viewBox="0 0 1000 1000""#));
+        assert!(line.contains(r#"<g transform="translate(600 800) scale(1 -1)"><title>b</title>"#));
+        assert!(line.contains(
+            r#"<circle class="anchor" cx="50" cy="100" r="10"><title>top</title></circle>"#
+        ));
+        assert!(line.contains(r#"<line class="guideline" x1="0" y1="500" x2="600" y2="500"/>"#));
+        assert!(line.contains(r#"<line class="guideline" x1="0" y1="0" x2="600" y2="600"/>"#));
+
+        let grid = document(
+            &glyphs,
+            metrics,
+            SvgOptions::default().with_layout(SvgLayout::Grid { columns: 1 }),
+        );
+        assert!(grid.contains(r#"viewBox="0 0 1000 2000""#));
+    }
+}
@@ -0,0 +1,323 @@
+//! Two-dimensional affine transformations.
+//!
+//! A [`Transform`] is a 2x3 matrix using the same field order as the transformation attributes
+//! of GLIF `<component>` and `<image>` elements:
+//!
+//! ```text
+//! | xx  yx  dx |
+//! | xy  yy  dy |
+//! ```
+//!
+//! A point `(x, y)` is mapped to `(xx * x + yx * y + dx, xy * x + yy * y + dy)`.
+
+use serde::Serialize;
+
+use crate::glif::{Component, Image};
+
+/// A 2x3 affine transformation matrix.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transform {
+    /// The x scale (`xScale`).
+    pub xx: f64,
+    /// The xy scale (`xyScale`).
+    pub xy: f64,
+    /// The yx scale (`yxScale`).
+    pub yx: f64,
+    /// The y scale (`yScale`).
+    pub yy: f64,
+    /// The x offset (`xOffset`).
+    pub dx: f64,
+    /// The y offset (`yOffset`).
+    pub dy: f64,
+}
+
+/// The decomposition of a [`Transform`] into translation, rotation, scale and skew.
+///
+/// Angles are given in degrees. Applying the parts in the order skew, scale, rotation and
+/// translation reproduces the original transform (see [`Decomposed::to_transform`]).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Decomposed {
+    pub translate_x: f64,
+    pub translate_y: f64,
+    pub rotation: f64,
+    pub scale_x: f64,
+    pub scale_y: f64,
+    pub skew_x: f64,
+    pub skew_y: f64,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Self::IDENTITY
+    }
+}
+
+impl Transform {
+    /// The identity transformation.
+    pub const IDENTITY: Self = Self::new(1.0, 0.0, 0.0, 1.0, 0.0, 0.0);
+
+    /// Creates a new transform from its six matrix components.
+    pub const fn new(xx: f64, xy: f64, yx: f64, yy: f64, dx: f64, dy: f64) -> Self {
+        Self {
+            xx,
+            xy,
+            yx,
+            yy,
+            dx,
+            dy,
+        }
+    }
+
+    /// Creates a translation by `(dx, dy)`.
+    pub const fn translate(dx: f64, dy: f64) -> Self {
+        Self::new(1.0, 0.0, 0.0, 1.0, dx, dy)
+    }
+
+    /// Creates a scale by `sx` horizontally and `sy` vertically.
+    pub const fn scale(sx: f64, sy: f64) -> Self {
+        Self::new(sx, 0.0, 0.0, sy, 0.0, 0.0)
+    }
+
+    /// Creates a counter-clockwise rotation by `degrees`.
+    pub fn rotate(degrees: f64) -> Self {
+        let (sin, cos) = degrees.to_radians().sin_cos();
+        Self::new(cos, sin, -sin, cos, 0.0, 0.0)
+    }
+
+    /// Creates a skew by `x_degrees` along the x axis and `y_degrees` along the y axis.
+    pub fn skew(x_degrees: f64, y_degrees: f64) -> Self {
+        let (tx, ty) = (x_degrees.to_radians().tan(), y_degrees.to_radians().tan());
+        Self::new(1.0, ty, tx, 1.0, 0.0, 0.0)
+    }
+
+    /// Returns the matrix components as an `(xx, xy, yx, yy, dx, dy)` tuple.
+    pub fn to_tuple(&self) -> (f64, f64, f64, f64, f64, f64) {
+        (self.xx, self.xy, self.yx, self.yy, self.dx, self.dy)
+    }
+
+    /// Returns the determinant of the linear part of the matrix.
+    pub fn determinant(&self) -> f64 {
+        self.xx * self.yy - self.xy * self.yx
+    }
+
+    /// Returns `true` if this is the identity transformation.
+    pub fn is_identity(&self) -> bool {
+        *self == Self::IDENTITY
+    }
+
+    /// Returns the composition `self ∘ other`: a transform that applies `other` first and then
+    /// `self`.
+    pub fn compose(&self, other: &Transform) -> Transform {
+        Transform {
+            xx: self.xx * other.xx + self.yx * other.xy,
+            xy: self.xy * other.xx + self.yy * other.xy,
+            yx: self.xx * other.yx + self.yx * other.yy,
+            yy: self.xy * other.yx + self.yy * other.yy,
+            dx: self.xx * other.dx + self.yx * other.dy + self.dx,
+            dy: self.xy * other.dx + self.yy * other.dy + self.dy,
+        }
+    }
+
+    /// Returns a transform that applies `self` first and then `other`.
+    pub fn then(&self, other: &Transform) -> Transform {
+        other.compose(self)
+    }
+
+    /// Returns the inverse transformation, or `None` if the matrix is degenerate.
+    pub fn invert(&self) -> Option<Transform> {
+        let det = self.determinant();
+        if det == 0.0 || !det.is_finite() {
+            return None;
+        }
+        let xx = self.yy / det;
+        let xy = -self.xy / det;
+        let yx = -self.yx / det;
+        let yy = self.xx / det;
+        Some(Transform {
+            xx,
+            xy,
+            yx,
+            yy,
+            dx: -(xx * self.dx + yx * self.dy),
+            dy: -(xy * self.dx + yy * self.dy),
+        })
+    }
+
+    /// Applies the transformation to the point `(x, y)`.
+    pub fn apply(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            self.xx * x + self.yx * y + self.dx,
+            self.xy * x + self.yy * y + self.dy,
+        )
+    }
+
+    /// Decomposes the transformation into translation, rotation, scale and skew.
+    pub fn decompose(&self) -> Decomposed {
+        let (mut a, mut b, c, d) = (self.xx, self.xy, self.yx, self.yy);
+        let sign = if a < 0.0 { -1.0 } else { 1.0 };
+        a *= sign;
+        b *= sign;
+        let delta = a * d - b * c;
+
+        let mut decomposed = Decomposed {
+            translate_x: self.dx,
+            translate_y: self.dy,
+            ..Default::default()
+        };
+        if a != 0.0 || b != 0.0 {
+            let r = a.hypot(b);
+            let angle = (a / r).acos();
+            decomposed.rotation = if b >= 0.0 { angle } else { -angle };
+            decomposed.scale_x = r;
+            decomposed.scale_y = delta / r;
+            decomposed.skew_x = ((a * c + b * d) / (r * r)).atan();
+        } else if c != 0.0 || d != 0.0 {
+            let s = c.hypot(d);
+            let angle = if d >= 0.0 {
+                (-c / s).acos()
+            } else {
+                -(c / s).acos()
+            };
+            decomposed.rotation = std::f64::consts::FRAC_PI_2 - angle;
+            decomposed.scale_x = delta / s;
+            decomposed.scale_y = s;
+            decomposed.skew_y = ((a * c + b * d) / (s * s)).atan();
+        }
+
+        decomposed.rotation = decomposed.rotation.to_degrees();
+        decomposed.scale_x *= sign;
+        decomposed.skew_x = decomposed.skew_x.to_degrees() * sign;
+        decomposed.skew_y = decomposed.skew_y.to_degrees();
+        decomposed
+    }
+}
+
+impl Decomposed {
+    /// Recombines the parts into a [`Transform`].
+    pub fn to_transform(&self) -> Transform {
+        Transform::translate(self.translate_x, self.translate_y)
+            .compose(&Transform::rotate(self.rotation))
+            .compose(&Transform::scale(self.scale_x, self.scale_y))
+            .compose(&Transform::skew(self.skew_x, self.skew_y))
+    }
+}
+
+impl From<&Component> for Transform {
+    /// Builds the component's transform, applying spec defaults for omitted attributes.
+    fn from(component: &Component) -> Self {
+        Self::new(
+            component.x_scale_or_default(),
+            component.xy_scale_or_default(),
+            component.yx_scale_or_default(),
+            component.y_scale_or_default(),
+            component.x_offset_or_default(),
+            component.y_offset_or_default(),
+        )
+    }
+}
+
+impl From<&Image> for Transform {
+    /// Builds the image's transform, applying spec defaults for omitted attributes.
+    fn from(image: &Image) -> Self {
+        Self::new(
+            image.x_scale_or_default(),
+            image.xy_scale_or_default(),
+            image.yx_scale_or_default(),
+            image.y_scale_or_default(),
+            image.x_offset_or_default(),
+            image.y_offset_or_default(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: &Transform, b: &Transform) {
+        let (a, b) = (a.to_tuple(), b.to_tuple());
+        let pairs = [
+            (a.0, b.0),
+            (a.1, b.1),
+            (a.2, b.2),
+            (a.3, b.3),
+            (a.4, b.4),
+            (a.5, b.5),
+        ];
+        for (x, y) in pairs {
+            assert!((x - y).abs() < 1e-9, "{:?} != {:?}", a, b);
+        }
+    }
+
+    #[test]
+    fn test_apply() {
+        let t = Transform::new(2.0, 0.5, 0.25, 3.0, 10.0, 20.0);
+        assert_eq!(t.apply(1.0, 2.0), (2.0 + 0.5 + 10.0, 0.5 + 6.0 + 20.0));
+        assert_eq!(Transform::IDENTITY.apply(5.0, -3.0), (5.0, -3.0));
+    }
+
+    #[test]
+    fn test_compose_order() {
+        let scale = Transform::scale(2.0, 2.0);
+        let translate = Transform::translate(10.0, 0.0);
+
+        // Scale first, then translate.
+        assert_eq!(translate.compose(&scale).apply(1.0, 1.0), (12.0, 2.0));
+        assert_eq!(scale.then(&translate).apply(1.0, 1.0), (12.0, 2.0));
+        // Translate first, then scale.
+        assert_eq!(scale.compose(&translate).apply(1.0, 1.0), (22.0, 2.0));
+    }
+
+    #[test]
+    fn test_invert() {
+        let t = Transform::new(2.0, 0.5, 0.25, 3.0, 10.0, 20.0);
+        let inv = t.invert().unwrap();
+        assert_close(&t.compose(&inv), &Transform::IDENTITY);
+
+        assert!(Transform::scale(0.0, 1.0).invert().is_none());
+    }
+
+    #[test]
+    fn test_decompose_round_trip() {
+        let transforms = [
+            Transform::IDENTITY,
+            Transform::new(2.0, 0.5, 0.25, 3.0, 10.0, 20.0),
+            Transform::rotate(30.0).compose(&Transform::scale(-1.0, 2.0)),
+            Transform::skew(15.0, 0.0).then(&Transform::translate(5.0, 5.0)),
+            Transform::new(0.0, 0.0, 1.0, 1.0, 0.0, 0.0),
+        ];
+        for t in transforms {
+            assert_close(&t.decompose().to_transform(), &t);
+        }
+    }
+
+    #[test]
+    fn test_decompose_parts() {
+        let d = Transform::rotate(90.0).decompose();
+        assert!((d.rotation - 90.0).abs() < 1e-9);
+        assert!((d.scale_x - 1.0).abs() < 1e-9);
+
+        let d = Transform::scale(2.0, 3.0)
+            .then(&Transform::translate(4.0, 5.0))
+            .decompose();
+        assert_eq!((d.scale_x, d.scale_y), (2.0, 3.0));
+        assert_eq!((d.translate_x, d.translate_y), (4.0, 5.0));
+        assert_eq!((d.rotation, d.skew_x, d.skew_y), (0.0, 0.0, 0.0));
+    }
+
+    #[test]
+    fn test_from_component_applies_defaults() {
+        let component = Component {
+            xy_scale: Some(0.5),
+            x_offset: Some(100.0),
+            ..Default::default()
+        };
+        let t = Transform::from(&component);
+        assert_eq!(t.to_tuple(), (1.0, 0.5, 0.0, 1.0, 100.0, 0.0));
+
+        let t = Transform::from(&Image::default());
+        assert!(t.is_identity());
+    }
+}
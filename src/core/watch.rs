@@ -0,0 +1,186 @@
+//! File system watching for fonts read through a [`crate::provider::FileProvider`].
+//!
+//! Requires the `watch` feature.
+
+use std::path::Path;
+use std::rc::Rc;
+use std::sync::mpsc::{Receiver, channel};
+use std::time::{Duration, Instant};
+
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher as _};
+
+use crate::error::{Error, ErrorKind, Result};
+use crate::font::Font;
+use crate::paths::UfoRelativePath;
+
+/// Watches a font's `.glif` files, `contents.plist` and `lib.plist` for changes.
+///
+/// Change notifications arrive on a background thread and are queued; [`Watcher::poll`] and
+/// [`Watcher::wait`] apply them by calling [`Font::refresh`], which discards the cached data of
+/// the affected glyphs, or reloads the font if `contents.plist` or `lib.plist` changed.
+///
+/// # Example
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// use ufo_gleaner::font::Font;
+/// use ufo_gleaner::provider::FileProvider;
+/// use ufo_gleaner::watch::Watcher;
+///
+/// let font = Font::new(FileProvider::new("MyFont.ufo")).unwrap();
+/// let watcher = Watcher::new(font.clone()).unwrap();
+/// loop {
+///     let changed = watcher.wait(Duration::from_secs(1)).unwrap();
+///     if !changed.is_empty() {
+///         println!("reloaded {:?}", changed);
+///     }
+/// }
+/// ```
+pub struct Watcher {
+    font: Rc<Font>,
+    events: Receiver<notify::Result<Event>>,
+    _watcher: RecommendedWatcher,
+}
+
+impl Watcher {
+    /// Starts watching the UFO directory at the root of the font's provider.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the provider's root is not a UFO directory that can be watched.
+    pub fn new(font: Rc<Font>) -> Result<Self> {
+        let root = font.provider().root().to_path_buf();
+        let (sender, events) = channel();
+        let mut watcher =
+            notify::recommended_watcher(sender).map_err(|err| watch_error(err, &root))?;
+        for path in [
+            root.clone(),
+            root.join(UfoRelativePath::Glyphs.to_pathbuf()),
+        ] {
+            watcher
+                .watch(&path, RecursiveMode::NonRecursive)
+                .map_err(|err| watch_error(err, &path))?;
+        }
+        Ok(Self {
+            font,
+            events,
+            _watcher: watcher,
+        })
+    }
+
+    /// Returns the watched font.
+    pub fn font(&self) -> &Rc<Font> {
+        &self.font
+    }
+
+    /// Applies all queued changes without blocking, and returns the names of the glyphs whose
+    /// cached data was discarded.
+    pub fn poll(&self) -> Result<Vec<String>> {
+        let mut relevant = false;
+        while let Ok(event) = self.events.try_recv() {
+            relevant |= is_relevant(&event);
+        }
+        match relevant {
+            true => self.font.refresh(),
+            false => Ok(Vec::new()),
+        }
+    }
+
+    /// Waits up to `timeout` for a relevant change, then applies all queued changes like
+    /// [`Watcher::poll`].
+    pub fn wait(&self, timeout: Duration) -> Result<Vec<String>> {
+        let deadline = Instant::now() + timeout;
+        let mut relevant = false;
+        while !relevant {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.events.recv_timeout(remaining) {
+                Ok(event) => relevant = is_relevant(&event),
+                Err(_) => break,
+            }
+        }
+        while let Ok(event) = self.events.try_recv() {
+            relevant |= is_relevant(&event);
+        }
+        match relevant {
+            true => self.font.refresh(),
+            false => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Returns `true` if the event concerns a `.glif` file, `contents.plist` or `lib.plist`.
+fn is_relevant(event: &notify::Result<Event>) -> bool {
+    let Ok(event) = event else {
+        return true;
+    };
+    let contents = UfoRelativePath::Contents.to_pathbuf();
+    let lib = UfoRelativePath::Lib.to_pathbuf();
+    event.paths.iter().any(|path| {
+        path.extension().is_some_and(|ext| ext == "glif")
+            || path.file_name() == contents.file_name()
+            || path.file_name() == lib.file_name()
+    })
+}
+
+fn watch_error(err: notify::Error, path: &Path) -> Error {
+    Error::new(ErrorKind::Io)
+        .with_context(|| "cannot watch directory")
+        .with_path(path.to_string_lossy())
+        .with_cause(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    use tempfile::tempdir;
+
+    use crate::provider::{FileProvider, MemoryProvider, WritableProvider};
+
+    #[test]
+    fn test_watcher_invalidates_changed_glyphs() {
+        let dir = tempdir().unwrap();
+        let glyphs = dir.path().join("glyphs");
+        fs::create_dir(&glyphs).unwrap();
+        fs::write(
+            glyphs.join("contents.plist"),
+            br#"<plist version="1.0"><dict><key>A</key><string>A_.glif</string></dict></plist>"#,
+        )
+        .unwrap();
+        fs::write(
+            glyphs.join("A_.glif"),
+            br#"<glyph name="A" format="2"><advance width="500"/></glyph>"#,
+        )
+        .unwrap();
+
+        let font = Font::new(FileProvider::new(dir.path())).unwrap();
+        let glyph = font.glyph("A").unwrap();
+        glyph.data().unwrap();
+        let watcher = Watcher::new(font.clone()).unwrap();
+        assert!(watcher.poll().unwrap().is_empty());
+
+        fs::write(
+            glyphs.join("A_.glif"),
+            br#"<glyph name="A" format="2"><advance width="600"/></glyph>"#,
+        )
+        .unwrap();
+        assert_eq!(watcher.wait(Duration::from_secs(5)).unwrap(), ["A"]);
+        assert_eq!(glyph.advance().unwrap().unwrap().width, Some(600.0));
+    }
+
+    #[test]
+    fn test_watcher_requires_directory() {
+        let provider = MemoryProvider::new();
+        provider
+            .write(
+                &UfoRelativePath::Contents.to_pathbuf(),
+                br#"<plist version="1.0"><dict/></plist>"#,
+            )
+            .unwrap();
+        let font = Font::new(provider).unwrap();
+        assert!(Watcher::new(font).is_err());
+    }
+}
@@ -43,6 +43,11 @@ impl Provider for MockProvider {
                     .with_context(|| format!("file not found: {}", rel_path.display()))
             })
     }
+    fn exists(&self, rel_path: &Path) -> bool {
+        self.files
+            .borrow()
+            .contains_key(&rel_path.to_string_lossy().to_string())
+    }
 }
 
 #[cfg(test)]
@@ -64,5 +69,8 @@ mod tests {
 
         let err = provider.read(Path::new("missing.txt")).unwrap_err();
         assert_eq!(err.kind(), &crate::error::ErrorKind::Io);
+
+        assert!(provider.exists(Path::new("foo.txt")));
+        assert!(!provider.exists(Path::new("missing.txt")));
     }
 }